@@ -0,0 +1,131 @@
+-- 一覧は作成した順 (`seq` の昇順) に返す
+
+CREATE TABLE groups (
+    seq BIGSERIAL PRIMARY KEY,
+    id TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL,
+    title TEXT NOT NULL,
+    currency TEXT NOT NULL,
+    rounding TEXT NOT NULL
+);
+
+CREATE TABLE group_participants (
+    group_id TEXT NOT NULL,
+    position BIGINT NOT NULL,
+    user_id TEXT NOT NULL,
+    PRIMARY KEY (group_id, position)
+);
+
+CREATE INDEX group_participants_user_id ON group_participants (user_id);
+
+CREATE TABLE group_guests (
+    group_id TEXT NOT NULL,
+    position BIGINT NOT NULL,
+    user_id TEXT NOT NULL,
+    PRIMARY KEY (group_id, position)
+);
+
+CREATE TABLE group_rates (
+    group_id TEXT NOT NULL,
+    position BIGINT NOT NULL,
+    currency TEXT NOT NULL,
+    rate BIGINT NOT NULL,
+    PRIMARY KEY (group_id, position)
+);
+
+CREATE TABLE group_roles (
+    group_id TEXT NOT NULL,
+    position BIGINT NOT NULL,
+    user_id TEXT NOT NULL,
+    role TEXT NOT NULL,
+    PRIMARY KEY (group_id, position)
+);
+
+CREATE TABLE invites (
+    seq BIGSERIAL PRIMARY KEY,
+    token TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL,
+    group_id TEXT NOT NULL,
+    created_by TEXT NOT NULL
+);
+
+CREATE INDEX invites_group_id ON invites (group_id);
+
+CREATE TABLE payments (
+    seq BIGSERIAL PRIMARY KEY,
+    id TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL,
+    occurred_at TEXT NOT NULL,
+    title TEXT NOT NULL,
+    amount BIGINT NOT NULL,
+    currency TEXT NOT NULL,
+    adjustments TEXT NOT NULL,
+    rate BIGINT NOT NULL,
+    group_id TEXT NOT NULL,
+    split TEXT NOT NULL,
+    category TEXT NOT NULL
+);
+
+CREATE INDEX payments_group_id_category ON payments (group_id, category);
+
+CREATE TABLE payment_payers (
+    payment_id TEXT NOT NULL,
+    position BIGINT NOT NULL,
+    user_id TEXT NOT NULL,
+    amount BIGINT NOT NULL,
+    currency TEXT NOT NULL,
+    PRIMARY KEY (payment_id, position)
+);
+
+-- `split` から求めた債務者 (誰が関わる支払いかを引くため)
+CREATE TABLE payment_debtors (
+    payment_id TEXT NOT NULL,
+    position BIGINT NOT NULL,
+    user_id TEXT NOT NULL,
+    PRIMARY KEY (payment_id, position)
+);
+
+CREATE INDEX payment_debtors_user_id ON payment_debtors (user_id);
+
+CREATE TABLE payment_tags (
+    payment_id TEXT NOT NULL,
+    position BIGINT NOT NULL,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (payment_id, position)
+);
+
+CREATE INDEX payment_tags_tag ON payment_tags (tag);
+
+CREATE TABLE recurring_payments (
+    seq BIGSERIAL PRIMARY KEY,
+    id TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL,
+    group_id TEXT NOT NULL,
+    schedule TEXT NOT NULL,
+    starts_at TEXT NOT NULL,
+    ends_on TEXT,
+    last_occurrence TEXT,
+    template TEXT NOT NULL
+);
+
+CREATE INDEX recurring_payments_group_id ON recurring_payments (group_id);
+
+CREATE TABLE settlements (
+    seq BIGSERIAL PRIMARY KEY,
+    id TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL,
+    group_id TEXT NOT NULL,
+    from_user TEXT NOT NULL,
+    to_user TEXT NOT NULL,
+    amount BIGINT NOT NULL,
+    currency TEXT NOT NULL
+);
+
+CREATE INDEX settlements_group_id ON settlements (group_id);
+
+CREATE TABLE users (
+    seq BIGSERIAL PRIMARY KEY,
+    id TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL
+);
@@ -0,0 +1,3 @@
+-- 記録が止まっている繰り返す支払いの理由
+
+ALTER TABLE recurring_payments ADD COLUMN stalled_reason TEXT;
@@ -1,21 +1,29 @@
 use crate::{
     controllers::{graphiql, graphql, Mutation, Query},
     entities::Validator,
-    repositories::{Mongo, MongoConfig, MongoError},
+    repositories::{
+        ExchangeRateProvider, ExchangeRatesError, InMemory, Mongo, MongoConfig, MongoError,
+        Repository, Sql, SqlConfig, SqlError, StaticExchangeRates, SystemClock,
+    },
+    scheduler::Scheduler,
     usecases::UseCase,
 };
 use async_graphql::{EmptySubscription, Schema};
 use axum::{routing::get, Router};
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use shaku::{module, HasComponent};
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    time::Duration,
+};
 use thiserror::Error;
 use tokio::net::TcpListener;
 use url::Url;
 
 module! {
     pub Module {
-        components = [Mongo],
+        components = [Mongo, StaticExchangeRates, SystemClock],
         providers = [],
     }
 }
@@ -31,11 +39,38 @@ pub struct Args {
     #[arg(long, env)]
     pub auth0_audience: String,
 
+    /// データの保存先
+    #[arg(long, env, value_enum, default_value_t = Storage::Mongo)]
+    pub storage: Storage,
+
+    /// `--storage mongo` のときに必要
+    #[arg(long, env)]
+    pub mongo_uri: Option<String>,
+
+    /// `--storage mongo` のときに必要
+    #[arg(long, env)]
+    pub mongo_db: Option<String>,
+
+    /// `--storage sql` のときに必要 (`sqlite://warikan.db?mode=rwc`, `postgres://...`)
     #[arg(long, env)]
-    pub mongo_uri: String,
+    pub database_url: Option<String>,
 
+    /// 為替レートの JSON/CSV ファイル (省略時は同じ通貨どうしのみ換算できる)
     #[arg(long, env)]
-    pub mongo_db: String,
+    pub exchange_rates: Option<PathBuf>,
+
+    /// 繰り返す支払いを記録する間隔 (秒、1 以上)
+    #[arg(long, env, default_value_t = 60, value_parser = clap::value_parser!(u64).range(1..))]
+    pub scheduler_interval: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Storage {
+    Mongo,
+    /// SQLite か PostgreSQL (`--database-url` で選ぶ)
+    Sql,
+    /// プロセスのメモリ上 (終了すると消える)
+    Memory,
 }
 
 #[derive(Debug, Error)]
@@ -48,6 +83,15 @@ pub enum Error {
 
     #[error("mongo")]
     Mongo(#[from] MongoError),
+
+    #[error("sql")]
+    Sql(#[from] SqlError),
+
+    #[error("exchange rates")]
+    ExchangeRates(#[from] ExchangeRatesError),
+
+    #[error("--{0} is required for the storage")]
+    MissingArgument(&'static str),
 }
 
 #[derive(Clone)]
@@ -70,24 +114,56 @@ impl App {
             port,
             auth0_issuer,
             auth0_audience,
+            storage,
             mongo_uri,
             mongo_db,
+            database_url,
+            exchange_rates,
+            scheduler_interval,
         } = self.args;
 
-        // Mongo
-        let mongo = Mongo::new(MongoConfig {
-            uri: &mongo_uri,
-            database: &mongo_db,
-        })
-        .await?;
+        // Repository
+        let repository: Box<dyn Repository> = match storage {
+            Storage::Mongo => {
+                let uri = mongo_uri.ok_or(Error::MissingArgument("mongo-uri"))?;
+                let database = mongo_db.ok_or(Error::MissingArgument("mongo-db"))?;
+                Box::new(
+                    Mongo::new(MongoConfig {
+                        uri: &uri,
+                        database: &database,
+                    })
+                    .await?,
+                )
+            }
+            Storage::Sql => {
+                let url = database_url.ok_or(Error::MissingArgument("database-url"))?;
+                Box::new(Sql::new(SqlConfig { url: &url }).await?)
+            }
+            Storage::Memory => Box::new(InMemory::default()),
+        };
+
+        // ExchangeRates
+        let exchange_rates = match exchange_rates {
+            Some(path) => StaticExchangeRates::load(path)?,
+            None => StaticExchangeRates::default(),
+        };
 
         // Module
         let module = Module::builder()
-            .with_component_override(Box::new(mongo))
+            .with_component_override::<dyn Repository>(repository)
+            .with_component_override::<dyn ExchangeRateProvider>(Box::new(exchange_rates))
             .build();
 
         // UseCase
-        let usecase = UseCase::new(module.resolve());
+        let usecase = UseCase::new(module.resolve(), module.resolve());
+
+        // Scheduler
+        let scheduler = Scheduler::new(
+            usecase.clone(),
+            module.resolve(),
+            Duration::from_secs(scheduler_interval),
+        );
+        tokio::spawn(scheduler.run());
 
         // GraphQL
         let schema = Schema::build(Query::default(), Mutation::default(), EmptySubscription)
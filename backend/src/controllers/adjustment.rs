@@ -0,0 +1,78 @@
+use crate::{
+    controllers::MoneyInput,
+    entities::{Adjustment, AdjustmentKind, AdjustmentValue, AppliedAdjustment, Breakdown, Money},
+};
+use async_graphql::{InputObject, Object, OneofObject};
+
+#[Object]
+impl Adjustment {
+    async fn kind(&self) -> AdjustmentKind {
+        self.kind
+    }
+
+    /// 小計に対する割合 (1/100 % 単位)。固定額なら `null`
+    async fn basis_points(&self) -> Option<u32> {
+        match &self.value {
+            AdjustmentValue::Percentage { basis_points } => Some(*basis_points),
+            AdjustmentValue::Fixed { .. } => None,
+        }
+    }
+
+    /// 固定額。割合なら `null`
+    async fn fixed(&self) -> Option<Money> {
+        match &self.value {
+            AdjustmentValue::Percentage { .. } => None,
+            AdjustmentValue::Fixed { amount } => Some(amount.clone()),
+        }
+    }
+}
+
+#[Object]
+impl AppliedAdjustment {
+    async fn adjustment(&self) -> Adjustment {
+        self.adjustment.clone()
+    }
+
+    /// 小計に対して計算した額 (割引は負)
+    async fn amount(&self) -> Money {
+        self.amount.clone()
+    }
+}
+
+#[Object]
+impl Breakdown {
+    async fn subtotal(&self) -> Money {
+        self.subtotal.clone()
+    }
+
+    async fn adjustments(&self) -> Vec<AppliedAdjustment> {
+        self.adjustments.clone()
+    }
+
+    async fn total(&self) -> Money {
+        self.total.clone()
+    }
+}
+
+#[derive(InputObject)]
+pub struct AdjustmentInput {
+    pub kind: AdjustmentKind,
+    pub value: AdjustmentValueInput,
+}
+
+#[derive(OneofObject)]
+pub enum AdjustmentValueInput {
+    BasisPoints(u32),
+    Fixed(MoneyInput),
+}
+
+impl From<AdjustmentInput> for Adjustment {
+    fn from(value: AdjustmentInput) -> Self {
+        match value.value {
+            AdjustmentValueInput::BasisPoints(basis_points) => {
+                Adjustment::percentage(value.kind, basis_points)
+            }
+            AdjustmentValueInput::Fixed(amount) => Adjustment::fixed(value.kind, amount.into()),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{
+    entities::{AuthState, Balance, Money, User},
+    usecases::UseCase,
+};
+use async_graphql::{Context, Object};
+
+#[Object]
+impl Balance {
+    async fn user(&self, ctx: &Context<'_>) -> async_graphql::Result<User> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let user = usecase.get_user(&self.user, auth).await?;
+        Ok(user)
+    }
+
+    async fn paid(&self) -> Money {
+        self.paid.clone()
+    }
+
+    async fn owed(&self) -> Money {
+        self.owed.clone()
+    }
+
+    async fn sent(&self) -> Money {
+        self.sent.clone()
+    }
+
+    async fn received(&self) -> Money {
+        self.received.clone()
+    }
+
+    async fn net(&self) -> Money {
+        self.net.clone()
+    }
+}
@@ -1,5 +1,9 @@
 use crate::{
-    entities::{AuthState, Group, GroupID, Payment, User},
+    entities::{
+        AuthState, Balance, Category, CategoryTotal, Currency, ExchangeRate, Group, GroupID,
+        Invite, ParticipantRole, Payment, PaymentFilter, RateOverride, RecurringPayment, Role,
+        RoundingPolicy, Settlement, Transfer, User, UserID,
+    },
     usecases::UseCase,
 };
 use async_graphql::{Context, Object};
@@ -19,16 +23,123 @@ impl Group {
         self.title.clone()
     }
 
+    async fn currency(&self) -> Currency {
+        self.currency.clone()
+    }
+
+    async fn rates(&self) -> Vec<RateOverride> {
+        self.rates.clone()
+    }
+
+    async fn rounding(&self) -> RoundingPolicy {
+        self.rounding
+    }
+
     async fn participants(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<User>> {
         let usecase = ctx.data::<UseCase>()?;
         let auth = ctx.data::<AuthState>()?;
         Ok(usecase.get_users(&self.participants, auth).await?)
     }
 
-    async fn payments(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Payment>> {
+    async fn invites(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Invite>> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase.get_invites_by_group(&self.id, auth).await?)
+    }
+
+    async fn guests(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<User>> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase.get_users(&self.guests, auth).await?)
+    }
+
+    async fn roles(&self) -> Vec<ParticipantRole> {
+        self.participants
+            .iter()
+            .filter_map(|user| {
+                self.role(user).map(|role| ParticipantRole {
+                    user: user.clone(),
+                    role,
+                })
+            })
+            .collect()
+    }
+
+    async fn payments(
+        &self,
+        ctx: &Context<'_>,
+        category: Option<Category>,
+        tag: Option<String>,
+    ) -> async_graphql::Result<Vec<Payment>> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let filter = PaymentFilter { category, tag };
+        Ok(usecase
+            .get_payments_by_group(&self.id, &filter, auth)
+            .await?)
+    }
+
+    async fn category_totals(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<CategoryTotal>> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase.get_category_totals(&self.id, auth).await?)
+    }
+
+    async fn recurring_payments(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<RecurringPayment>> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase
+            .get_recurring_payments_by_group(&self.id, auth)
+            .await?)
+    }
+
+    async fn settlements(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Settlement>> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase.get_settlements_by_group(&self.id, auth).await?)
+    }
+
+    async fn balances(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Balance>> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase.get_balances(&self.id, auth).await?)
+    }
+
+    async fn settlement_plan(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Transfer>> {
         let usecase = ctx.data::<UseCase>()?;
         let auth = ctx.data::<AuthState>()?;
-        Ok(usecase.get_payments_by_group(&self.id, auth).await?)
+        Ok(usecase.get_settlement_plan(&self.id, auth).await?)
+    }
+}
+
+#[Object]
+impl ParticipantRole {
+    async fn user(&self, ctx: &Context<'_>) -> async_graphql::Result<User> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let user = usecase.get_user(&self.user, auth).await?;
+        Ok(user)
+    }
+
+    async fn role(&self) -> Role {
+        self.role
+    }
+}
+
+#[Object]
+impl RateOverride {
+    async fn currency(&self) -> Currency {
+        self.currency.clone()
+    }
+
+    async fn rate(&self) -> ExchangeRate {
+        self.rate
     }
 }
 
@@ -59,10 +170,15 @@ pub struct GroupMutation;
 
 #[Object]
 impl GroupMutation {
-    async fn create_group(&self, ctx: &Context<'_>, title: String) -> async_graphql::Result<Group> {
+    async fn create_group(
+        &self,
+        ctx: &Context<'_>,
+        title: String,
+        currency: Option<Currency>,
+    ) -> async_graphql::Result<Group> {
         let usecase = ctx.data::<UseCase>()?;
         let auth = ctx.data::<AuthState>()?;
-        Ok(usecase.create_group(title, auth).await?)
+        Ok(usecase.create_group(title, currency, auth).await?)
     }
 
     async fn delete_group(&self, ctx: &Context<'_>, id: GroupID) -> async_graphql::Result<GroupID> {
@@ -70,4 +186,93 @@ impl GroupMutation {
         let auth = ctx.data::<AuthState>()?;
         Ok(usecase.delete_group(&id, auth).await?)
     }
+
+    async fn set_rounding_policy(
+        &self,
+        ctx: &Context<'_>,
+        id: GroupID,
+        rounding: RoundingPolicy,
+    ) -> async_graphql::Result<Group> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase.set_rounding_policy(&id, rounding, auth).await?)
+    }
+
+    async fn leave_group(&self, ctx: &Context<'_>, id: GroupID) -> async_graphql::Result<GroupID> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase.leave_group(&id, auth).await?)
+    }
+
+    /// 自分より低い権限の参加者かゲストを外す。清算が済んでいないときは、`force` を指定すると外す人の負担分を
+    /// 各支払いの残りの債務者に負担の比で振り分けてから外す (ほかに債務者がいない支払いは
+    /// 立て替えた人の負担になる)。立て替えた人や返済に関わっている人は `force` でも外せない
+    async fn remove_participant(
+        &self,
+        ctx: &Context<'_>,
+        id: GroupID,
+        user: UserID,
+        #[graphql(default = false)] force: bool,
+    ) -> async_graphql::Result<Group> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase.remove_participant(&id, &user, force, auth).await?)
+    }
+
+    async fn transfer_ownership(
+        &self,
+        ctx: &Context<'_>,
+        id: GroupID,
+        user: UserID,
+    ) -> async_graphql::Result<Group> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase.transfer_ownership(&id, &user, auth).await?)
+    }
+
+    async fn set_participant_role(
+        &self,
+        ctx: &Context<'_>,
+        id: GroupID,
+        user: UserID,
+        role: Role,
+    ) -> async_graphql::Result<Group> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase.set_participant_role(&id, &user, role, auth).await?)
+    }
+
+    async fn add_guest(
+        &self,
+        ctx: &Context<'_>,
+        id: GroupID,
+        name: String,
+    ) -> async_graphql::Result<User> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase.add_guest(&id, name, auth).await?)
+    }
+
+    async fn claim_guest(
+        &self,
+        ctx: &Context<'_>,
+        id: GroupID,
+        guest: UserID,
+    ) -> async_graphql::Result<Group> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase.claim_guest(&id, &guest, auth).await?)
+    }
+
+    async fn set_exchange_rate(
+        &self,
+        ctx: &Context<'_>,
+        id: GroupID,
+        currency: Currency,
+        rate: Option<ExchangeRate>,
+    ) -> async_graphql::Result<Group> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase.set_exchange_rate(&id, currency, rate, auth).await?)
+    }
 }
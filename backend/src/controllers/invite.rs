@@ -0,0 +1,71 @@
+use crate::{
+    entities::{AuthState, Group, GroupID, Invite, InviteToken, User},
+    usecases::UseCase,
+};
+use async_graphql::{Context, Object};
+use chrono::{DateTime, Utc};
+
+#[Object]
+impl Invite {
+    async fn token(&self) -> InviteToken {
+        self.token.clone()
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    async fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    async fn group(&self, ctx: &Context<'_>) -> async_graphql::Result<Group> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let group = usecase.get_group(&self.group, auth).await?;
+        Ok(group)
+    }
+
+    async fn created_by(&self, ctx: &Context<'_>) -> async_graphql::Result<User> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let user = usecase.get_user(&self.created_by, auth).await?;
+        Ok(user)
+    }
+}
+
+#[derive(Default)]
+pub struct InviteMutation;
+
+#[Object]
+impl InviteMutation {
+    async fn create_invite(
+        &self,
+        ctx: &Context<'_>,
+        group: GroupID,
+    ) -> async_graphql::Result<Invite> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase.create_invite(&group, auth).await?)
+    }
+
+    async fn revoke_invite(
+        &self,
+        ctx: &Context<'_>,
+        token: InviteToken,
+    ) -> async_graphql::Result<InviteToken> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase.revoke_invite(&token, auth).await?)
+    }
+
+    async fn accept_invite(
+        &self,
+        ctx: &Context<'_>,
+        token: InviteToken,
+    ) -> async_graphql::Result<Group> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase.accept_invite(&token, auth).await?)
+    }
+}
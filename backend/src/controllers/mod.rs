@@ -1,16 +1,34 @@
+mod adjustment;
+mod balance;
 mod group;
+mod invite;
+mod money;
 mod payment;
+mod recurring;
+mod settlement;
+mod split;
+mod transfer;
 mod user;
 
+pub use adjustment::*;
+pub use balance::*;
 pub use group::*;
+pub use invite::*;
+pub use money::*;
 pub use payment::*;
+pub use recurring::*;
+pub use settlement::*;
+pub use split::*;
+pub use transfer::*;
 pub use user::*;
 
 use crate::{
     app,
     entities::{AuthState, Claims},
+    repositories::RepositoryError,
+    usecases::UseCaseError,
 };
-use async_graphql::{http::GraphiQLSource, MergedObject};
+use async_graphql::{http::GraphiQLSource, MergedObject, ServerError};
 use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
 use async_trait::async_trait;
 use axum::{
@@ -29,7 +47,14 @@ use axum_extra::{
 pub struct Query(GroupQuery, PaymentQuery, UserQuery);
 
 #[derive(Default, MergedObject)]
-pub struct Mutation(GroupMutation, PaymentMutation, UserMutation);
+pub struct Mutation(
+    GroupMutation,
+    InviteMutation,
+    PaymentMutation,
+    RecurringPaymentMutation,
+    SettlementMutation,
+    UserMutation,
+);
 
 pub async fn graphql(
     State(state): State<app::State>,
@@ -38,7 +63,31 @@ pub async fn graphql(
 ) -> GraphQLResponse {
     dbg!(&auth);
     let req = req.into_inner().data(auth);
-    state.schema.execute(req).await.into()
+    let mut res = state.schema.execute(req).await;
+    res.errors.iter_mut().for_each(extend_error);
+    res.into()
+}
+
+/// ユースケースのエラーに、種類ごとの `extensions.code` を付ける。
+/// `Repository` のエラーは `UseCaseError` に読み替えて返す
+fn extend_error(error: &mut ServerError) {
+    let Some(source) = error.source::<Box<dyn std::error::Error + Send + Sync>>() else {
+        return;
+    };
+    let (message, code) = if let Some(source) = source.downcast_ref::<UseCaseError>() {
+        (source.to_string(), source.code())
+    } else if let Some(source) = source.downcast_ref::<RepositoryError>() {
+        let source = UseCaseError::from(source);
+        (source.to_string(), source.code())
+    } else {
+        return;
+    };
+
+    error.message = message;
+    error
+        .extensions
+        .get_or_insert_with(Default::default)
+        .set("code", code);
 }
 
 pub async fn graphiql() -> impl IntoResponse {
@@ -74,3 +123,46 @@ async fn validate(
     let claims = state.validator.validate(token)?;
     Ok(claims)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_graphql::{Pos, Value};
+
+    /// リゾルバが `?` で返すのと同じ形のエラー
+    fn server_error(error: impl std::error::Error + Send + Sync + 'static) -> ServerError {
+        let error: Box<dyn std::error::Error + Send + Sync> = Box::new(error);
+        async_graphql::Error::from(error).into_server_error(Pos::default())
+    }
+
+    fn code(error: &ServerError) -> Option<&Value> {
+        error.extensions.as_ref()?.get("code")
+    }
+
+    #[test]
+    fn extend_error_usecase() {
+        let mut error = server_error(UseCaseError::LastParticipant);
+        extend_error(&mut error);
+
+        assert_eq!(error.message, UseCaseError::LastParticipant.to_string());
+        assert_eq!(code(&error), Some(&Value::from("FAILED_PRECONDITION")));
+    }
+
+    #[test]
+    fn extend_error_repository() {
+        let mut error = server_error(RepositoryError::Backend("connection reset".into()));
+        extend_error(&mut error);
+
+        assert_eq!(error.message, "internal error");
+        assert_eq!(code(&error), Some(&Value::from("INTERNAL_SERVER_ERROR")));
+    }
+
+    #[test]
+    fn extend_error_other() {
+        let mut error = ServerError::new("syntax error", None);
+        extend_error(&mut error);
+
+        assert_eq!(error.message, "syntax error");
+        assert_eq!(code(&error), None);
+    }
+}
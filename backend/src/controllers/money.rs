@@ -0,0 +1,42 @@
+use crate::entities::{Currency, ExchangeRate, Money};
+use async_graphql::{
+    InputObject, InputValueError, InputValueResult, Object, Scalar, ScalarType, Value,
+};
+
+#[Object]
+impl Money {
+    async fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    async fn currency(&self) -> Currency {
+        self.currency.clone()
+    }
+}
+
+#[derive(InputObject)]
+pub struct MoneyInput {
+    pub amount: i64,
+    pub currency: Currency,
+}
+
+impl From<MoneyInput> for Money {
+    fn from(value: MoneyInput) -> Self {
+        Money::new(value.amount, value.currency)
+    }
+}
+
+/// `"150.25"` のような小数の文字列で表す為替レート
+#[Scalar]
+impl ScalarType for ExchangeRate {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match &value {
+            Value::String(s) => Ok(s.parse()?),
+            _ => Err(InputValueError::expected_type(value)),
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
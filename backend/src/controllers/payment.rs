@@ -1,9 +1,14 @@
 use crate::{
-    entities::{AuthState, Group, GroupID, Payment, PaymentID, User, UserID},
+    controllers::{AdjustmentInput, MoneyInput, PaymentItemInput, SplitInput},
+    entities::{
+        Adjustment, AuthState, Breakdown, Category, CategoryTotal, ExchangeRate, Group, GroupID,
+        Money, NewPayment, Payer, Payment, PaymentID, PaymentItemID, PaymentItemUpdate,
+        PaymentUpdate, Share, Split, User, UserID,
+    },
     usecases::UseCase,
 };
-use async_graphql::{Context, Object};
-use chrono::{DateTime, Utc};
+use async_graphql::{Context, InputObject, Object};
+use chrono::{DateTime, FixedOffset, Utc};
 
 #[Object]
 impl Payment {
@@ -11,14 +16,51 @@ impl Payment {
         self.id.clone()
     }
 
+    /// 記録した日時
     async fn created_at(&self) -> DateTime<Utc> {
         self.created_at
     }
 
+    /// 支払った日時
+    async fn occurred_at(&self) -> DateTime<FixedOffset> {
+        self.occurred_at
+    }
+
     async fn title(&self) -> String {
         self.title.clone()
     }
 
+    /// 調整前の小計
+    async fn amount(&self) -> Money {
+        self.amount.clone()
+    }
+
+    async fn adjustments(&self) -> Vec<Adjustment> {
+        self.adjustments.clone()
+    }
+
+    /// 小計・各調整の額・合計の内訳
+    async fn breakdown(&self) -> async_graphql::Result<Breakdown> {
+        Ok(self.breakdown()?)
+    }
+
+    /// 調整後の合計
+    async fn total(&self) -> async_graphql::Result<Money> {
+        Ok(self.total()?)
+    }
+
+    async fn rate(&self) -> ExchangeRate {
+        self.rate
+    }
+
+    /// 調整後の合計をグループの基準通貨に換算した金額
+    async fn base_amount(&self, ctx: &Context<'_>) -> async_graphql::Result<Money> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let group = usecase.get_group(&self.group, auth).await?;
+        Ok(self.rate.convert(&self.total()?, &group.currency)?)
+    }
+
     async fn group(&self, ctx: &Context<'_>) -> async_graphql::Result<Group> {
         let usecase = ctx.data::<UseCase>()?;
         let auth = ctx.data::<AuthState>()?;
@@ -26,19 +68,85 @@ impl Payment {
         Ok(group)
     }
 
-    async fn creditor(&self, ctx: &Context<'_>) -> async_graphql::Result<User> {
+    /// 支払者を移行できなかった古い記録では `null`
+    #[graphql(deprecation = "use `payers`")]
+    async fn creditor(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<User>> {
         let usecase = ctx.data::<UseCase>()?;
         let auth = ctx.data::<AuthState>()?;
-        let creditor = usecase.get_user(&self.creditor, auth).await?;
-        Ok(creditor)
+        let Some(creditor) = self.creditor() else {
+            return Ok(None);
+        };
+        let creditor = usecase.get_user(creditor, auth).await?;
+        Ok(Some(creditor))
+    }
+
+    async fn payers(&self) -> Vec<Payer> {
+        self.payers.clone()
     }
 
     async fn debtors(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<User>> {
         let usecase = ctx.data::<UseCase>()?;
         let auth = ctx.data::<AuthState>()?;
-        let debtors = usecase.get_users(&self.debtors, auth).await?;
+        let debtors = usecase.get_users(&self.split.debtors(), auth).await?;
         Ok(debtors)
     }
+
+    async fn split(&self) -> Split {
+        self.split.clone()
+    }
+
+    async fn category(&self) -> Category {
+        self.category
+    }
+
+    async fn tags(&self) -> Vec<String> {
+        self.tags.clone()
+    }
+
+    async fn shares(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Share>> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let shares = usecase.get_shares(self, auth).await?;
+        Ok(shares)
+    }
+}
+
+#[Object]
+impl Payer {
+    async fn user(&self, ctx: &Context<'_>) -> async_graphql::Result<User> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let user = usecase.get_user(&self.user, auth).await?;
+        Ok(user)
+    }
+
+    async fn amount(&self) -> Money {
+        self.amount.clone()
+    }
+}
+
+#[Object]
+impl CategoryTotal {
+    async fn category(&self) -> Category {
+        self.category
+    }
+
+    /// グループの基準通貨に換算した調整後の合計
+    async fn total(&self) -> Money {
+        self.total.clone()
+    }
+}
+
+#[derive(InputObject)]
+pub struct PayerInput {
+    pub user: UserID,
+    pub amount: MoneyInput,
+}
+
+impl From<PayerInput> for Payer {
+    fn from(value: PayerInput) -> Self {
+        Payer::new(value.user, value.amount.into())
+    }
 }
 
 #[derive(Default)]
@@ -67,17 +175,108 @@ impl PaymentMutation {
         &self,
         ctx: &Context<'_>,
         title: String,
+        occurred_at: Option<DateTime<FixedOffset>>,
+        amount: MoneyInput,
+        #[graphql(default)] adjustments: Vec<AdjustmentInput>,
+        rate: Option<ExchangeRate>,
         group: GroupID,
-        creditor: UserID,
-        debtors: Vec<UserID>,
+        payers: Vec<PayerInput>,
+        split: SplitInput,
+        #[graphql(default)] category: Category,
+        #[graphql(default)] tags: Vec<String>,
+    ) -> async_graphql::Result<Payment> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let payment = NewPayment {
+            title,
+            occurred_at,
+            amount: amount.into(),
+            adjustments: adjustments.into_iter().map(Into::into).collect(),
+            rate,
+            payers: payers.into_iter().map(Into::into).collect(),
+            split: split.into(),
+            category,
+            tags,
+        };
+        Ok(usecase.create_payment(group, payment, auth).await?)
+    }
+
+    async fn update_payment(
+        &self,
+        ctx: &Context<'_>,
+        id: PaymentID,
+        title: Option<String>,
+        occurred_at: Option<DateTime<FixedOffset>>,
+        amount: Option<MoneyInput>,
+        adjustments: Option<Vec<AdjustmentInput>>,
+        rate: Option<ExchangeRate>,
+        payers: Option<Vec<PayerInput>>,
+        split: Option<SplitInput>,
+        category: Option<Category>,
+        tags: Option<Vec<String>>,
+    ) -> async_graphql::Result<Payment> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let update = PaymentUpdate {
+            title,
+            occurred_at,
+            amount: amount.map(Into::into),
+            adjustments: adjustments
+                .map(|adjustments| adjustments.into_iter().map(Into::into).collect()),
+            rate,
+            payers: payers.map(|payers| payers.into_iter().map(Into::into).collect()),
+            split: split.map(Into::into),
+            category,
+            tags,
+        };
+        Ok(usecase.update_payment(&id, update, auth).await?)
+    }
+
+    async fn add_payment_item(
+        &self,
+        ctx: &Context<'_>,
+        id: PaymentID,
+        item: PaymentItemInput,
+    ) -> async_graphql::Result<Payment> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase.add_payment_item(&id, item.into(), auth).await?)
+    }
+
+    async fn update_payment_item(
+        &self,
+        ctx: &Context<'_>,
+        id: PaymentID,
+        item: PaymentItemID,
+        name: Option<String>,
+        price: Option<MoneyInput>,
+        quantity: Option<u32>,
+        consumers: Option<Vec<UserID>>,
     ) -> async_graphql::Result<Payment> {
         let usecase = ctx.data::<UseCase>()?;
         let auth = ctx.data::<AuthState>()?;
+        let update = PaymentItemUpdate {
+            name,
+            price: price.map(Into::into),
+            quantity,
+            consumers,
+        };
         Ok(usecase
-            .create_payment(title, group, creditor, debtors, auth)
+            .update_payment_item(&id, &item, update, auth)
             .await?)
     }
 
+    async fn remove_payment_item(
+        &self,
+        ctx: &Context<'_>,
+        id: PaymentID,
+        item: PaymentItemID,
+    ) -> async_graphql::Result<Payment> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase.remove_payment_item(&id, &item, auth).await?)
+    }
+
     async fn delete_payment(
         &self,
         ctx: &Context<'_>,
@@ -0,0 +1,229 @@
+use crate::{
+    controllers::{AdjustmentInput, MoneyInput, PayerInput, SplitInput},
+    entities::{
+        Adjustment, AuthState, Category, CustomSchedule, ExchangeRate, Group, GroupID, Money,
+        MonthlySchedule, NewPayment, Payer, RecurringPayment, RecurringPaymentID,
+        RecurringPaymentUpdate, Schedule, Split, Weekday, WeeklySchedule,
+    },
+    usecases::UseCase,
+};
+use async_graphql::{Context, InputObject, Object, OneofObject};
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+
+#[Object]
+impl RecurringPayment {
+    async fn id(&self) -> RecurringPaymentID {
+        self.id.clone()
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    async fn group(&self, ctx: &Context<'_>) -> async_graphql::Result<Group> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let group = usecase.get_group(&self.group, auth).await?;
+        Ok(group)
+    }
+
+    async fn schedule(&self) -> Schedule {
+        self.schedule.clone()
+    }
+
+    /// 最初の支払い日時
+    async fn starts_at(&self) -> DateTime<FixedOffset> {
+        self.starts_at
+    }
+
+    async fn ends_on(&self) -> Option<NaiveDate> {
+        self.ends_on
+    }
+
+    /// 最後に支払いを記録した日
+    async fn last_occurrence(&self) -> Option<NaiveDate> {
+        self.last_occurrence
+    }
+
+    /// 次に支払いを記録する日
+    async fn next_occurrence(&self) -> Option<NaiveDate> {
+        self.next_occurrence()
+    }
+
+    async fn template(&self) -> NewPayment {
+        self.template.clone()
+    }
+
+    /// 記録が止まっていればその理由。`updateRecurringPayment` でテンプレートを直すと `null` に戻る
+    async fn stalled_reason(&self) -> Option<String> {
+        self.stalled_reason.clone()
+    }
+}
+
+/// 繰り返し記録する支払いの内容
+#[Object(name = "PaymentTemplate")]
+impl NewPayment {
+    async fn title(&self) -> String {
+        self.title.clone()
+    }
+
+    async fn amount(&self) -> Money {
+        self.amount.clone()
+    }
+
+    async fn adjustments(&self) -> Vec<Adjustment> {
+        self.adjustments.clone()
+    }
+
+    /// `null` なら記録するたびにグループの設定か為替レートの取得元から決める
+    async fn rate(&self) -> Option<ExchangeRate> {
+        self.rate
+    }
+
+    async fn payers(&self) -> Vec<Payer> {
+        self.payers.clone()
+    }
+
+    async fn split(&self) -> Split {
+        self.split.clone()
+    }
+
+    async fn category(&self) -> Category {
+        self.category
+    }
+
+    async fn tags(&self) -> Vec<String> {
+        self.tags.clone()
+    }
+}
+
+#[Object]
+impl WeeklySchedule {
+    async fn weekday(&self) -> Weekday {
+        self.weekday
+    }
+}
+
+#[Object]
+impl MonthlySchedule {
+    /// その月にない日は月末
+    async fn day(&self) -> u32 {
+        self.day
+    }
+}
+
+#[Object]
+impl CustomSchedule {
+    /// `"日 月 曜日"` の規則
+    async fn rule(&self) -> String {
+        self.rule.clone()
+    }
+}
+
+#[derive(OneofObject)]
+pub enum ScheduleInput {
+    Weekly(Weekday),
+    Monthly(u32),
+    /// `"日 月 曜日"` の規則 (例: `"1,15 * *"`)
+    Custom(String),
+}
+
+impl From<ScheduleInput> for Schedule {
+    fn from(value: ScheduleInput) -> Self {
+        match value {
+            ScheduleInput::Weekly(weekday) => Schedule::Weekly(WeeklySchedule { weekday }),
+            ScheduleInput::Monthly(day) => Schedule::Monthly(MonthlySchedule { day }),
+            ScheduleInput::Custom(rule) => Schedule::Custom(CustomSchedule { rule }),
+        }
+    }
+}
+
+#[derive(InputObject)]
+pub struct PaymentTemplateInput {
+    pub title: String,
+    pub amount: MoneyInput,
+    #[graphql(default)]
+    pub adjustments: Vec<AdjustmentInput>,
+    pub rate: Option<ExchangeRate>,
+    pub payers: Vec<PayerInput>,
+    pub split: SplitInput,
+    #[graphql(default)]
+    pub category: Category,
+    #[graphql(default)]
+    pub tags: Vec<String>,
+}
+
+impl From<PaymentTemplateInput> for NewPayment {
+    fn from(value: PaymentTemplateInput) -> Self {
+        NewPayment {
+            title: value.title,
+            occurred_at: None,
+            amount: value.amount.into(),
+            adjustments: value.adjustments.into_iter().map(Into::into).collect(),
+            rate: value.rate,
+            payers: value.payers.into_iter().map(Into::into).collect(),
+            split: value.split.into(),
+            category: value.category,
+            tags: value.tags,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct RecurringPaymentMutation;
+
+#[Object]
+impl RecurringPaymentMutation {
+    /// `startsAt` は 31 日前までさかのぼれる
+    async fn create_recurring_payment(
+        &self,
+        ctx: &Context<'_>,
+        group: GroupID,
+        schedule: ScheduleInput,
+        starts_at: DateTime<FixedOffset>,
+        ends_on: Option<NaiveDate>,
+        template: PaymentTemplateInput,
+    ) -> async_graphql::Result<RecurringPayment> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase
+            .create_recurring_payment(
+                group,
+                schedule.into(),
+                starts_at,
+                ends_on,
+                template.into(),
+                auth,
+            )
+            .await?)
+    }
+
+    /// `startsAt` は変えられない。`endsOn` は省略すると変えない
+    async fn update_recurring_payment(
+        &self,
+        ctx: &Context<'_>,
+        id: RecurringPaymentID,
+        schedule: Option<ScheduleInput>,
+        ends_on: Option<NaiveDate>,
+        template: Option<PaymentTemplateInput>,
+    ) -> async_graphql::Result<RecurringPayment> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let update = RecurringPaymentUpdate {
+            schedule: schedule.map(Into::into),
+            ends_on,
+            template: template.map(Into::into),
+        };
+        Ok(usecase.update_recurring_payment(&id, update, auth).await?)
+    }
+
+    async fn delete_recurring_payment(
+        &self,
+        ctx: &Context<'_>,
+        id: RecurringPaymentID,
+    ) -> async_graphql::Result<RecurringPaymentID> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase.delete_recurring_payment(&id, auth).await?)
+    }
+}
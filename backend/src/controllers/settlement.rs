@@ -0,0 +1,74 @@
+use crate::{
+    controllers::MoneyInput,
+    entities::{AuthState, Group, GroupID, Money, Settlement, SettlementID, User, UserID},
+    usecases::UseCase,
+};
+use async_graphql::{Context, Object};
+use chrono::{DateTime, Utc};
+
+#[Object]
+impl Settlement {
+    async fn id(&self) -> SettlementID {
+        self.id.clone()
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    async fn group(&self, ctx: &Context<'_>) -> async_graphql::Result<Group> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let group = usecase.get_group(&self.group, auth).await?;
+        Ok(group)
+    }
+
+    async fn from(&self, ctx: &Context<'_>) -> async_graphql::Result<User> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let from = usecase.get_user(&self.from, auth).await?;
+        Ok(from)
+    }
+
+    async fn to(&self, ctx: &Context<'_>) -> async_graphql::Result<User> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let to = usecase.get_user(&self.to, auth).await?;
+        Ok(to)
+    }
+
+    async fn amount(&self) -> Money {
+        self.amount.clone()
+    }
+}
+
+#[derive(Default)]
+pub struct SettlementMutation;
+
+#[Object]
+impl SettlementMutation {
+    async fn record_settlement(
+        &self,
+        ctx: &Context<'_>,
+        group: GroupID,
+        from: UserID,
+        to: UserID,
+        amount: MoneyInput,
+    ) -> async_graphql::Result<Settlement> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase
+            .record_settlement(group, from, to, amount.into(), auth)
+            .await?)
+    }
+
+    async fn delete_settlement(
+        &self,
+        ctx: &Context<'_>,
+        id: SettlementID,
+    ) -> async_graphql::Result<SettlementID> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        Ok(usecase.delete_settlement(&id, auth).await?)
+    }
+}
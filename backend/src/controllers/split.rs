@@ -0,0 +1,232 @@
+use crate::{
+    controllers::MoneyInput,
+    entities::{
+        AuthState, EqualSplit, ExactDebtor, ExactSplit, ItemizedSplit, Money, PaymentItem,
+        PaymentItemID, PercentageDebtor, PercentagesSplit, Share, ShareDebtor, SharesSplit, Split,
+        User, UserID,
+    },
+    usecases::UseCase,
+};
+use async_graphql::{Context, InputObject, Object, OneofObject};
+
+#[Object]
+impl EqualSplit {
+    async fn debtors(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<User>> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let debtors = usecase.get_users(&self.debtors, auth).await?;
+        Ok(debtors)
+    }
+}
+
+#[Object]
+impl SharesSplit {
+    async fn debtors(&self) -> Vec<ShareDebtor> {
+        self.debtors.clone()
+    }
+}
+
+#[Object]
+impl ShareDebtor {
+    async fn user(&self, ctx: &Context<'_>) -> async_graphql::Result<User> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let user = usecase.get_user(&self.user, auth).await?;
+        Ok(user)
+    }
+
+    async fn shares(&self) -> u32 {
+        self.shares
+    }
+}
+
+#[Object]
+impl PercentagesSplit {
+    async fn debtors(&self) -> Vec<PercentageDebtor> {
+        self.debtors.clone()
+    }
+}
+
+#[Object]
+impl PercentageDebtor {
+    async fn user(&self, ctx: &Context<'_>) -> async_graphql::Result<User> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let user = usecase.get_user(&self.user, auth).await?;
+        Ok(user)
+    }
+
+    async fn percent(&self) -> u32 {
+        self.percent
+    }
+}
+
+#[Object]
+impl ExactSplit {
+    async fn debtors(&self) -> Vec<ExactDebtor> {
+        self.debtors.clone()
+    }
+}
+
+#[Object]
+impl ExactDebtor {
+    async fn user(&self, ctx: &Context<'_>) -> async_graphql::Result<User> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let user = usecase.get_user(&self.user, auth).await?;
+        Ok(user)
+    }
+
+    async fn amount(&self) -> Money {
+        self.amount.clone()
+    }
+}
+
+#[Object]
+impl ItemizedSplit {
+    async fn items(&self) -> Vec<PaymentItem> {
+        self.items.clone()
+    }
+
+    async fn tax(&self) -> Money {
+        self.tax.clone()
+    }
+
+    async fn tip(&self) -> Money {
+        self.tip.clone()
+    }
+}
+
+#[Object]
+impl PaymentItem {
+    async fn id(&self) -> PaymentItemID {
+        self.id.clone()
+    }
+
+    async fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    async fn price(&self) -> Money {
+        self.price.clone()
+    }
+
+    async fn quantity(&self) -> u32 {
+        self.quantity
+    }
+
+    async fn consumers(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<User>> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let consumers = usecase.get_users(&self.consumers, auth).await?;
+        Ok(consumers)
+    }
+}
+
+#[Object]
+impl Share {
+    async fn user(&self, ctx: &Context<'_>) -> async_graphql::Result<User> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let user = usecase.get_user(&self.user, auth).await?;
+        Ok(user)
+    }
+
+    async fn amount(&self) -> Money {
+        self.amount.clone()
+    }
+}
+
+#[derive(OneofObject)]
+pub enum SplitInput {
+    Equal(Vec<UserID>),
+    Shares(Vec<ShareDebtorInput>),
+    Percentages(Vec<PercentageDebtorInput>),
+    Exact(Vec<ExactDebtorInput>),
+    Itemized(ItemizedSplitInput),
+}
+
+#[derive(InputObject)]
+pub struct ShareDebtorInput {
+    pub user: UserID,
+    pub shares: u32,
+}
+
+#[derive(InputObject)]
+pub struct PercentageDebtorInput {
+    pub user: UserID,
+    pub percent: u32,
+}
+
+#[derive(InputObject)]
+pub struct ExactDebtorInput {
+    pub user: UserID,
+    pub amount: MoneyInput,
+}
+
+#[derive(InputObject)]
+pub struct ItemizedSplitInput {
+    pub items: Vec<PaymentItemInput>,
+    pub tax: MoneyInput,
+    pub tip: MoneyInput,
+}
+
+#[derive(InputObject)]
+pub struct PaymentItemInput {
+    pub name: String,
+    pub price: MoneyInput,
+    #[graphql(default = 1)]
+    pub quantity: u32,
+    pub consumers: Vec<UserID>,
+}
+
+impl From<PaymentItemInput> for PaymentItem {
+    fn from(value: PaymentItemInput) -> Self {
+        PaymentItem::new(
+            value.name,
+            value.price.into(),
+            value.quantity,
+            value.consumers,
+        )
+    }
+}
+
+impl From<SplitInput> for Split {
+    fn from(value: SplitInput) -> Self {
+        match value {
+            SplitInput::Equal(debtors) => Split::Equal(EqualSplit { debtors }),
+            SplitInput::Shares(debtors) => Split::Shares(SharesSplit {
+                debtors: debtors
+                    .into_iter()
+                    .map(|d| ShareDebtor {
+                        user: d.user,
+                        shares: d.shares,
+                    })
+                    .collect(),
+            }),
+            SplitInput::Percentages(debtors) => Split::Percentages(PercentagesSplit {
+                debtors: debtors
+                    .into_iter()
+                    .map(|d| PercentageDebtor {
+                        user: d.user,
+                        percent: d.percent,
+                    })
+                    .collect(),
+            }),
+            SplitInput::Exact(debtors) => Split::Exact(ExactSplit {
+                debtors: debtors
+                    .into_iter()
+                    .map(|d| ExactDebtor {
+                        user: d.user,
+                        amount: d.amount.into(),
+                    })
+                    .collect(),
+            }),
+            SplitInput::Itemized(split) => Split::Itemized(ItemizedSplit {
+                items: split.items.into_iter().map(Into::into).collect(),
+                tax: split.tax.into(),
+                tip: split.tip.into(),
+            }),
+        }
+    }
+}
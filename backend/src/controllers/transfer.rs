@@ -0,0 +1,26 @@
+use crate::{
+    entities::{AuthState, Money, Transfer, User},
+    usecases::UseCase,
+};
+use async_graphql::{Context, Object};
+
+#[Object]
+impl Transfer {
+    async fn from(&self, ctx: &Context<'_>) -> async_graphql::Result<User> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let from = usecase.get_user(&self.from, auth).await?;
+        Ok(from)
+    }
+
+    async fn to(&self, ctx: &Context<'_>) -> async_graphql::Result<User> {
+        let usecase = ctx.data::<UseCase>()?;
+        let auth = ctx.data::<AuthState>()?;
+        let to = usecase.get_user(&self.to, auth).await?;
+        Ok(to)
+    }
+
+    async fn amount(&self) -> Money {
+        self.amount.clone()
+    }
+}
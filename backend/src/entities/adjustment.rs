@@ -0,0 +1,196 @@
+use crate::entities::{Money, MoneyError};
+use async_graphql::Enum;
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+use fake::Dummy;
+
+/// 支払い全体にかける調整の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(test, derive(Dummy))]
+pub enum AdjustmentKind {
+    Tax,
+    Tip,
+    ServiceCharge,
+    /// 小計から差し引く
+    Discount,
+}
+
+/// 分ける前の小計にかける税・チップ・サービス料・割引
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(Dummy))]
+pub struct Adjustment {
+    pub kind: AdjustmentKind,
+    pub value: AdjustmentValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[cfg_attr(test, derive(Dummy))]
+pub enum AdjustmentValue {
+    /// 小計に対する割合 (1/100 % 単位)
+    Percentage {
+        #[cfg_attr(test, dummy(faker = "0..3000"))]
+        basis_points: u32,
+    },
+    /// 小計と同じ通貨の固定額
+    Fixed { amount: Money },
+}
+
+/// 小計・各調整の額・合計の内訳
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breakdown {
+    pub subtotal: Money,
+    pub adjustments: Vec<AppliedAdjustment>,
+    pub total: Money,
+}
+
+/// 小計に対して計算した調整の額 (割引は負)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedAdjustment {
+    pub adjustment: Adjustment,
+    pub amount: Money,
+}
+
+impl Adjustment {
+    pub const MAX_BASIS_POINTS: u32 = 10_000;
+
+    pub fn percentage(kind: AdjustmentKind, basis_points: u32) -> Self {
+        Self {
+            kind,
+            value: AdjustmentValue::Percentage { basis_points },
+        }
+    }
+
+    pub fn fixed(kind: AdjustmentKind, amount: Money) -> Self {
+        Self {
+            kind,
+            value: AdjustmentValue::Fixed { amount },
+        }
+    }
+
+    /// 割合は 100 % 以下、固定額は小計と同じ通貨で負でないこと
+    pub fn is_valid(&self, subtotal: &Money) -> bool {
+        match &self.value {
+            AdjustmentValue::Percentage { basis_points } => *basis_points <= Self::MAX_BASIS_POINTS,
+            AdjustmentValue::Fixed { amount } => {
+                amount.currency == subtotal.currency
+                    && 0 <= amount.amount
+                    && amount.amount <= Money::MAX_AMOUNT
+            }
+        }
+    }
+
+    /// `subtotal` に対する調整の額 (最小通貨単位未満は四捨五入)。割引は負の額を返す
+    pub fn amount(&self, subtotal: &Money) -> Result<Money, MoneyError> {
+        let amount = match &self.value {
+            AdjustmentValue::Percentage { basis_points } => {
+                let numerator = i128::from(subtotal.amount) * i128::from(*basis_points);
+                let denominator = i128::from(Self::MAX_BASIS_POINTS);
+
+                let mut amount = numerator / denominator;
+                if denominator <= 2 * (numerator % denominator).abs() {
+                    amount += numerator.signum();
+                }
+                i64::try_from(amount).map_err(|_| MoneyError::Overflow)?
+            }
+            AdjustmentValue::Fixed { amount } => {
+                if amount.currency != subtotal.currency {
+                    return Err(MoneyError::CurrencyMismatch);
+                }
+                amount.amount
+            }
+        };
+
+        let amount = match self.kind {
+            AdjustmentKind::Discount => -amount,
+            _ => amount,
+        };
+        Ok(Money::new(amount, subtotal.currency.clone()))
+    }
+}
+
+impl Breakdown {
+    /// 各調整はいずれも `subtotal` に対して計算する (調整同士で複利にはしない)
+    pub fn new(subtotal: &Money, adjustments: &[Adjustment]) -> Result<Self, MoneyError> {
+        let mut total = subtotal.clone();
+        let mut applied = vec![];
+        for adjustment in adjustments {
+            let amount = adjustment.amount(subtotal)?;
+            total = total.checked_add(&amount)?;
+            applied.push(AppliedAdjustment {
+                adjustment: adjustment.clone(),
+                amount,
+            });
+        }
+
+        Ok(Self {
+            subtotal: subtotal.clone(),
+            adjustments: applied,
+            total,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::Currency;
+
+    fn jpy(amount: i64) -> Money {
+        Money::new(amount, Currency::default())
+    }
+
+    #[test]
+    fn percentage() {
+        let tax = Adjustment::percentage(AdjustmentKind::Tax, 1000);
+        let tip = Adjustment::percentage(AdjustmentKind::Tip, 1250);
+
+        assert_eq!(tax.amount(&jpy(1234)).unwrap(), jpy(123));
+        assert_eq!(tip.amount(&jpy(1234)).unwrap(), jpy(154));
+        assert_eq!(tip.amount(&jpy(1236)).unwrap(), jpy(155));
+    }
+
+    #[test]
+    fn discount() {
+        let discount = Adjustment::fixed(AdjustmentKind::Discount, jpy(300));
+
+        assert_eq!(discount.amount(&jpy(1000)).unwrap(), jpy(-300));
+    }
+
+    #[test]
+    fn invalid() {
+        let percentage = Adjustment::percentage(AdjustmentKind::Discount, 10_001);
+        let fixed = Adjustment::fixed(
+            AdjustmentKind::ServiceCharge,
+            Money::new(100, Currency::new("USD").unwrap()),
+        );
+
+        assert!(!percentage.is_valid(&jpy(1000)));
+        assert!(!fixed.is_valid(&jpy(1000)));
+        assert!(fixed.amount(&jpy(1000)).is_err());
+    }
+
+    #[test]
+    fn breakdown() {
+        let adjustments = vec![
+            Adjustment::percentage(AdjustmentKind::Tax, 1000),
+            Adjustment::percentage(AdjustmentKind::ServiceCharge, 500),
+            Adjustment::fixed(AdjustmentKind::Discount, jpy(200)),
+        ];
+
+        let breakdown = Breakdown::new(&jpy(2000), &adjustments).unwrap();
+
+        assert_eq!(breakdown.subtotal, jpy(2000));
+        assert_eq!(
+            breakdown
+                .adjustments
+                .iter()
+                .map(|adjustment| adjustment.amount.amount)
+                .collect::<Vec<_>>(),
+            vec![200, 100, -200]
+        );
+        assert_eq!(breakdown.total, jpy(2100));
+    }
+}
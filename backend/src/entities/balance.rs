@@ -0,0 +1,234 @@
+use crate::entities::{Currency, Group, Money, MoneyError, Payment, Settlement, UserID};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Balance {
+    pub user: UserID,
+    pub paid: Money,
+    pub owed: Money,
+    pub sent: Money,
+    pub received: Money,
+    pub net: Money,
+}
+
+impl Balance {
+    pub fn new(user: UserID, currency: Currency) -> Self {
+        Self {
+            user,
+            paid: Money::zero(currency.clone()),
+            owed: Money::zero(currency.clone()),
+            sent: Money::zero(currency.clone()),
+            received: Money::zero(currency.clone()),
+            net: Money::zero(currency),
+        }
+    }
+}
+
+/// グループ内の各参加者について、支払った額・負担すべき額・返済の送受金額・差し引きを
+/// グループの基準通貨で計算する
+pub fn compute_balances(
+    group: &Group,
+    payments: &[Payment],
+    settlements: &[Settlement],
+) -> Result<Vec<Balance>, MoneyError> {
+    let currency = group.currency.clone();
+
+    let mut balances: Vec<Balance> = group
+        .members()
+        .map(|user| Balance::new(user.clone(), currency.clone()))
+        .collect();
+
+    for payment in payments {
+        // 換算の端数で差し引きがずれないよう、立て替えた額は換算後の負担額の合計を
+        // 各支払者が立て替えた額の比で配分したものとする
+        let shares = payment.shares_in(&currency, group.rounding)?;
+        let mut paid = Money::zero(currency.clone());
+
+        for share in shares {
+            paid = paid.checked_add(&share.amount)?;
+            let debtor = entry(&mut balances, &share.user, &currency);
+            debtor.owed = debtor.owed.checked_add(&share.amount)?;
+        }
+
+        for share in payment.paid_in(&paid, group.rounding) {
+            let payer = entry(&mut balances, &share.user, &currency);
+            payer.paid = payer.paid.checked_add(&share.amount)?;
+        }
+    }
+
+    for settlement in settlements {
+        let from = entry(&mut balances, &settlement.from, &currency);
+        from.sent = from.sent.checked_add(&settlement.amount)?;
+
+        let to = entry(&mut balances, &settlement.to, &currency);
+        to.received = to.received.checked_add(&settlement.amount)?;
+    }
+
+    for balance in balances.iter_mut() {
+        balance.net = balance
+            .paid
+            .checked_sub(&balance.owed)?
+            .checked_add(&balance.sent)?
+            .checked_sub(&balance.received)?;
+    }
+
+    Ok(balances)
+}
+
+fn entry<'a>(
+    balances: &'a mut Vec<Balance>,
+    user: &UserID,
+    currency: &Currency,
+) -> &'a mut Balance {
+    let index = match balances.iter().position(|balance| &balance.user == user) {
+        Some(index) => index,
+        None => {
+            balances.push(Balance::new(user.clone(), currency.clone()));
+            balances.len() - 1
+        }
+    };
+    &mut balances[index]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Payer, RoundingPolicy, Split};
+    use fake::{Fake, Faker};
+
+    fn jpy(amount: i64) -> Money {
+        Money::new(amount, Currency::default())
+    }
+
+    #[test]
+    fn no_payments() {
+        let group: Group = Faker.fake();
+
+        let balances = compute_balances(&group, &[], &[]).unwrap();
+
+        assert_eq!(balances.len(), group.participants.len());
+        assert!(balances.iter().all(|balance| balance.net.is_zero()));
+    }
+
+    #[test]
+    fn single_payment() {
+        let mut group: Group = Faker.fake();
+        let a: UserID = Faker.fake();
+        let b: UserID = Faker.fake();
+        let c: UserID = Faker.fake();
+        group.participants = vec![a.clone(), b.clone(), c.clone()];
+
+        let mut payment: Payment = Faker.fake();
+        payment.amount = jpy(900);
+        payment.payers = vec![Payer::new(a.clone(), jpy(900))];
+        payment.split = Split::equal(vec![a.clone(), b.clone(), c.clone()]);
+
+        let balances = compute_balances(&group, &[payment], &[]).unwrap();
+
+        assert_eq!(balances[0].user, a);
+        assert_eq!(balances[0].paid, jpy(900));
+        assert_eq!(balances[0].owed, jpy(300));
+        assert_eq!(balances[0].net, jpy(600));
+        assert_eq!(balances[1].net, jpy(-300));
+        assert_eq!(balances[2].net, jpy(-300));
+    }
+
+    #[test]
+    fn settled() {
+        let mut group: Group = Faker.fake();
+        let a: UserID = Faker.fake();
+        let b: UserID = Faker.fake();
+        group.participants = vec![a.clone(), b.clone()];
+
+        let mut payment: Payment = Faker.fake();
+        payment.amount = jpy(1000);
+        payment.payers = vec![Payer::new(a.clone(), jpy(1000))];
+        payment.split = Split::equal(vec![a.clone(), b.clone()]);
+
+        let mut settlement: Settlement = Faker.fake();
+        settlement.from = b.clone();
+        settlement.to = a.clone();
+        settlement.amount = jpy(500);
+
+        let balances = compute_balances(&group, &[payment], &[settlement]).unwrap();
+
+        assert_eq!(balances[0].received, jpy(500));
+        assert_eq!(balances[1].sent, jpy(500));
+        assert!(balances.iter().all(|balance| balance.net.is_zero()));
+    }
+
+    #[test]
+    fn nets_sum_to_zero() {
+        for _ in 0..100 {
+            let group: Group = Faker.fake();
+            let mut payments: Vec<Payment> = (0..10).map(|_| Faker.fake()).collect();
+            for payment in payments.iter_mut() {
+                payment.payers = vec![Payer::new(
+                    group.participants[0].clone(),
+                    payment.amount.clone(),
+                )];
+                payment.split = Split::equal(group.participants.clone());
+            }
+
+            let balances = compute_balances(&group, &payments, &[]).unwrap();
+
+            let sum: i64 = balances.iter().map(|balance| balance.net.amount).sum();
+            assert_eq!(sum, 0);
+        }
+    }
+
+    #[test]
+    fn foreign_payment() {
+        let mut group: Group = Faker.fake();
+        let a: UserID = Faker.fake();
+        let b: UserID = Faker.fake();
+        group.participants = vec![a.clone(), b.clone()];
+        group.rounding = RoundingPolicy::ToFirstDebtor;
+
+        // 10.01 USD = 5.01 USD + 5.00 USD -> 752 JPY + 750 JPY
+        let mut payment: Payment = Faker.fake();
+        payment.amount = Money::new(1001, Currency::new("USD").unwrap());
+        payment.rate = "150".parse().unwrap();
+        payment.payers = vec![Payer::new(a.clone(), payment.amount.clone())];
+        payment.split = Split::equal(vec![a.clone(), b.clone()]);
+
+        let balances = compute_balances(&group, &[payment], &[]).unwrap();
+
+        assert_eq!(balances[0].paid, jpy(1502));
+        assert_eq!(balances[0].net, jpy(750));
+        assert_eq!(balances[1].net, jpy(-750));
+    }
+
+    #[test]
+    fn multiple_payers() {
+        let mut group: Group = Faker.fake();
+        let a: UserID = Faker.fake();
+        let b: UserID = Faker.fake();
+        let c: UserID = Faker.fake();
+        group.participants = vec![a.clone(), b.clone(), c.clone()];
+
+        let mut payment: Payment = Faker.fake();
+        payment.amount = jpy(900);
+        payment.payers = vec![
+            Payer::new(a.clone(), jpy(600)),
+            Payer::new(b.clone(), jpy(300)),
+        ];
+        payment.split = Split::equal(vec![a.clone(), b.clone(), c.clone()]);
+
+        let balances = compute_balances(&group, &[payment], &[]).unwrap();
+
+        assert_eq!(balances[0].paid, jpy(600));
+        assert_eq!(balances[0].net, jpy(300));
+        assert_eq!(balances[1].paid, jpy(300));
+        assert_eq!(balances[1].net, jpy(0));
+        assert_eq!(balances[2].net, jpy(-300));
+    }
+
+    #[test]
+    fn currency_mismatch() {
+        let group: Group = Faker.fake();
+        let mut settlement: Settlement = Faker.fake();
+        settlement.amount.currency = Currency::new("USD").unwrap();
+
+        assert!(compute_balances(&group, &[], &[settlement]).is_err());
+    }
+}
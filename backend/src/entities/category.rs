@@ -0,0 +1,193 @@
+use crate::entities::{Group, Money, MoneyError, Payment};
+use async_graphql::Enum;
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+use fake::Dummy;
+
+/// 支払いの分類
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Enum,
+)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(test, derive(Dummy))]
+pub enum Category {
+    Food,
+    Transport,
+    Lodging,
+    Entertainment,
+    Shopping,
+    #[default]
+    Other,
+}
+
+/// 支払いの絞り込み条件 (`None` の条件では絞り込まない)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(test, derive(Dummy))]
+pub struct PaymentFilter {
+    pub category: Option<Category>,
+    pub tag: Option<String>,
+}
+
+/// カテゴリごとの、グループの基準通貨に換算した調整後の合計
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryTotal {
+    pub category: Category,
+    pub total: Money,
+}
+
+impl PaymentFilter {
+    /// タグを保存するときと同じ形にそろえる。空のタグでは絞り込まない
+    pub fn normalized(self) -> Self {
+        Self {
+            tag: self.tag.as_deref().and_then(normalize_tag),
+            ..self
+        }
+    }
+
+    pub fn matches(&self, payment: &Payment) -> bool {
+        self.category
+            .is_none_or(|category| payment.category == category)
+            && self
+                .tag
+                .as_ref()
+                .is_none_or(|tag| payment.tags.contains(tag))
+    }
+}
+
+/// 前後の空白を取り除いて小文字にそろえ、空のタグと重複を捨てる
+pub fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = vec![];
+    for tag in tags.iter().filter_map(|tag| normalize_tag(tag)) {
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim();
+    (!tag.is_empty()).then(|| tag.to_lowercase())
+}
+
+/// 支払いのあるカテゴリについて、調整後の合計をグループの基準通貨に換算して合計する
+pub fn compute_category_totals(
+    group: &Group,
+    payments: &[Payment],
+) -> Result<Vec<CategoryTotal>, MoneyError> {
+    let mut totals: Vec<CategoryTotal> = vec![];
+
+    for payment in payments {
+        let amount = payment.rate.convert(&payment.total()?, &group.currency)?;
+        match totals
+            .iter_mut()
+            .find(|total| total.category == payment.category)
+        {
+            Some(total) => total.total = total.total.checked_add(&amount)?,
+            None => totals.push(CategoryTotal {
+                category: payment.category,
+                total: amount,
+            }),
+        }
+    }
+
+    totals.sort_by_key(|total| total.category);
+    Ok(totals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::Currency;
+    use fake::{Fake, Faker};
+
+    fn jpy(amount: i64) -> Money {
+        Money::new(amount, Currency::default())
+    }
+
+    #[test]
+    fn filter() {
+        let mut payment: Payment = Faker.fake();
+        payment.category = Category::Food;
+        payment.tags = vec!["dinner".to_string()];
+
+        assert!(PaymentFilter::default().matches(&payment));
+        assert!(PaymentFilter {
+            category: Some(Category::Food),
+            tag: Some("dinner".to_string()),
+        }
+        .matches(&payment));
+        assert!(!PaymentFilter {
+            category: Some(Category::Transport),
+            tag: None,
+        }
+        .matches(&payment));
+        assert!(!PaymentFilter {
+            category: None,
+            tag: Some("lunch".to_string()),
+        }
+        .matches(&payment));
+        assert!(PaymentFilter {
+            category: None,
+            tag: Some(" Dinner".to_string()),
+        }
+        .normalized()
+        .matches(&payment));
+        assert_eq!(
+            PaymentFilter {
+                category: None,
+                tag: Some(" ".to_string()),
+            }
+            .normalized(),
+            PaymentFilter::default()
+        );
+    }
+
+    #[test]
+    fn tags() {
+        let tags = vec![
+            " dinner ".to_string(),
+            "".to_string(),
+            "Dinner".to_string(),
+            "day 1".to_string(),
+        ];
+
+        assert_eq!(
+            normalize_tags(tags),
+            vec!["dinner".to_string(), "day 1".to_string()]
+        );
+    }
+
+    #[test]
+    fn category_totals() {
+        let group: Group = Faker.fake();
+
+        let mut food1: Payment = Faker.fake();
+        food1.amount = jpy(1000);
+        food1.category = Category::Food;
+        let mut food2: Payment = Faker.fake();
+        food2.amount = jpy(500);
+        food2.category = Category::Food;
+        let mut transport: Payment = Faker.fake();
+        transport.amount = Money::new(1000, Currency::new("USD").unwrap());
+        transport.rate = "150".parse().unwrap();
+        transport.category = Category::Transport;
+
+        let totals = compute_category_totals(&group, &[transport, food1, food2]).unwrap();
+
+        assert_eq!(
+            totals,
+            vec![
+                CategoryTotal {
+                    category: Category::Food,
+                    total: jpy(1500),
+                },
+                CategoryTotal {
+                    category: Category::Transport,
+                    total: jpy(1500),
+                },
+            ]
+        );
+    }
+}
@@ -1,5 +1,5 @@
-use crate::entities::{Claims, UserID};
-use async_graphql::{types::ID, NewType};
+use crate::entities::{Claims, Currency, ExchangeRate, RoundingPolicy, UserID};
+use async_graphql::{types::ID, Enum, NewType};
 use chrono::{DateTime, Utc};
 use nanoid::nanoid;
 use serde::{Deserialize, Serialize};
@@ -20,6 +20,51 @@ pub struct Group {
     pub title: String,
     #[cfg_attr(test, dummy(faker = "(Faker, 1..10)"))]
     pub participants: Vec<UserID>,
+    /// アカウントを持たない参加者。支払いや返済には関われるがログインはできない
+    #[serde(default)]
+    #[cfg_attr(test, dummy(default))]
+    pub guests: Vec<UserID>,
+    /// 残高と清算はこの通貨で計算する
+    #[serde(default)]
+    pub currency: Currency,
+    /// 手動で設定した為替レート。設定がない通貨は `ExchangeRateProvider` から取得する
+    #[serde(default)]
+    #[cfg_attr(test, dummy(default))]
+    pub rates: Vec<RateOverride>,
+    #[serde(default)]
+    pub rounding: RoundingPolicy,
+    #[serde(default)]
+    #[cfg_attr(test, dummy(default))]
+    pub roles: Vec<ParticipantRole>,
+}
+
+/// 参加者の権限。`Member < Admin < Owner` の順に強い
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Enum,
+)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(test, derive(Dummy))]
+pub enum Role {
+    #[default]
+    Member,
+    Admin,
+    Owner,
+}
+
+/// `currency` 1 単位あたりのグループの基準通貨の量
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(Dummy))]
+pub struct RateOverride {
+    pub currency: Currency,
+    pub rate: ExchangeRate,
+}
+
+/// `Member` 以外の権限を持つ参加者 (記録のない参加者は `Member`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(Dummy))]
+pub struct ParticipantRole {
+    pub user: UserID,
+    pub role: Role,
 }
 
 impl GroupID {
@@ -43,12 +88,131 @@ impl Dummy<Faker> for GroupID {
 }
 
 impl Group {
-    pub fn new(title: String, auth: &Claims) -> Self {
+    pub fn new(title: String, currency: Currency, auth: &Claims) -> Self {
         Self {
             id: GroupID::new(nanoid!()),
             created_at: Utc::now(),
             title,
             participants: vec![UserID::new(&auth.sub)],
+            guests: vec![],
+            currency,
+            rates: vec![],
+            rounding: RoundingPolicy::default(),
+            roles: vec![ParticipantRole {
+                user: UserID::new(&auth.sub),
+                role: Role::Owner,
+            }],
+        }
+    }
+
+    pub fn rate_override(&self, currency: &Currency) -> Option<ExchangeRate> {
+        self.rates
+            .iter()
+            .find(|rate| &rate.currency == currency)
+            .map(|rate| rate.rate)
+    }
+
+    /// `None` なら手動設定を取り消す
+    pub fn set_rate_override(&mut self, currency: Currency, rate: Option<ExchangeRate>) {
+        self.rates.retain(|r| r.currency != currency);
+        if let Some(rate) = rate {
+            self.rates.push(RateOverride { currency, rate });
+        }
+    }
+
+    /// 参加者とゲストを合わせた、支払いや返済に関われるメンバー
+    pub fn members(&self) -> impl Iterator<Item = &UserID> {
+        self.participants.iter().chain(self.guests.iter())
+    }
+
+    pub fn is_member(&self, user: &UserID) -> bool {
+        self.participants.contains(user) || self.guests.contains(user)
+    }
+
+    /// 参加者でなければ `None`。オーナーの記録がない古いグループでは先頭の参加者をオーナーとみなす
+    pub fn role(&self, user: &UserID) -> Option<Role> {
+        if !self.participants.contains(user) {
+            return None;
+        }
+        if let Some(role) = self.roles.iter().find(|role| &role.user == user) {
+            return Some(role.role);
+        }
+        if self.owner().as_ref() == Some(user) {
+            Some(Role::Owner)
+        } else {
+            Some(Role::Member)
         }
     }
+
+    pub fn owner(&self) -> Option<UserID> {
+        self.roles
+            .iter()
+            .find(|role| role.role == Role::Owner)
+            .map(|role| role.user.clone())
+            .or_else(|| self.participants.first().cloned())
+    }
+
+    /// `Owner` を設定すると元のオーナーは `Admin` になる
+    pub fn set_role(&mut self, user: &UserID, role: Role) {
+        if self.roles.iter().all(|role| role.role != Role::Owner) {
+            if let Some(owner) = self.owner() {
+                self.roles.push(ParticipantRole {
+                    user: owner,
+                    role: Role::Owner,
+                });
+            }
+        }
+        if role == Role::Owner {
+            for role in self
+                .roles
+                .iter_mut()
+                .filter(|role| role.role == Role::Owner)
+            {
+                role.role = Role::Admin;
+            }
+        }
+        self.roles.retain(|role| &role.user != user);
+        if role != Role::Member {
+            self.roles.push(ParticipantRole {
+                user: user.clone(),
+                role,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fake::Fake;
+
+    #[test]
+    fn role_of_legacy_group() {
+        let mut group: Group = Faker.fake();
+        group.participants = vec![Faker.fake(), Faker.fake()];
+        let outsider: UserID = Faker.fake();
+
+        assert_eq!(group.role(&group.participants[0]), Some(Role::Owner));
+        assert_eq!(group.role(&group.participants[1]), Some(Role::Member));
+        assert_eq!(group.role(&outsider), None);
+    }
+
+    #[test]
+    fn set_role() {
+        let mut group: Group = Faker.fake();
+        group.participants = vec![Faker.fake(), Faker.fake()];
+        let owner = group.participants[0].clone();
+        let member = group.participants[1].clone();
+
+        group.set_role(&member, Role::Owner);
+
+        assert_eq!(group.role(&member), Some(Role::Owner));
+        assert_eq!(group.role(&owner), Some(Role::Admin));
+        assert_eq!(group.owner(), Some(member.clone()));
+
+        group.set_role(&owner, Role::Member);
+
+        assert_eq!(group.role(&owner), Some(Role::Member));
+        assert_eq!(group.owner(), Some(member));
+    }
 }
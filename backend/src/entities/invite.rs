@@ -0,0 +1,63 @@
+use crate::entities::{GroupID, UserID};
+use async_graphql::{types::ID, NewType};
+use chrono::{DateTime, Duration, Utc};
+use nanoid::nanoid;
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+use fake::{Dummy, Faker};
+#[cfg(test)]
+use rand::Rng;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, NewType)]
+pub struct InviteToken(pub ID);
+
+/// グループへの招待。トークンを知っていれば期限内は誰でも参加できる
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(Dummy))]
+pub struct Invite {
+    pub token: InviteToken,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub group: GroupID,
+    pub created_by: UserID,
+}
+
+impl InviteToken {
+    pub fn new<T: ToString>(token: T) -> Self {
+        InviteToken(ID(token.to_string()))
+    }
+}
+
+impl ToString for InviteToken {
+    fn to_string(&self) -> String {
+        self.0 .0.to_string()
+    }
+}
+
+#[cfg(test)]
+impl Dummy<Faker> for InviteToken {
+    fn dummy_with_rng<R: Rng + ?Sized>(config: &Faker, rng: &mut R) -> Self {
+        let s = String::dummy_with_rng(config, rng);
+        InviteToken(ID(s))
+    }
+}
+
+impl Invite {
+    pub const TTL_DAYS: i64 = 7;
+
+    pub fn new(group: GroupID, created_by: UserID) -> Self {
+        let created_at = Utc::now();
+        Self {
+            token: InviteToken::new(nanoid!(32)),
+            created_at,
+            expires_at: created_at + Duration::days(Self::TTL_DAYS),
+            group,
+            created_by,
+        }
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+}
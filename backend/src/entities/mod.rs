@@ -1,9 +1,27 @@
+mod adjustment;
 mod auth;
+mod balance;
+mod category;
 mod group;
+mod invite;
+mod money;
 mod payment;
+mod recurring;
+mod settlement;
+mod split;
+mod transfer;
 mod user;
 
+pub use adjustment::*;
 pub use auth::*;
+pub use balance::*;
+pub use category::*;
 pub use group::*;
+pub use invite::*;
+pub use money::*;
 pub use payment::*;
+pub use recurring::*;
+pub use settlement::*;
+pub use split::*;
+pub use transfer::*;
 pub use user::*;
@@ -0,0 +1,320 @@
+use async_graphql::NewType;
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+#[cfg(test)]
+use fake::{Dummy, Faker};
+#[cfg(test)]
+use rand::Rng;
+
+/// ISO 4217 の通貨コード
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, NewType)]
+pub struct Currency(pub String);
+
+/// 最小通貨単位 (円, セント, ...) の整数で表した金額
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(Dummy))]
+pub struct Money {
+    #[cfg_attr(test, dummy(faker = "1..1_000_000"))]
+    pub amount: i64,
+    pub currency: Currency,
+}
+
+#[derive(Debug, Error)]
+pub enum MoneyError {
+    #[error("invalid currency code")]
+    InvalidCurrency,
+
+    #[error("currency mismatch")]
+    CurrencyMismatch,
+
+    #[error("amount overflow")]
+    Overflow,
+
+    #[error("invalid exchange rate")]
+    InvalidExchangeRate,
+}
+
+/// 為替レート。換算元の通貨 1 単位 (補助単位ではない) あたりの換算先の通貨の量を
+/// `SCALE` 倍した整数で表す
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExchangeRate(pub i64);
+
+impl Currency {
+    pub fn new<T: ToString>(code: T) -> Result<Self, MoneyError> {
+        let currency = Currency(code.to_string());
+        currency
+            .is_valid()
+            .then_some(currency)
+            .ok_or(MoneyError::InvalidCurrency)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.0.len() == 3 && self.0.chars().all(|c| c.is_ascii_uppercase())
+    }
+
+    /// 1 単位が何桁の最小通貨単位からなるか (ISO 4217 の minor unit)
+    pub fn exponent(&self) -> u32 {
+        match self.0.as_str() {
+            "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF"
+            | "UGX" | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+            "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+            _ => 2,
+        }
+    }
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Currency("JPY".to_string())
+    }
+}
+
+impl ToString for Currency {
+    fn to_string(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[cfg(test)]
+impl Dummy<Faker> for Currency {
+    fn dummy_with_rng<R: Rng + ?Sized>(_: &Faker, _: &mut R) -> Self {
+        Currency::default()
+    }
+}
+
+impl Money {
+    pub const MAX_AMOUNT: i64 = 1_000_000_000_000;
+
+    pub fn new(amount: i64, currency: Currency) -> Self {
+        Self { amount, currency }
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(0, currency)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.amount == 0
+    }
+
+    pub fn checked_add(&self, other: &Money) -> Result<Money, MoneyError> {
+        if self.currency != other.currency {
+            return Err(MoneyError::CurrencyMismatch);
+        }
+        let amount = self
+            .amount
+            .checked_add(other.amount)
+            .ok_or(MoneyError::Overflow)?;
+        Ok(Money::new(amount, self.currency.clone()))
+    }
+
+    pub fn checked_sub(&self, other: &Money) -> Result<Money, MoneyError> {
+        if self.currency != other.currency {
+            return Err(MoneyError::CurrencyMismatch);
+        }
+        let amount = self
+            .amount
+            .checked_sub(other.amount)
+            .ok_or(MoneyError::Overflow)?;
+        Ok(Money::new(amount, self.currency.clone()))
+    }
+}
+
+impl ExchangeRate {
+    pub const SCALE: i64 = 1_000_000;
+    pub const IDENTITY: ExchangeRate = ExchangeRate(Self::SCALE);
+
+    pub fn is_valid(&self) -> bool {
+        0 < self.0
+    }
+
+    /// 逆向きのレート (`SCALE` 未満の端数は四捨五入)
+    pub fn inverse(&self) -> Option<ExchangeRate> {
+        if !self.is_valid() {
+            return None;
+        }
+        let scale = i128::from(Self::SCALE);
+        let rate = i128::from(self.0);
+        let inverse = (scale * scale + rate / 2) / rate;
+        i64::try_from(inverse)
+            .ok()
+            .filter(|inverse| 0 < *inverse)
+            .map(ExchangeRate)
+    }
+
+    /// `money` を `to` に換算する (最小通貨単位未満は四捨五入)。同じ通貨ならそのまま返す
+    pub fn convert(&self, money: &Money, to: &Currency) -> Result<Money, MoneyError> {
+        if &money.currency == to {
+            return Ok(money.clone());
+        }
+        let numerator = i128::from(money.amount) * i128::from(self.0) * 10i128.pow(to.exponent());
+        let denominator = i128::from(Self::SCALE) * 10i128.pow(money.currency.exponent());
+
+        let mut amount = numerator / denominator;
+        if denominator <= 2 * (numerator % denominator).abs() {
+            amount += numerator.signum();
+        }
+
+        let amount = i64::try_from(amount).map_err(|_| MoneyError::Overflow)?;
+        Ok(Money::new(amount, to.clone()))
+    }
+}
+
+impl Default for ExchangeRate {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+/// `"150.25"` のような小数表記 (小数点以下 6 桁まで)
+impl FromStr for ExchangeRate {
+    type Err = MoneyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (integer, fraction) = s.split_once('.').unwrap_or((s, ""));
+        let digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+        if integer.is_empty() || 6 < fraction.len() || !digits(integer) || !digits(fraction) {
+            return Err(MoneyError::InvalidExchangeRate);
+        }
+
+        let integer: i64 = integer
+            .parse()
+            .map_err(|_| MoneyError::InvalidExchangeRate)?;
+        let fraction: i64 = format!("{fraction:0<6}")
+            .parse()
+            .map_err(|_| MoneyError::InvalidExchangeRate)?;
+        let rate = integer
+            .checked_mul(Self::SCALE)
+            .and_then(|rate| rate.checked_add(fraction))
+            .ok_or(MoneyError::InvalidExchangeRate)?;
+
+        Ok(ExchangeRate(rate))
+    }
+}
+
+impl fmt::Display for ExchangeRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let integer = self.0 / Self::SCALE;
+        let fraction = format!("{:06}", self.0 % Self::SCALE);
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            write!(f, "{integer}")
+        } else {
+            write!(f, "{integer}.{fraction}")
+        }
+    }
+}
+
+#[cfg(test)]
+impl Dummy<Faker> for ExchangeRate {
+    fn dummy_with_rng<R: Rng + ?Sized>(_: &Faker, _: &mut R) -> Self {
+        ExchangeRate::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn currency_valid() {
+        assert!(Currency::new("JPY").is_ok());
+        assert!(Currency::new("USD").is_ok());
+    }
+
+    #[test]
+    fn currency_invalid() {
+        assert!(Currency::new("jpy").is_err());
+        assert!(Currency::new("YEN!").is_err());
+        assert!(Currency::new("").is_err());
+    }
+
+    #[test]
+    fn checked_add() {
+        let a = Money::new(1000, Currency::default());
+        let b = Money::new(234, Currency::default());
+        assert_eq!(
+            a.checked_add(&b).unwrap(),
+            Money::new(1234, Currency::default())
+        );
+    }
+
+    #[test]
+    fn checked_add_currency_mismatch() {
+        let a = Money::new(1000, Currency::default());
+        let b = Money::new(234, Currency::new("USD").unwrap());
+        assert!(a.checked_add(&b).is_err());
+    }
+
+    #[test]
+    fn checked_add_overflow() {
+        let a = Money::new(i64::MAX, Currency::default());
+        let b = Money::new(1, Currency::default());
+        assert!(a.checked_add(&b).is_err());
+    }
+
+    #[test]
+    fn checked_sub() {
+        let a = Money::new(1000, Currency::default());
+        let b = Money::new(1234, Currency::default());
+        assert_eq!(
+            a.checked_sub(&b).unwrap(),
+            Money::new(-234, Currency::default())
+        );
+    }
+
+    #[test]
+    fn exchange_rate_parse() {
+        assert_eq!(
+            "150.25".parse::<ExchangeRate>().unwrap(),
+            ExchangeRate(150_250_000)
+        );
+        assert_eq!("1".parse::<ExchangeRate>().unwrap(), ExchangeRate::IDENTITY);
+        assert_eq!("0.000001".parse::<ExchangeRate>().unwrap(), ExchangeRate(1));
+        assert!("1.0000001".parse::<ExchangeRate>().is_err());
+        assert!("-1".parse::<ExchangeRate>().is_err());
+        assert!(".5".parse::<ExchangeRate>().is_err());
+        assert_eq!(ExchangeRate(150_250_000).to_string(), "150.25");
+        assert_eq!(ExchangeRate::IDENTITY.to_string(), "1");
+    }
+
+    #[test]
+    fn exchange_rate_inverse() {
+        assert_eq!(
+            "0.5".parse::<ExchangeRate>().unwrap().inverse(),
+            Some(ExchangeRate(2_000_000))
+        );
+        assert_eq!(
+            "150".parse::<ExchangeRate>().unwrap().inverse(),
+            Some(ExchangeRate(6_667))
+        );
+        assert_eq!(ExchangeRate(0).inverse(), None);
+    }
+
+    #[test]
+    fn exchange_rate_convert() {
+        let jpy = Currency::default();
+        let usd = Currency::new("USD").unwrap();
+        let rate: ExchangeRate = "150.25".parse().unwrap();
+
+        // 10.01 USD -> 1504.0025 JPY
+        assert_eq!(
+            rate.convert(&Money::new(1001, usd.clone()), &jpy).unwrap(),
+            Money::new(1504, jpy.clone())
+        );
+
+        // 1000 JPY -> 6.666... USD
+        let rate: ExchangeRate = "0.006667".parse().unwrap();
+        assert_eq!(
+            rate.convert(&Money::new(1000, jpy.clone()), &usd).unwrap(),
+            Money::new(667, usd.clone())
+        );
+
+        assert_eq!(
+            rate.convert(&Money::new(1000, jpy.clone()), &jpy).unwrap(),
+            Money::new(1000, jpy)
+        );
+    }
+}
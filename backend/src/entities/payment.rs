@@ -1,6 +1,9 @@
-use crate::entities::{GroupID, UserID};
+use crate::entities::{
+    normalize_tags, split::allocate, Adjustment, Breakdown, Category, Currency, ExchangeRate,
+    GroupID, Money, MoneyError, RoundingPolicy, Share, Split, UserID,
+};
 use async_graphql::{types::ID, NewType};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, Utc};
 use nanoid::nanoid;
 use serde::{Deserialize, Serialize};
 
@@ -16,12 +19,69 @@ pub struct PaymentID(pub ID);
 #[cfg_attr(test, derive(Dummy))]
 pub struct Payment {
     pub id: PaymentID,
+    /// 記録した日時
     pub created_at: DateTime<Utc>,
+    /// 支払った日時 (入力したユーザーのタイムゾーン)
+    pub occurred_at: DateTime<FixedOffset>,
     pub title: String,
+    /// 調整前の小計
+    pub amount: Money,
+    /// 小計にかける税・チップ・サービス料・割引
+    #[serde(default)]
+    #[cfg_attr(test, dummy(default))]
+    pub adjustments: Vec<Adjustment>,
+    /// 作成時点での `amount` の通貨からグループの基準通貨へのレート
+    #[serde(default)]
+    pub rate: ExchangeRate,
     pub group: GroupID,
-    pub creditor: UserID,
-    #[cfg_attr(test, dummy(faker = "(Faker, 1..10)"))]
-    pub debtors: Vec<UserID>,
+    /// 立て替えた人とその額。合計は調整後の合計と一致する
+    #[cfg_attr(test, dummy(faker = "(Faker, 1..2)"))]
+    pub payers: Vec<Payer>,
+    pub split: Split,
+    #[serde(default)]
+    pub category: Category,
+    #[serde(default)]
+    #[cfg_attr(test, dummy(default))]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(Dummy))]
+pub struct Payer {
+    pub user: UserID,
+    pub amount: Money,
+}
+
+/// 新しく記録する支払いの内容
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(Dummy))]
+pub struct NewPayment {
+    pub title: String,
+    /// `None` なら記録した日時
+    pub occurred_at: Option<DateTime<FixedOffset>>,
+    pub amount: Money,
+    pub adjustments: Vec<Adjustment>,
+    /// `None` ならグループの設定か `ExchangeRateProvider` から決める
+    pub rate: Option<ExchangeRate>,
+    pub payers: Vec<Payer>,
+    pub split: Split,
+    pub category: Category,
+    pub tags: Vec<String>,
+}
+
+/// `Payment` の部分更新 (`None` のフィールドは変更しない)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(test, derive(Dummy))]
+pub struct PaymentUpdate {
+    pub title: Option<String>,
+    pub occurred_at: Option<DateTime<FixedOffset>>,
+    pub amount: Option<Money>,
+    pub adjustments: Option<Vec<Adjustment>>,
+    pub rate: Option<ExchangeRate>,
+    pub payers: Option<Vec<Payer>>,
+    pub split: Option<Split>,
+    pub category: Option<Category>,
+    pub tags: Option<Vec<String>>,
 }
 
 impl PaymentID {
@@ -44,15 +104,314 @@ impl Dummy<Faker> for PaymentID {
     }
 }
 
+impl Payer {
+    pub fn new(user: UserID, amount: Money) -> Self {
+        Self { user, amount }
+    }
+}
+
+impl NewPayment {
+    /// 支払者か債務者として `user` が含まれるか
+    pub fn involves(&self, user: &UserID) -> bool {
+        self.payers.iter().any(|payer| &payer.user == user) || self.split.debtors().contains(user)
+    }
+
+    /// 支払者・債務者の `from` を `to` に置き換える。`to` も支払者なら額をまとめる
+    pub fn replace_member(&mut self, from: &UserID, to: &UserID) -> Result<(), MoneyError> {
+        replace_payer(&mut self.payers, from, to)?;
+        self.split = self.split.replace(from, to);
+        Ok(())
+    }
+}
+
 impl Payment {
-    pub fn new(title: String, group: GroupID, creditor: UserID, debtors: Vec<UserID>) -> Self {
+    pub fn new(payment: NewPayment, rate: ExchangeRate, group: GroupID) -> Self {
+        let created_at = Utc::now();
         Self {
             id: PaymentID::new(nanoid!()),
-            created_at: Utc::now(),
-            title,
+            created_at,
+            occurred_at: payment.occurred_at.unwrap_or_else(|| created_at.into()),
+            title: payment.title,
+            amount: payment.amount,
+            adjustments: payment.adjustments,
+            rate,
             group,
-            creditor,
-            debtors,
+            payers: payment.payers,
+            split: payment.split,
+            category: payment.category,
+            tags: normalize_tags(payment.tags),
+        }
+    }
+
+    pub fn apply(&mut self, update: PaymentUpdate) {
+        if let Some(title) = update.title {
+            self.title = title;
+        }
+        if let Some(occurred_at) = update.occurred_at {
+            self.occurred_at = occurred_at;
+        }
+        if let Some(amount) = update.amount {
+            self.amount = amount;
+        }
+        if let Some(adjustments) = update.adjustments {
+            self.adjustments = adjustments;
+        }
+        if let Some(rate) = update.rate {
+            self.rate = rate;
+        }
+        if let Some(payers) = update.payers {
+            self.payers = payers;
+        }
+        if let Some(split) = update.split {
+            self.split = split;
+        }
+        if let Some(category) = update.category {
+            self.category = category;
+        }
+        if let Some(tags) = update.tags {
+            self.tags = normalize_tags(tags);
+        }
+    }
+
+    /// 先頭の支払者。端数の負担 (`RoundingPolicy::ToCreditor`) などで使う。
+    /// 作成時に確かめているが、支払者を移行できなかった古い記録では `None` になる
+    pub fn creditor(&self) -> Option<&UserID> {
+        self.payers.first().map(|payer| &payer.user)
+    }
+
+    pub fn is_payer(&self, user: &UserID) -> bool {
+        self.payers.iter().any(|payer| &payer.user == user)
+    }
+
+    /// 支払者が 1 人なら、その額を調整後の合計に合わせる
+    pub fn sync_sole_payer(&mut self) -> Result<(), MoneyError> {
+        if let [payer] = self.payers.as_mut_slice() {
+            payer.amount = Breakdown::new(&self.amount, &self.adjustments)?.total;
+        }
+        Ok(())
+    }
+
+    /// 支払者 `from` を `to` に置き換える。`to` も支払者なら額をまとめる
+    pub fn replace_payer(&mut self, from: &UserID, to: &UserID) -> Result<(), MoneyError> {
+        replace_payer(&mut self.payers, from, to)
+    }
+
+    pub fn breakdown(&self) -> Result<Breakdown, MoneyError> {
+        Breakdown::new(&self.amount, &self.adjustments)
+    }
+
+    /// 調整後の合計
+    pub fn total(&self) -> Result<Money, MoneyError> {
+        Ok(self.breakdown()?.total)
+    }
+
+    /// 支払者がいなければ誰も立て替えていないので、負担もない
+    pub fn shares(&self, rounding: RoundingPolicy) -> Result<Vec<Share>, MoneyError> {
+        let Some(creditor) = self.creditor() else {
+            return Ok(vec![]);
+        };
+        let shares = self.split.shares(&self.amount, creditor, rounding);
+        if self.adjustments.is_empty() {
+            return Ok(shares);
         }
+
+        // 小計を分けた額を重みとして、調整後の合計を配分し直す
+        let weights: Vec<(UserID, i64)> = shares
+            .into_iter()
+            .map(|share| (share.user, share.amount.amount))
+            .collect();
+        Ok(allocate(&self.total()?, creditor, &weights, rounding))
+    }
+
+    /// 各債務者の負担額を `currency` に換算して返す
+    pub fn shares_in(
+        &self,
+        currency: &Currency,
+        rounding: RoundingPolicy,
+    ) -> Result<Vec<Share>, MoneyError> {
+        self.shares(rounding)?
+            .into_iter()
+            .map(|share| {
+                Ok(Share {
+                    amount: self.rate.convert(&share.amount, currency)?,
+                    user: share.user,
+                })
+            })
+            .collect()
+    }
+
+    /// 換算後の合計 `total` を、各支払者が立て替えた額の比で配分する
+    pub fn paid_in(&self, total: &Money, rounding: RoundingPolicy) -> Vec<Share> {
+        let Some(creditor) = self.creditor() else {
+            return vec![];
+        };
+        let weights: Vec<(UserID, i64)> = self
+            .payers
+            .iter()
+            .map(|payer| (payer.user.clone(), payer.amount.amount))
+            .collect();
+        allocate(total, creditor, &weights, rounding)
+    }
+}
+
+/// 支払った日時の順 (同じなら記録した順) に並べる
+pub fn sort_payments(payments: &mut [Payment]) {
+    payments.sort_by(|a, b| {
+        a.occurred_at
+            .cmp(&b.occurred_at)
+            .then(a.created_at.cmp(&b.created_at))
+    });
+}
+
+/// `Payment::replace_payer` と `NewPayment::replace_member` の共通部分
+fn replace_payer(payers: &mut Vec<Payer>, from: &UserID, to: &UserID) -> Result<(), MoneyError> {
+    let Some(index) = payers.iter().position(|payer| &payer.user == from) else {
+        return Ok(());
+    };
+    match payers.iter().position(|payer| &payer.user == to) {
+        Some(target) => {
+            let payer = payers.remove(index);
+            let target = if index < target { target - 1 } else { target };
+            payers[target].amount = payers[target].amount.checked_add(&payer.amount)?;
+        }
+        None => payers[index].user = to.clone(),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{AdjustmentKind, ExactDebtor, ExactSplit};
+    use fake::Fake;
+
+    fn jpy(amount: i64) -> Money {
+        Money::new(amount, Currency::default())
+    }
+
+    #[test]
+    fn shares_with_adjustments() {
+        let a: UserID = Faker.fake();
+        let b: UserID = Faker.fake();
+
+        let mut payment: Payment = Faker.fake();
+        payment.amount = jpy(3000);
+        payment.payers = vec![Payer::new(a.clone(), jpy(3601))];
+        payment.split = Split::Exact(ExactSplit {
+            debtors: vec![
+                ExactDebtor {
+                    user: a.clone(),
+                    amount: jpy(2000),
+                },
+                ExactDebtor {
+                    user: b.clone(),
+                    amount: jpy(1000),
+                },
+            ],
+        });
+        payment.adjustments = vec![
+            Adjustment::percentage(AdjustmentKind::Tax, 1000),
+            Adjustment::fixed(AdjustmentKind::Tip, jpy(301)),
+        ];
+
+        let shares = payment.shares(RoundingPolicy::ToFirstDebtor).unwrap();
+
+        assert_eq!(payment.total().unwrap(), jpy(3601));
+        assert_eq!(shares[0].user, a);
+        assert_eq!(shares[0].amount, jpy(2401));
+        assert_eq!(shares[1].amount, jpy(1200));
+    }
+
+    #[test]
+    fn shares_without_adjustments() {
+        let mut payment: Payment = Faker.fake();
+        payment.split = Split::equal(vec![Faker.fake(), Faker.fake()]);
+
+        let shares = payment.shares(RoundingPolicy::default()).unwrap();
+
+        assert_eq!(payment.total().unwrap(), payment.amount);
+        assert_eq!(
+            shares.iter().map(|share| share.amount.amount).sum::<i64>(),
+            payment.amount.amount
+        );
+    }
+
+    #[test]
+    fn shares_without_payers() {
+        let mut payment: Payment = Faker.fake();
+        payment.payers = vec![];
+        payment.split = Split::equal(vec![Faker.fake(), Faker.fake()]);
+
+        assert_eq!(payment.creditor(), None);
+        assert!(payment
+            .shares(RoundingPolicy::default())
+            .unwrap()
+            .is_empty());
+        assert!(payment
+            .paid_in(&payment.amount, RoundingPolicy::default())
+            .is_empty());
+    }
+
+    #[test]
+    fn paid_in() {
+        let a: UserID = Faker.fake();
+        let b: UserID = Faker.fake();
+
+        let mut payment: Payment = Faker.fake();
+        payment.amount = Money::new(1001, Currency::new("USD").unwrap());
+        payment.payers = vec![
+            Payer::new(a.clone(), Money::new(501, Currency::new("USD").unwrap())),
+            Payer::new(b.clone(), Money::new(500, Currency::new("USD").unwrap())),
+        ];
+
+        let paid = payment.paid_in(&jpy(1502), RoundingPolicy::ToCreditor);
+
+        assert_eq!(paid[0].user, a);
+        assert_eq!(paid[0].amount, jpy(752));
+        assert_eq!(paid[1].amount, jpy(750));
+    }
+
+    #[test]
+    fn replace_payer() {
+        let a: UserID = Faker.fake();
+        let b: UserID = Faker.fake();
+        let c: UserID = Faker.fake();
+
+        let mut payment: Payment = Faker.fake();
+        payment.payers = vec![
+            Payer::new(a.clone(), jpy(600)),
+            Payer::new(b.clone(), jpy(400)),
+        ];
+
+        payment.replace_payer(&a, &c).unwrap();
+        assert_eq!(
+            payment.payers,
+            vec![
+                Payer::new(c.clone(), jpy(600)),
+                Payer::new(b.clone(), jpy(400))
+            ]
+        );
+
+        payment.replace_payer(&c, &b).unwrap();
+        assert_eq!(payment.payers, vec![Payer::new(b, jpy(1000))]);
+    }
+
+    #[test]
+    fn sort_by_occurred_at() {
+        let mut payment1: Payment = Faker.fake();
+        let mut payment2: Payment = Faker.fake();
+        let mut payment3: Payment = Faker.fake();
+
+        // 09:00+09:00 (00:00Z) < 01:00Z
+        payment1.occurred_at = "2024-01-01T01:00:00Z".parse().unwrap();
+        payment2.occurred_at = "2024-01-01T09:00:00+09:00".parse().unwrap();
+        payment3.occurred_at = "2024-01-01T09:00:00+09:00".parse().unwrap();
+        payment2.created_at = "2024-01-03T00:00:00Z".parse().unwrap();
+        payment3.created_at = "2024-01-02T00:00:00Z".parse().unwrap();
+
+        let mut payments = vec![payment1.clone(), payment2.clone(), payment3.clone()];
+        sort_payments(&mut payments);
+
+        assert_eq!(payments, vec![payment3, payment2, payment1]);
     }
 }
@@ -0,0 +1,440 @@
+use crate::entities::{GroupID, NewPayment, PaymentID};
+use async_graphql::{types::ID, Enum, NewType, Union};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, Utc};
+use nanoid::nanoid;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[cfg(test)]
+use fake::{Dummy, Faker};
+#[cfg(test)]
+use rand::Rng;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, NewType)]
+pub struct RecurringPaymentID(pub ID);
+
+/// 家賃や光熱費のように繰り返す支払い。支払い日が来るたびに `template` から `Payment` を記録する
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(Dummy))]
+pub struct RecurringPayment {
+    pub id: RecurringPaymentID,
+    pub created_at: DateTime<Utc>,
+    pub group: GroupID,
+    pub schedule: Schedule,
+    /// 最初の支払い日の日時。2 回目以降も同じ時刻・タイムゾーンで支払ったものとする
+    pub starts_at: DateTime<FixedOffset>,
+    /// この日より後には支払わない
+    pub ends_on: Option<NaiveDate>,
+    /// 最後に `Payment` を記録した支払い日
+    pub last_occurrence: Option<NaiveDate>,
+    pub template: NewPayment,
+    /// テンプレートが正しくなくなるなどして記録が止まっている理由。記録できれば `None` に戻す
+    #[serde(default)]
+    pub stalled_reason: Option<String>,
+}
+
+/// `RecurringPayment` の変更。`None` のフィールドは変えない。
+/// 記録済みの支払い日と支払いの ID を引き継ぐので、`starts_at` は変えられない
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecurringPaymentUpdate {
+    pub schedule: Option<Schedule>,
+    pub ends_on: Option<NaiveDate>,
+    pub template: Option<NewPayment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Union)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+#[cfg_attr(test, derive(Dummy))]
+pub enum Schedule {
+    Weekly(WeeklySchedule),
+    Monthly(MonthlySchedule),
+    Custom(CustomSchedule),
+}
+
+/// 毎週 `weekday` に支払う
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(Dummy))]
+pub struct WeeklySchedule {
+    pub weekday: Weekday,
+}
+
+/// 毎月 `day` 日に支払う。その月にない日 (31 日など) は月末に支払う
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(Dummy))]
+pub struct MonthlySchedule {
+    #[cfg_attr(test, dummy(faker = "1..32"))]
+    pub day: u32,
+}
+
+/// cron の日付の部分と同じ `"日 月 曜日"` の規則 (例: `"1,15 * *"`, `"* * 1-5"`)。
+/// 曜日は 0 (と 7) が日曜日。日と曜日の両方を指定した場合はどちらかに当てはまれば支払う
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(Dummy))]
+pub struct CustomSchedule {
+    pub rule: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(test, derive(Dummy))]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+/// `CustomSchedule` の規則を解析したもの。各フィールドは当てはまる値のビット集合
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CronRule {
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl RecurringPaymentID {
+    pub fn new<T: ToString>(id: T) -> Self {
+        RecurringPaymentID(ID(id.to_string()))
+    }
+}
+
+impl fmt::Display for RecurringPaymentID {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+impl Dummy<Faker> for RecurringPaymentID {
+    fn dummy_with_rng<R: Rng + ?Sized>(config: &Faker, rng: &mut R) -> Self {
+        let s = String::dummy_with_rng(config, rng);
+        RecurringPaymentID(ID(s))
+    }
+}
+
+impl RecurringPayment {
+    /// 次の支払い日を探す範囲。2 月 29 日だけの規則でも見つかるよう 8 年とする
+    const SEARCH_DAYS: usize = 366 * 8;
+
+    /// 作成時にさかのぼれる日数。これより前から始めると、最初の記録で大量の支払いができてしまう
+    pub const MAX_BACKFILL_DAYS: i64 = 31;
+
+    pub fn new(
+        group: GroupID,
+        schedule: Schedule,
+        starts_at: DateTime<FixedOffset>,
+        ends_on: Option<NaiveDate>,
+        template: NewPayment,
+    ) -> Self {
+        Self {
+            id: RecurringPaymentID::new(nanoid!()),
+            created_at: Utc::now(),
+            group,
+            schedule,
+            starts_at,
+            ends_on,
+            last_occurrence: None,
+            template,
+            stalled_reason: None,
+        }
+    }
+
+    pub fn apply(&mut self, update: RecurringPaymentUpdate) {
+        if let Some(schedule) = update.schedule {
+            self.schedule = schedule;
+        }
+        if let Some(ends_on) = update.ends_on {
+            self.ends_on = Some(ends_on);
+        }
+        if let Some(template) = update.template {
+            self.template = template;
+        }
+    }
+
+    /// 規則が正しく、終わりの日が最初の支払い日より前でないこと
+    pub fn is_valid(&self) -> bool {
+        self.schedule.is_valid()
+            && self
+                .ends_on
+                .is_none_or(|ends_on| self.starts_at.date_naive() <= ends_on)
+    }
+
+    /// `starts_at` が `now` の `MAX_BACKFILL_DAYS` 日前より前か
+    pub fn starts_too_early(&self, now: DateTime<Utc>) -> bool {
+        self.starts_at < now - Duration::days(Self::MAX_BACKFILL_DAYS)
+    }
+
+    /// `last_occurrence` より後 (まだなければ `starts_at` 以降) の最初の支払い日
+    pub fn next_occurrence(&self) -> Option<NaiveDate> {
+        let from = match self.last_occurrence {
+            Some(last) => last.succ_opt()?,
+            None => self.starts_at.date_naive(),
+        };
+        from.iter_days()
+            .take(Self::SEARCH_DAYS)
+            .take_while(|date| self.ends_on.is_none_or(|ends_on| *date <= ends_on))
+            .find(|date| self.schedule.matches(*date))
+    }
+
+    /// 支払い日 `date` に支払った日時
+    pub fn occurred_at(&self, date: NaiveDate) -> Option<DateTime<FixedOffset>> {
+        date.and_time(self.starts_at.time())
+            .and_local_timezone(*self.starts_at.offset())
+            .single()
+    }
+
+    /// 支払い日 `date` に記録する `Payment` の ID。同じ支払い日を 2 度記録しないよう決まった値にする
+    pub fn payment_id(&self, date: NaiveDate) -> PaymentID {
+        PaymentID::new(format!("{}-{}", self.id, date.format("%Y%m%d")))
+    }
+
+    /// `now` までに来ていて、まだ記録していない支払い日
+    pub fn due(&self, now: DateTime<Utc>) -> Vec<NaiveDate> {
+        let mut due = vec![];
+        let mut cursor = self.clone();
+        while let Some(date) = cursor.next_occurrence() {
+            match cursor.occurred_at(date) {
+                Some(occurred_at) if occurred_at <= now => due.push(date),
+                _ => break,
+            }
+            cursor.last_occurrence = Some(date);
+        }
+        due
+    }
+}
+
+impl Schedule {
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Schedule::Weekly(_) => true,
+            Schedule::Monthly(schedule) => (1..=31).contains(&schedule.day),
+            Schedule::Custom(schedule) => CronRule::parse(&schedule.rule).is_some(),
+        }
+    }
+
+    pub fn matches(&self, date: NaiveDate) -> bool {
+        match self {
+            Schedule::Weekly(schedule) => date.weekday() == schedule.weekday.into(),
+            Schedule::Monthly(schedule) => date.day() == schedule.day.min(days_in_month(date)),
+            Schedule::Custom(schedule) => {
+                CronRule::parse(&schedule.rule).is_some_and(|rule| rule.matches(date))
+            }
+        }
+    }
+}
+
+impl From<Weekday> for chrono::Weekday {
+    fn from(value: Weekday) -> Self {
+        match value {
+            Weekday::Monday => chrono::Weekday::Mon,
+            Weekday::Tuesday => chrono::Weekday::Tue,
+            Weekday::Wednesday => chrono::Weekday::Wed,
+            Weekday::Thursday => chrono::Weekday::Thu,
+            Weekday::Friday => chrono::Weekday::Fri,
+            Weekday::Saturday => chrono::Weekday::Sat,
+            Weekday::Sunday => chrono::Weekday::Sun,
+        }
+    }
+}
+
+impl CronRule {
+    fn parse(rule: &str) -> Option<Self> {
+        let fields: Vec<&str> = rule.split_whitespace().collect();
+        let [days, months, weekdays] = fields.as_slice() else {
+            return None;
+        };
+
+        // 7 も日曜日
+        let weekday_bits = parse_field(weekdays, 0, 7)?;
+        Some(Self {
+            days: parse_field(days, 1, 31)?,
+            months: parse_field(months, 1, 12)?,
+            weekdays: (weekday_bits | weekday_bits >> 7) & 0x7f,
+            any_day: *days == "*",
+            any_weekday: *weekdays == "*",
+        })
+    }
+
+    fn matches(&self, date: NaiveDate) -> bool {
+        let day = self.days & (1 << date.day()) != 0;
+        let month = self.months & (1 << date.month()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+
+        // cron と同じく、日と曜日の両方を指定したらどちらかに当てはまればよい
+        let date = match (self.any_day, self.any_weekday) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        };
+        month && date
+    }
+}
+
+/// `*`, `*/n`, `a`, `a-b`, `a-b/n` をカンマで区切ったものを `min..=max` のビット集合にする
+fn parse_field(field: &str, min: u32, max: u32) -> Option<u64> {
+    let mut bits = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse().ok().filter(|step| 0 < *step)?),
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (start.parse().ok()?, end.parse().ok()?),
+                None => {
+                    let value = range.parse().ok()?;
+                    (value, value)
+                }
+            },
+        };
+        if start < min || max < end || end < start {
+            return None;
+        }
+        for value in (start..=end).step_by(step) {
+            bits |= 1 << value;
+        }
+    }
+    Some(bits)
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    let (year, month) = match date.month() {
+        12 => (date.year() + 1, 1),
+        month => (date.year(), month + 1),
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|first| first.pred_opt())
+        .map_or(31, |last| last.day())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fake::Fake;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn recurring(schedule: Schedule, starts_at: &str) -> RecurringPayment {
+        let mut recurring: RecurringPayment = Faker.fake();
+        recurring.schedule = schedule;
+        recurring.starts_at = starts_at.parse().unwrap();
+        recurring.ends_on = None;
+        recurring.last_occurrence = None;
+        recurring
+    }
+
+    #[test]
+    fn monthly_end_of_month() {
+        let recurring = recurring(
+            Schedule::Monthly(MonthlySchedule { day: 31 }),
+            "2024-01-31T09:00:00+09:00",
+        );
+
+        let due = recurring.due("2024-05-01T00:00:00Z".parse().unwrap());
+
+        assert_eq!(
+            due,
+            vec![
+                date(2024, 1, 31),
+                date(2024, 2, 29),
+                date(2024, 3, 31),
+                date(2024, 4, 30),
+            ]
+        );
+    }
+
+    #[test]
+    fn weekly() {
+        let mut recurring = recurring(
+            Schedule::Weekly(WeeklySchedule {
+                weekday: Weekday::Monday,
+            }),
+            "2024-01-01T00:00:00Z",
+        );
+        recurring.ends_on = Some(date(2024, 1, 20));
+
+        let due = recurring.due("2024-02-01T00:00:00Z".parse().unwrap());
+
+        assert_eq!(
+            due,
+            vec![date(2024, 1, 1), date(2024, 1, 8), date(2024, 1, 15)]
+        );
+    }
+
+    #[test]
+    fn due_respects_time_and_offset() {
+        let mut recurring = recurring(
+            Schedule::Monthly(MonthlySchedule { day: 1 }),
+            "2024-01-01T09:00:00+09:00",
+        );
+        recurring.last_occurrence = Some(date(2024, 1, 1));
+
+        // 2024-02-01T09:00:00+09:00 == 2024-02-01T00:00:00Z
+        assert!(recurring
+            .due("2024-01-31T23:59:59Z".parse().unwrap())
+            .is_empty());
+        assert_eq!(
+            recurring.due("2024-02-01T00:00:00Z".parse().unwrap()),
+            vec![date(2024, 2, 1)]
+        );
+    }
+
+    #[test]
+    fn custom() {
+        let rule = |rule: &str| {
+            Schedule::Custom(CustomSchedule {
+                rule: rule.to_string(),
+            })
+        };
+
+        assert!(rule("1,15 * *").matches(date(2024, 3, 15)));
+        assert!(!rule("1,15 * *").matches(date(2024, 3, 16)));
+        assert!(rule("* * 1-5").matches(date(2024, 3, 15)));
+        assert!(!rule("* * 1-5").matches(date(2024, 3, 16)));
+        assert!(rule("* */3 *").matches(date(2024, 4, 2)));
+        assert!(!rule("* */3 *").matches(date(2024, 3, 2)));
+        assert!(rule("* * 7").matches(date(2024, 3, 17)));
+        // 日と曜日の両方を指定したらどちらか
+        assert!(rule("1 * 0").matches(date(2024, 3, 1)));
+        assert!(rule("1 * 0").matches(date(2024, 3, 17)));
+
+        assert!(!rule("* *").is_valid());
+        assert!(!rule("0 * *").is_valid());
+        assert!(!rule("* 13 *").is_valid());
+        assert!(!rule("* * */0").is_valid());
+        assert!(!rule("5-1 * *").is_valid());
+    }
+
+    #[test]
+    fn leap_day() {
+        let recurring = recurring(
+            Schedule::Custom(CustomSchedule {
+                rule: "29 2 *".to_string(),
+            }),
+            "2024-03-01T00:00:00Z",
+        );
+
+        assert_eq!(recurring.next_occurrence(), Some(date(2028, 2, 29)));
+    }
+
+    #[test]
+    fn payment_id() {
+        let recurring: RecurringPayment = Faker.fake();
+
+        assert_eq!(
+            recurring.payment_id(date(2024, 1, 31)),
+            recurring.payment_id(date(2024, 1, 31))
+        );
+        assert_ne!(
+            recurring.payment_id(date(2024, 1, 31)),
+            recurring.payment_id(date(2024, 2, 29))
+        );
+    }
+}
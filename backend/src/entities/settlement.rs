@@ -0,0 +1,58 @@
+use crate::entities::{GroupID, Money, UserID};
+use async_graphql::{types::ID, NewType};
+use chrono::{DateTime, Utc};
+use nanoid::nanoid;
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+use fake::{Dummy, Faker};
+#[cfg(test)]
+use rand::Rng;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, NewType)]
+pub struct SettlementID(pub ID);
+
+/// 立て替え分の返済 (`from` から `to` への送金) の記録
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(Dummy))]
+pub struct Settlement {
+    pub id: SettlementID,
+    pub created_at: DateTime<Utc>,
+    pub group: GroupID,
+    pub from: UserID,
+    pub to: UserID,
+    pub amount: Money,
+}
+
+impl SettlementID {
+    pub fn new<T: ToString>(id: T) -> Self {
+        SettlementID(ID(id.to_string()))
+    }
+}
+
+impl ToString for SettlementID {
+    fn to_string(&self) -> String {
+        self.0 .0.to_string()
+    }
+}
+
+#[cfg(test)]
+impl Dummy<Faker> for SettlementID {
+    fn dummy_with_rng<R: Rng + ?Sized>(config: &Faker, rng: &mut R) -> Self {
+        let s = String::dummy_with_rng(config, rng);
+        SettlementID(ID(s))
+    }
+}
+
+impl Settlement {
+    pub fn new(group: GroupID, from: UserID, to: UserID, amount: Money) -> Self {
+        Self {
+            id: SettlementID::new(nanoid!()),
+            created_at: Utc::now(),
+            group,
+            from,
+            to,
+            amount,
+        }
+    }
+}
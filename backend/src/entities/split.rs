@@ -0,0 +1,1023 @@
+use crate::entities::{Money, MoneyError, UserID};
+use async_graphql::{types::ID, Enum, NewType, Union};
+use nanoid::nanoid;
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+use fake::{Dummy, Fake, Faker};
+#[cfg(test)]
+use rand::Rng;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Union)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum Split {
+    Equal(EqualSplit),
+    Shares(SharesSplit),
+    Percentages(PercentagesSplit),
+    Exact(ExactSplit),
+    Itemized(ItemizedSplit),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EqualSplit {
+    pub debtors: Vec<UserID>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SharesSplit {
+    pub debtors: Vec<ShareDebtor>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShareDebtor {
+    pub user: UserID,
+    pub shares: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PercentagesSplit {
+    pub debtors: Vec<PercentageDebtor>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PercentageDebtor {
+    pub user: UserID,
+    pub percent: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExactSplit {
+    pub debtors: Vec<ExactDebtor>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExactDebtor {
+    pub user: UserID,
+    pub amount: Money,
+}
+
+/// レシートの明細ごとに分ける。税とチップは各自の明細の合計に比例して負担する
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemizedSplit {
+    pub items: Vec<PaymentItem>,
+    pub tax: Money,
+    pub tip: Money,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, NewType)]
+pub struct PaymentItemID(pub ID);
+
+/// 明細の 1 行。`price * quantity` を `consumers` で等分する
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(Dummy))]
+pub struct PaymentItem {
+    pub id: PaymentItemID,
+    pub name: String,
+    pub price: Money,
+    #[cfg_attr(test, dummy(faker = "1..5"))]
+    pub quantity: u32,
+    #[cfg_attr(test, dummy(faker = "(Faker, 1..5)"))]
+    pub consumers: Vec<UserID>,
+}
+
+/// `PaymentItem` の部分更新 (`None` のフィールドは変更しない)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(test, derive(Dummy))]
+pub struct PaymentItemUpdate {
+    pub name: Option<String>,
+    pub price: Option<Money>,
+    pub quantity: Option<u32>,
+    pub consumers: Option<Vec<UserID>>,
+}
+
+/// 割り切れずに余った最小通貨単位を誰が負担するか
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(test, derive(Dummy))]
+pub enum RoundingPolicy {
+    /// 余りはすべて立て替えた人が負担する
+    ToCreditor,
+    /// 余りはすべて先頭の債務者が負担する
+    ToFirstDebtor,
+    /// `UserID` の昇順に1単位ずつ負担する
+    #[default]
+    RoundRobin,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Share {
+    pub user: UserID,
+    pub amount: Money,
+}
+
+impl PaymentItemID {
+    pub fn new<T: ToString>(id: T) -> Self {
+        PaymentItemID(ID(id.to_string()))
+    }
+}
+
+impl ToString for PaymentItemID {
+    fn to_string(&self) -> String {
+        self.0 .0.to_string()
+    }
+}
+
+#[cfg(test)]
+impl Dummy<Faker> for PaymentItemID {
+    fn dummy_with_rng<R: Rng + ?Sized>(config: &Faker, rng: &mut R) -> Self {
+        let s = String::dummy_with_rng(config, rng);
+        PaymentItemID(ID(s))
+    }
+}
+
+impl PaymentItem {
+    pub fn new(name: String, price: Money, quantity: u32, consumers: Vec<UserID>) -> Self {
+        Self {
+            id: PaymentItemID::new(nanoid!()),
+            name,
+            price,
+            quantity,
+            consumers,
+        }
+    }
+
+    pub fn apply(&mut self, update: PaymentItemUpdate) {
+        if let Some(name) = update.name {
+            self.name = name;
+        }
+        if let Some(price) = update.price {
+            self.price = price;
+        }
+        if let Some(quantity) = update.quantity {
+            self.quantity = quantity;
+        }
+        if let Some(consumers) = update.consumers {
+            self.consumers = consumers;
+        }
+    }
+
+    pub fn total(&self) -> Result<Money, MoneyError> {
+        let amount = self
+            .price
+            .amount
+            .checked_mul(i64::from(self.quantity))
+            .ok_or(MoneyError::Overflow)?;
+        Ok(Money::new(amount, self.price.currency.clone()))
+    }
+}
+
+impl ItemizedSplit {
+    /// 明細・税・チップの合計 (支払い全体の金額になるべき値)
+    pub fn total(&self) -> Result<Money, MoneyError> {
+        self.items
+            .iter()
+            .try_fold(self.tax.checked_add(&self.tip)?, |total, item| {
+                total.checked_add(&item.total()?)
+            })
+    }
+}
+
+#[cfg(test)]
+impl Dummy<Faker> for Split {
+    fn dummy_with_rng<R: Rng + ?Sized>(_: &Faker, rng: &mut R) -> Self {
+        Split::Equal(EqualSplit {
+            debtors: (Faker, 1..10).fake_with_rng(rng),
+        })
+    }
+}
+
+impl Split {
+    pub fn equal(debtors: Vec<UserID>) -> Self {
+        Split::Equal(EqualSplit { debtors })
+    }
+
+    pub fn debtors(&self) -> Vec<UserID> {
+        match self {
+            Split::Equal(split) => split.debtors.clone(),
+            Split::Shares(split) => split.debtors.iter().map(|d| d.user.clone()).collect(),
+            Split::Percentages(split) => split.debtors.iter().map(|d| d.user.clone()).collect(),
+            Split::Exact(split) => split.debtors.iter().map(|d| d.user.clone()).collect(),
+            Split::Itemized(split) => {
+                let mut debtors: Vec<UserID> = vec![];
+                for consumer in split.items.iter().flat_map(|item| item.consumers.iter()) {
+                    if !debtors.contains(consumer) {
+                        debtors.push(consumer.clone());
+                    }
+                }
+                debtors
+            }
+        }
+    }
+
+    pub fn is_valid(&self, amount: &Money) -> bool {
+        match self {
+            Split::Equal(_) => true,
+            Split::Shares(split) => split.debtors.iter().all(|d| 0 < d.shares),
+            Split::Percentages(split) => {
+                split
+                    .debtors
+                    .iter()
+                    .map(|d| u64::from(d.percent))
+                    .sum::<u64>()
+                    == 100
+            }
+            Split::Exact(split) => {
+                split
+                    .debtors
+                    .iter()
+                    .all(|d| d.amount.currency == amount.currency && 0 <= d.amount.amount)
+                    && split
+                        .debtors
+                        .iter()
+                        .map(|d| i128::from(d.amount.amount))
+                        .sum::<i128>()
+                        == i128::from(amount.amount)
+            }
+            Split::Itemized(split) => {
+                split.tax.currency == amount.currency
+                    && split.tip.currency == amount.currency
+                    && 0 <= split.tax.amount
+                    && 0 <= split.tip.amount
+                    && split.items.iter().all(|item| {
+                        item.price.currency == amount.currency
+                            && 0 <= item.price.amount
+                            && 0 < item.quantity
+                            && !item.consumers.is_empty()
+                            && item
+                                .consumers
+                                .iter()
+                                .enumerate()
+                                .all(|(i, consumer)| !item.consumers[..i].contains(consumer))
+                    })
+                    && split.total().is_ok_and(|total| &total == amount)
+            }
+        }
+    }
+
+    /// `user` を債務者から外した分け方を返す。
+    /// 外した分は残りの債務者が負担の比で (全員 0 なら均等に) 引き受け、
+    /// 債務者がいなくなった場合だけ全額を立て替えた人が負担する
+    pub fn without(&self, user: &UserID, creditor: &UserID) -> Split {
+        if !self.debtors().contains(user) {
+            return self.clone();
+        }
+        match self {
+            Split::Equal(split) => {
+                let debtors: Vec<UserID> = split
+                    .debtors
+                    .iter()
+                    .filter(|d| *d != user)
+                    .cloned()
+                    .collect();
+                if debtors.is_empty() {
+                    Split::equal(vec![creditor.clone()])
+                } else {
+                    Split::equal(debtors)
+                }
+            }
+            Split::Shares(split) => {
+                let mut debtors: Vec<ShareDebtor> = split
+                    .debtors
+                    .iter()
+                    .filter(|d| &d.user != user)
+                    .cloned()
+                    .collect();
+                if debtors.is_empty() {
+                    debtors.push(ShareDebtor {
+                        user: creditor.clone(),
+                        shares: 1,
+                    });
+                }
+                Split::Shares(SharesSplit { debtors })
+            }
+            Split::Percentages(split) => {
+                let percent: u32 = split
+                    .debtors
+                    .iter()
+                    .filter(|d| &d.user == user)
+                    .map(|d| d.percent)
+                    .sum();
+                let mut debtors: Vec<PercentageDebtor> = split
+                    .debtors
+                    .iter()
+                    .filter(|d| &d.user != user)
+                    .cloned()
+                    .collect();
+                if debtors.is_empty() {
+                    debtors.push(PercentageDebtor {
+                        user: creditor.clone(),
+                        percent,
+                    });
+                } else {
+                    let weights: Vec<i64> = debtors.iter().map(|d| i64::from(d.percent)).collect();
+                    for (debtor, extra) in
+                        debtors.iter_mut().zip(spread(i64::from(percent), &weights))
+                    {
+                        // `extra` は 0 以上 `percent` 以下
+                        debtor.percent += extra as u32;
+                    }
+                }
+                Split::Percentages(PercentagesSplit { debtors })
+            }
+            Split::Exact(split) => {
+                let mut removed = split.debtors.iter().filter(|d| &d.user == user);
+                let Some(first) = removed.next() else {
+                    return self.clone();
+                };
+                let amount = Money::new(
+                    first.amount.amount + removed.map(|d| d.amount.amount).sum::<i64>(),
+                    first.amount.currency.clone(),
+                );
+                let mut debtors: Vec<ExactDebtor> = split
+                    .debtors
+                    .iter()
+                    .filter(|d| &d.user != user)
+                    .cloned()
+                    .collect();
+                if debtors.is_empty() {
+                    debtors.push(ExactDebtor {
+                        user: creditor.clone(),
+                        amount,
+                    });
+                } else {
+                    let weights: Vec<i64> = debtors.iter().map(|d| d.amount.amount).collect();
+                    for (debtor, extra) in debtors.iter_mut().zip(spread(amount.amount, &weights)) {
+                        debtor.amount.amount += extra;
+                    }
+                }
+                Split::Exact(ExactSplit { debtors })
+            }
+            Split::Itemized(split) => Split::Itemized(ItemizedSplit {
+                items: split
+                    .items
+                    .iter()
+                    .map(|item| {
+                        let mut item = item.clone();
+                        item.consumers.retain(|consumer| consumer != user);
+                        if item.consumers.is_empty() {
+                            item.consumers.push(creditor.clone());
+                        }
+                        item
+                    })
+                    .collect(),
+                tax: split.tax.clone(),
+                tip: split.tip.clone(),
+            }),
+        }
+    }
+
+    /// 債務者の `from` を `to` に置き換える。`to` がすでに債務者なら負担分をまとめる
+    pub fn replace(&self, from: &UserID, to: &UserID) -> Split {
+        match self {
+            Split::Equal(split) => Split::equal(replace(&split.debtors, from, to)),
+            Split::Shares(split) => Split::Shares(SharesSplit {
+                debtors: replace(&split.debtors, from, to),
+            }),
+            Split::Percentages(split) => Split::Percentages(PercentagesSplit {
+                debtors: replace(&split.debtors, from, to),
+            }),
+            Split::Exact(split) => Split::Exact(ExactSplit {
+                debtors: replace(&split.debtors, from, to),
+            }),
+            Split::Itemized(split) => Split::Itemized(ItemizedSplit {
+                items: split
+                    .items
+                    .iter()
+                    .map(|item| PaymentItem {
+                        consumers: replace(&item.consumers, from, to),
+                        ..item.clone()
+                    })
+                    .collect(),
+                tax: split.tax.clone(),
+                tip: split.tip.clone(),
+            }),
+        }
+    }
+
+    /// 各債務者の負担額を返す (合計は常に `amount` に一致する)
+    pub fn shares(
+        &self,
+        amount: &Money,
+        creditor: &UserID,
+        rounding: RoundingPolicy,
+    ) -> Vec<Share> {
+        let weights: Vec<(UserID, i64)> = match self {
+            Split::Equal(split) => split.debtors.iter().map(|d| (d.clone(), 1)).collect(),
+            Split::Shares(split) => split
+                .debtors
+                .iter()
+                .map(|d| (d.user.clone(), i64::from(d.shares)))
+                .collect(),
+            Split::Percentages(split) => split
+                .debtors
+                .iter()
+                .map(|d| (d.user.clone(), i64::from(d.percent)))
+                .collect(),
+            Split::Exact(split) => {
+                return split
+                    .debtors
+                    .iter()
+                    .map(|d| Share {
+                        user: d.user.clone(),
+                        amount: d.amount.clone(),
+                    })
+                    .collect()
+            }
+            Split::Itemized(split) => {
+                // 明細ごとに消費者で等分した額を重みとして、税とチップを含む全額を配分する
+                let mut weights: Vec<(UserID, i64)> = vec![];
+                for item in &split.items {
+                    let total = item
+                        .total()
+                        .unwrap_or_else(|_| Money::zero(amount.currency.clone()));
+                    let consumers: Vec<(UserID, i64)> =
+                        item.consumers.iter().map(|c| (c.clone(), 1)).collect();
+                    for share in allocate(&total, creditor, &consumers, rounding) {
+                        match weights.iter_mut().find(|(user, _)| user == &share.user) {
+                            Some((_, weight)) => *weight += share.amount.amount,
+                            None => weights.push((share.user, share.amount.amount)),
+                        }
+                    }
+                }
+                weights
+            }
+        };
+        allocate(amount, creditor, &weights, rounding)
+    }
+}
+
+trait Debtor: Clone {
+    fn user(&self) -> &UserID;
+    fn user_mut(&mut self) -> &mut UserID;
+    fn absorb(&mut self, other: &Self);
+}
+
+impl Debtor for UserID {
+    fn user(&self) -> &UserID {
+        self
+    }
+
+    fn user_mut(&mut self) -> &mut UserID {
+        self
+    }
+
+    fn absorb(&mut self, _: &Self) {}
+}
+
+impl Debtor for ShareDebtor {
+    fn user(&self) -> &UserID {
+        &self.user
+    }
+
+    fn user_mut(&mut self) -> &mut UserID {
+        &mut self.user
+    }
+
+    fn absorb(&mut self, other: &Self) {
+        self.shares += other.shares;
+    }
+}
+
+impl Debtor for PercentageDebtor {
+    fn user(&self) -> &UserID {
+        &self.user
+    }
+
+    fn user_mut(&mut self) -> &mut UserID {
+        &mut self.user
+    }
+
+    fn absorb(&mut self, other: &Self) {
+        self.percent += other.percent;
+    }
+}
+
+impl Debtor for ExactDebtor {
+    fn user(&self) -> &UserID {
+        &self.user
+    }
+
+    fn user_mut(&mut self) -> &mut UserID {
+        &mut self.user
+    }
+
+    fn absorb(&mut self, other: &Self) {
+        self.amount.amount += other.amount.amount;
+    }
+}
+
+fn replace<T: Debtor>(debtors: &[T], from: &UserID, to: &UserID) -> Vec<T> {
+    let mut replaced: Vec<T> = vec![];
+    for debtor in debtors {
+        let mut debtor = debtor.clone();
+        if debtor.user() == from {
+            *debtor.user_mut() = to.clone();
+        }
+        match replaced.iter_mut().find(|d| d.user() == debtor.user()) {
+            Some(existing) => existing.absorb(&debtor),
+            None => replaced.push(debtor),
+        }
+    }
+    replaced
+}
+
+/// 0 以上の `amount` を 0 以上の `weights` の比で分ける。
+/// 重みがすべて 0 なら均等に分け、割り切れない分は先頭から 1 ずつ足す
+fn spread(amount: i64, weights: &[i64]) -> Vec<i64> {
+    let weights: Vec<i128> = if weights.iter().all(|weight| *weight <= 0) {
+        vec![1; weights.len()]
+    } else {
+        weights
+            .iter()
+            .map(|weight| i128::from((*weight).max(0)))
+            .collect()
+    };
+    let total: i128 = weights.iter().sum();
+    if total == 0 {
+        return vec![];
+    }
+
+    let mut parts: Vec<i64> = weights
+        .iter()
+        .map(|weight| (i128::from(amount) * weight / total) as i64)
+        .collect();
+    let mut remainder = amount - parts.iter().sum::<i64>();
+    for part in parts.iter_mut() {
+        if remainder <= 0 {
+            break;
+        }
+        *part += 1;
+        remainder -= 1;
+    }
+    parts
+}
+
+pub(crate) fn allocate(
+    amount: &Money,
+    creditor: &UserID,
+    weights: &[(UserID, i64)],
+    rounding: RoundingPolicy,
+) -> Vec<Share> {
+    let total: i128 = weights.iter().map(|(_, weight)| i128::from(*weight)).sum();
+    if total <= 0 {
+        return vec![Share {
+            user: creditor.clone(),
+            amount: amount.clone(),
+        }];
+    }
+
+    let mut shares: Vec<Share> = weights
+        .iter()
+        .map(|(user, weight)| Share {
+            user: user.clone(),
+            amount: Money::new(
+                (i128::from(amount.amount) * i128::from(*weight) / total) as i64,
+                amount.currency.clone(),
+            ),
+        })
+        .collect();
+
+    let remainder = amount.amount - shares.iter().map(|s| s.amount.amount).sum::<i64>();
+    if remainder == 0 {
+        return shares;
+    }
+
+    match rounding {
+        RoundingPolicy::ToCreditor => {
+            match shares.iter_mut().find(|share| &share.user == creditor) {
+                Some(share) => share.amount.amount += remainder,
+                None => shares.push(Share {
+                    user: creditor.clone(),
+                    amount: Money::new(remainder, amount.currency.clone()),
+                }),
+            }
+        }
+        RoundingPolicy::ToFirstDebtor => {
+            if let Some(index) = weights.iter().position(|(_, weight)| 0 < *weight) {
+                shares[index].amount.amount += remainder;
+            }
+        }
+        RoundingPolicy::RoundRobin => {
+            let mut indices: Vec<usize> = (0..weights.len())
+                .filter(|index| 0 < weights[*index].1)
+                .collect();
+            indices.sort_by_key(|index| weights[*index].0.to_string());
+            // 負の額では端数も負になるので、端数の絶対値の回数だけ端数の符号の向きに 1 ずつ配る
+            let count = usize::try_from(remainder.unsigned_abs()).unwrap_or(usize::MAX);
+            for index in indices.into_iter().cycle().take(count) {
+                shares[index].amount.amount += remainder.signum();
+            }
+        }
+    }
+
+    shares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::Currency;
+
+    fn jpy(amount: i64) -> Money {
+        Money::new(amount, Currency::default())
+    }
+
+    fn amounts(shares: &[Share]) -> Vec<i64> {
+        shares.iter().map(|share| share.amount.amount).collect()
+    }
+
+    #[test]
+    fn equal() {
+        let split = Split::equal(vec![Faker.fake(), Faker.fake(), Faker.fake()]);
+
+        let shares = split.shares(&jpy(1000), &Faker.fake(), RoundingPolicy::ToFirstDebtor);
+
+        assert_eq!(amounts(&shares), vec![334, 333, 333]);
+    }
+
+    #[test]
+    fn rounding_to_creditor() {
+        let creditor: UserID = Faker.fake();
+        let split = Split::equal(vec![Faker.fake(), Faker.fake(), Faker.fake()]);
+
+        let shares = split.shares(&jpy(1000), &creditor, RoundingPolicy::ToCreditor);
+
+        assert_eq!(amounts(&shares), vec![333, 333, 333, 1]);
+        assert_eq!(shares[3].user, creditor);
+    }
+
+    #[test]
+    fn rounding_to_creditor_in_debtors() {
+        let creditor: UserID = Faker.fake();
+        let split = Split::equal(vec![Faker.fake(), creditor.clone(), Faker.fake()]);
+
+        let shares = split.shares(&jpy(1000), &creditor, RoundingPolicy::ToCreditor);
+
+        assert_eq!(amounts(&shares), vec![333, 334, 333]);
+    }
+
+    #[test]
+    fn rounding_round_robin() {
+        let split = Split::equal(vec![
+            UserID::new("c"),
+            UserID::new("b"),
+            UserID::new("a"),
+            UserID::new("d"),
+        ]);
+
+        let shares = split.shares(&jpy(1003), &Faker.fake(), RoundingPolicy::RoundRobin);
+
+        assert_eq!(amounts(&shares), vec![251, 251, 251, 250]);
+    }
+
+    #[test]
+    fn round_robin_negative() {
+        let split = Split::equal(vec![Faker.fake(), Faker.fake(), Faker.fake()]);
+
+        let shares = split.shares(&jpy(-1001), &Faker.fake(), RoundingPolicy::RoundRobin);
+
+        assert_eq!(
+            shares.iter().map(|share| share.amount.amount).sum::<i64>(),
+            -1001
+        );
+        let mut amounts = amounts(&shares);
+        amounts.sort();
+        assert_eq!(amounts, vec![-334, -334, -333]);
+    }
+
+    #[test]
+    fn equal_without_debtors() {
+        let creditor: UserID = Faker.fake();
+        let split = Split::equal(vec![]);
+
+        let shares = split.shares(&jpy(1000), &creditor, RoundingPolicy::ToFirstDebtor);
+
+        assert_eq!(shares.len(), 1);
+        assert_eq!(shares[0].user, creditor);
+        assert_eq!(shares[0].amount, jpy(1000));
+    }
+
+    #[test]
+    fn shares() {
+        let split = Split::Shares(SharesSplit {
+            debtors: vec![
+                ShareDebtor {
+                    user: Faker.fake(),
+                    shares: 2,
+                },
+                ShareDebtor {
+                    user: Faker.fake(),
+                    shares: 1,
+                },
+            ],
+        });
+
+        assert!(split.is_valid(&jpy(1000)));
+        assert_eq!(
+            amounts(&split.shares(&jpy(1000), &Faker.fake(), RoundingPolicy::ToFirstDebtor)),
+            vec![667, 333]
+        );
+    }
+
+    #[test]
+    fn shares_invalid() {
+        let split = Split::Shares(SharesSplit {
+            debtors: vec![ShareDebtor {
+                user: Faker.fake(),
+                shares: 0,
+            }],
+        });
+
+        assert!(!split.is_valid(&jpy(1000)));
+    }
+
+    #[test]
+    fn percentages() {
+        let split = Split::Percentages(PercentagesSplit {
+            debtors: vec![
+                PercentageDebtor {
+                    user: Faker.fake(),
+                    percent: 50,
+                },
+                PercentageDebtor {
+                    user: Faker.fake(),
+                    percent: 30,
+                },
+                PercentageDebtor {
+                    user: Faker.fake(),
+                    percent: 20,
+                },
+            ],
+        });
+
+        assert!(split.is_valid(&jpy(999)));
+        assert_eq!(
+            amounts(&split.shares(&jpy(999), &Faker.fake(), RoundingPolicy::ToFirstDebtor)),
+            vec![501, 299, 199]
+        );
+    }
+
+    #[test]
+    fn percentages_invalid() {
+        let split = Split::Percentages(PercentagesSplit {
+            debtors: vec![PercentageDebtor {
+                user: Faker.fake(),
+                percent: 99,
+            }],
+        });
+
+        assert!(!split.is_valid(&jpy(1000)));
+    }
+
+    #[test]
+    fn exact() {
+        let split = Split::Exact(ExactSplit {
+            debtors: vec![
+                ExactDebtor {
+                    user: Faker.fake(),
+                    amount: jpy(700),
+                },
+                ExactDebtor {
+                    user: Faker.fake(),
+                    amount: jpy(300),
+                },
+            ],
+        });
+
+        assert!(split.is_valid(&jpy(1000)));
+        assert!(!split.is_valid(&jpy(1001)));
+        assert_eq!(
+            amounts(&split.shares(&jpy(1000), &Faker.fake(), RoundingPolicy::ToFirstDebtor)),
+            vec![700, 300]
+        );
+    }
+
+    #[test]
+    fn without_equal() {
+        let user: UserID = Faker.fake();
+        let other: UserID = Faker.fake();
+        let creditor: UserID = Faker.fake();
+        let split = Split::equal(vec![user.clone(), other.clone()]);
+
+        assert_eq!(
+            split.without(&user, &creditor),
+            Split::equal(vec![other.clone()])
+        );
+        assert_eq!(
+            Split::equal(vec![user.clone()]).without(&user, &creditor),
+            Split::equal(vec![creditor])
+        );
+    }
+
+    #[test]
+    fn without_percentages() {
+        let user: UserID = Faker.fake();
+        let creditor: UserID = Faker.fake();
+        let split = Split::Percentages(PercentagesSplit {
+            debtors: vec![
+                PercentageDebtor {
+                    user: user.clone(),
+                    percent: 30,
+                },
+                PercentageDebtor {
+                    user: creditor.clone(),
+                    percent: 70,
+                },
+            ],
+        });
+
+        let without = split.without(&user, &creditor);
+
+        assert!(without.is_valid(&jpy(1000)));
+        assert_eq!(without.debtors(), vec![creditor]);
+    }
+
+    #[test]
+    fn without_exact() {
+        let user: UserID = Faker.fake();
+        let other: UserID = Faker.fake();
+        let creditor: UserID = Faker.fake();
+        let split = Split::Exact(ExactSplit {
+            debtors: vec![
+                ExactDebtor {
+                    user: user.clone(),
+                    amount: jpy(700),
+                },
+                ExactDebtor {
+                    user: other.clone(),
+                    amount: jpy(300),
+                },
+            ],
+        });
+
+        let without = split.without(&user, &creditor);
+
+        assert!(without.is_valid(&jpy(1000)));
+        assert_eq!(
+            amounts(&without.shares(&jpy(1000), &creditor, RoundingPolicy::default())),
+            vec![1000]
+        );
+        assert_eq!(without.debtors(), vec![other]);
+    }
+
+    #[test]
+    fn without_redistributes() {
+        let user: UserID = Faker.fake();
+        let a: UserID = Faker.fake();
+        let b: UserID = Faker.fake();
+        let creditor: UserID = Faker.fake();
+        let split = Split::Percentages(PercentagesSplit {
+            debtors: vec![
+                PercentageDebtor {
+                    user: user.clone(),
+                    percent: 25,
+                },
+                PercentageDebtor {
+                    user: a.clone(),
+                    percent: 50,
+                },
+                PercentageDebtor {
+                    user: b.clone(),
+                    percent: 25,
+                },
+            ],
+        });
+
+        let without = split.without(&user, &creditor);
+
+        assert!(without.is_valid(&jpy(1000)));
+        assert_eq!(without.debtors(), vec![a.clone(), b.clone()]);
+        assert_eq!(
+            amounts(&without.shares(&jpy(1000), &creditor, RoundingPolicy::default())),
+            vec![670, 330]
+        );
+
+        let split = Split::Exact(ExactSplit {
+            debtors: vec![
+                ExactDebtor {
+                    user: a.clone(),
+                    amount: jpy(0),
+                },
+                ExactDebtor {
+                    user: b.clone(),
+                    amount: jpy(0),
+                },
+                ExactDebtor {
+                    user: user.clone(),
+                    amount: jpy(1001),
+                },
+            ],
+        });
+
+        let without = split.without(&user, &creditor);
+
+        assert!(without.is_valid(&jpy(1001)));
+        assert_eq!(
+            amounts(&without.shares(&jpy(1001), &creditor, RoundingPolicy::default())),
+            vec![501, 500]
+        );
+    }
+
+    #[test]
+    fn replace() {
+        let from: UserID = Faker.fake();
+        let to: UserID = Faker.fake();
+        let other: UserID = Faker.fake();
+
+        assert_eq!(
+            Split::equal(vec![from.clone(), other.clone()]).replace(&from, &to),
+            Split::equal(vec![to.clone(), other.clone()])
+        );
+        assert_eq!(
+            Split::equal(vec![from.clone(), to.clone()]).replace(&from, &to),
+            Split::equal(vec![to.clone()])
+        );
+
+        let split = Split::Exact(ExactSplit {
+            debtors: vec![
+                ExactDebtor {
+                    user: from.clone(),
+                    amount: jpy(700),
+                },
+                ExactDebtor {
+                    user: to.clone(),
+                    amount: jpy(300),
+                },
+            ],
+        });
+        let replaced = split.replace(&from, &to);
+        assert!(replaced.is_valid(&jpy(1000)));
+        assert_eq!(replaced.debtors(), vec![to]);
+    }
+
+    #[test]
+    fn itemized() {
+        let a: UserID = Faker.fake();
+        let b: UserID = Faker.fake();
+        let split = Split::Itemized(ItemizedSplit {
+            items: vec![
+                PaymentItem::new("beer".to_string(), jpy(500), 2, vec![a.clone()]),
+                PaymentItem::new(
+                    "pizza".to_string(),
+                    jpy(1000),
+                    1,
+                    vec![a.clone(), b.clone()],
+                ),
+            ],
+            tax: jpy(200),
+            tip: jpy(100),
+        });
+
+        // a: 1000 + 500 = 1500, b: 500 -> 税とチップ 300 を 3:1 で配分
+        assert!(split.is_valid(&jpy(2300)));
+        assert!(!split.is_valid(&jpy(2000)));
+        assert_eq!(split.debtors(), vec![a.clone(), b.clone()]);
+        assert_eq!(
+            amounts(&split.shares(&jpy(2300), &a, RoundingPolicy::ToFirstDebtor)),
+            vec![1725, 575]
+        );
+    }
+
+    #[test]
+    fn itemized_invalid() {
+        let a: UserID = Faker.fake();
+        let split = Split::Itemized(ItemizedSplit {
+            items: vec![PaymentItem::new(
+                "beer".to_string(),
+                jpy(500),
+                1,
+                vec![a.clone(), a.clone()],
+            )],
+            tax: jpy(0),
+            tip: jpy(0),
+        });
+
+        assert!(!split.is_valid(&jpy(500)));
+    }
+
+    #[test]
+    fn shares_sum_to_amount() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let amount = jpy(rng.gen_range(1..1_000_000));
+            let split = Split::Shares(SharesSplit {
+                debtors: (0..rng.gen_range(1..10))
+                    .map(|_| ShareDebtor {
+                        user: Faker.fake(),
+                        shares: rng.gen_range(1..10),
+                    })
+                    .collect(),
+            });
+
+            let rounding: RoundingPolicy = Faker.fake();
+
+            let sum: i64 = split
+                .shares(&amount, &Faker.fake(), rounding)
+                .iter()
+                .map(|share| share.amount.amount)
+                .sum();
+
+            assert_eq!(sum, amount.amount);
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use crate::entities::{Balance, Money, UserID};
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transfer {
+    pub from: UserID,
+    pub to: UserID,
+    pub amount: Money,
+}
+
+/// 残高を精算するための送金リストを作る
+///
+/// 同額の債務者・債権者を先に組み合わせ、残りは最大の債務者から最大の債権者へ送金することで、
+/// 送金回数を高々 (残高が0でない人数 - 1) 回に抑える。
+/// 金額が同じ場合は `UserID` の昇順で選ぶため、結果は入力の順序に依存しない。
+pub fn settlement_plan(balances: &[Balance]) -> Vec<Transfer> {
+    let currency = match balances.first() {
+        Some(balance) => balance.net.currency.clone(),
+        None => return vec![],
+    };
+
+    let mut debtors: Vec<(UserID, i64)> = balances
+        .iter()
+        .filter(|balance| balance.net.amount < 0)
+        .map(|balance| (balance.user.clone(), -balance.net.amount))
+        .collect();
+    let mut creditors: Vec<(UserID, i64)> = balances
+        .iter()
+        .filter(|balance| balance.net.amount > 0)
+        .map(|balance| (balance.user.clone(), balance.net.amount))
+        .collect();
+
+    sort(&mut debtors);
+    sort(&mut creditors);
+
+    let mut transfers = Vec::new();
+
+    let mut i = 0;
+    while i < debtors.len() {
+        let amount = debtors[i].1;
+        if let Some(j) = creditors.iter().position(|(_, credit)| *credit == amount) {
+            let (from, _) = debtors.remove(i);
+            let (to, _) = creditors.remove(j);
+            transfers.push(Transfer {
+                from,
+                to,
+                amount: Money::new(amount, currency.clone()),
+            });
+        } else {
+            i += 1;
+        }
+    }
+
+    while !debtors.is_empty() && !creditors.is_empty() {
+        let amount = debtors[0].1.min(creditors[0].1);
+        transfers.push(Transfer {
+            from: debtors[0].0.clone(),
+            to: creditors[0].0.clone(),
+            amount: Money::new(amount, currency.clone()),
+        });
+
+        debtors[0].1 -= amount;
+        creditors[0].1 -= amount;
+        debtors.retain(|(_, debt)| *debt != 0);
+        creditors.retain(|(_, credit)| *credit != 0);
+        sort(&mut debtors);
+        sort(&mut creditors);
+    }
+
+    transfers
+}
+
+fn sort(entries: &mut [(UserID, i64)]) {
+    entries.sort_by(|(a_user, a), (b_user, b)| match b.cmp(a) {
+        Ordering::Equal => a_user.to_string().cmp(&b_user.to_string()),
+        ordering => ordering,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::Currency;
+    use fake::{Fake, Faker};
+    use rand::{seq::SliceRandom, Rng};
+
+    fn balance(user: &UserID, net: i64) -> Balance {
+        let mut balance = Balance::new(user.clone(), Currency::default());
+        balance.net = Money::new(net, Currency::default());
+        balance
+    }
+
+    fn random_balances() -> Vec<Balance> {
+        let mut rng = rand::thread_rng();
+        let count = rng.gen_range(1..20);
+
+        let mut nets: Vec<i64> = (0..count).map(|_| rng.gen_range(-10_000..10_000)).collect();
+        nets.push(-nets.iter().sum::<i64>());
+
+        nets.iter()
+            .map(|net| balance(&Faker.fake(), *net))
+            .collect()
+    }
+
+    fn apply(balances: &[Balance], transfers: &[Transfer]) -> Vec<i64> {
+        balances
+            .iter()
+            .map(|balance| {
+                let sent: i64 = transfers
+                    .iter()
+                    .filter(|transfer| transfer.from == balance.user)
+                    .map(|transfer| transfer.amount.amount)
+                    .sum();
+                let received: i64 = transfers
+                    .iter()
+                    .filter(|transfer| transfer.to == balance.user)
+                    .map(|transfer| transfer.amount.amount)
+                    .sum();
+                balance.net.amount + sent - received
+            })
+            .collect()
+    }
+
+    #[test]
+    fn empty() {
+        assert!(settlement_plan(&[]).is_empty());
+    }
+
+    #[test]
+    fn already_settled() {
+        let a: UserID = Faker.fake();
+        let b: UserID = Faker.fake();
+
+        let balances = vec![balance(&a, 0), balance(&b, 0)];
+
+        assert!(settlement_plan(&balances).is_empty());
+    }
+
+    #[test]
+    fn exact_match_first() {
+        let a = UserID::new("a");
+        let b = UserID::new("b");
+        let c = UserID::new("c");
+        let d = UserID::new("d");
+
+        let balances = vec![
+            balance(&a, -300),
+            balance(&b, -700),
+            balance(&c, 300),
+            balance(&d, 700),
+        ];
+
+        let transfers = settlement_plan(&balances);
+
+        assert_eq!(transfers.len(), 2);
+        assert!(transfers.contains(&Transfer {
+            from: a,
+            to: c,
+            amount: Money::new(300, Currency::default()),
+        }));
+        assert!(transfers.contains(&Transfer {
+            from: b,
+            to: d,
+            amount: Money::new(700, Currency::default()),
+        }));
+    }
+
+    #[test]
+    fn tie_breaking() {
+        let a = UserID::new("a");
+        let b = UserID::new("b");
+        let c = UserID::new("c");
+
+        let balances = vec![balance(&b, -100), balance(&a, -100), balance(&c, 200)];
+
+        let transfers = settlement_plan(&balances);
+
+        assert_eq!(transfers[0].from, a);
+        assert_eq!(transfers[1].from, b);
+    }
+
+    #[test]
+    fn property_zero_out() {
+        for _ in 0..1000 {
+            let balances = random_balances();
+
+            let transfers = settlement_plan(&balances);
+
+            assert!(apply(&balances, &transfers).iter().all(|net| *net == 0));
+            assert!(transfers.iter().all(|transfer| transfer.amount.amount > 0));
+        }
+    }
+
+    #[test]
+    fn property_transfer_count() {
+        for _ in 0..1000 {
+            let balances = random_balances();
+            let nonzero = balances
+                .iter()
+                .filter(|balance| !balance.net.is_zero())
+                .count();
+
+            let transfers = settlement_plan(&balances);
+
+            assert!(transfers.len() <= nonzero.saturating_sub(1));
+        }
+    }
+
+    #[test]
+    fn property_deterministic() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let balances = random_balances();
+            let mut shuffled = balances.clone();
+            shuffled.shuffle(&mut rng);
+
+            assert_eq!(settlement_plan(&balances), settlement_plan(&shuffled));
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::entities::Claims;
 use async_graphql::{types::ID, NewType};
+use nanoid::nanoid;
 use serde::{Deserialize, Serialize};
 
 #[cfg(test)]
@@ -18,9 +19,20 @@ pub struct User {
 }
 
 impl UserID {
+    const GUEST_PREFIX: &'static str = "guest|";
+
     pub fn new<T: ToString>(id: T) -> Self {
         UserID(ID(id.to_string()))
     }
+
+    /// Auth0 の `sub` と衝突しないゲスト用の ID を発行する
+    pub fn guest() -> Self {
+        UserID::new(format!("{}{}", Self::GUEST_PREFIX, nanoid!()))
+    }
+
+    pub fn is_guest(&self) -> bool {
+        self.0 .0.starts_with(Self::GUEST_PREFIX)
+    }
 }
 
 impl ToString for UserID {
@@ -44,4 +56,12 @@ impl User {
             name,
         }
     }
+
+    /// アカウントを持たない、特定のグループ内だけの参加者
+    pub fn guest(name: String) -> Self {
+        Self {
+            id: UserID::guest(),
+            name,
+        }
+    }
 }
@@ -0,0 +1,14 @@
+use crate::repositories::Clock;
+use chrono::{DateTime, Utc};
+use shaku::Component;
+
+/// システムの時計
+#[derive(Debug, Default, Component)]
+#[shaku(interface = Clock)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
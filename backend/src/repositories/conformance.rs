@@ -0,0 +1,720 @@
+//! すべての `Repository` の実装が同じように振る舞うことを確かめるテスト。
+//! 実装ごとに `conformance_tests!(<Repository を作る式>)` でテストを生成する
+
+use crate::{
+    entities::{
+        normalize_tags, Category, Group, GroupID, Invite, Payment, PaymentFilter, RecurringPayment,
+        Settlement, User, UserID,
+    },
+    repositories::{Operation, Repository, RepositoryError, UnitOfWork},
+};
+use fake::{Fake, Faker};
+
+/// `$repository` で作った `Repository` に対して、このモジュールのテストをすべて生成する
+macro_rules! conformance_tests {
+    ($repository:expr) => {
+        $crate::repositories::conformance_tests!(
+            @tests $repository;
+            create_group,
+            create_group_duplicate,
+            get_group_not_found,
+            delete_group,
+            delete_group_not_found,
+            update_group,
+            update_group_not_found,
+            add_participant,
+            add_participant_not_found,
+            get_groups_by_user,
+            create_invite,
+            create_invite_duplicate,
+            delete_invite,
+            get_invites_by_group,
+            create_payment,
+            create_payment_duplicate,
+            delete_payment,
+            delete_payment_not_found,
+            update_payment,
+            update_payment_not_found,
+            get_payments_by_group,
+            get_payments_by_filter,
+            create_recurring_payment,
+            delete_recurring_payment,
+            update_recurring_payment,
+            get_recurring_payments_by_group,
+            get_recurring_payments,
+            create_settlement,
+            delete_settlement,
+            update_settlement,
+            get_settlements_by_group,
+            create_user,
+            create_user_duplicate,
+            get_user_not_found,
+            delete_user,
+            delete_user_not_found,
+            commit,
+            commit_delete_by_group,
+            commit_rollback
+        );
+    };
+    (@tests $repository:expr; $($test:ident),*) => {
+        $(
+            #[tokio::test]
+            async fn $test() {
+                let repository = $repository;
+                $crate::repositories::conformance::$test(&repository).await;
+            }
+        )*
+    };
+}
+
+pub(crate) use conformance_tests;
+
+pub async fn create_group(repository: &impl Repository) {
+    let group: Group = Faker.fake();
+
+    let create = repository.create_group(group).await.unwrap();
+    let get = repository.get_group(&create.id).await.unwrap();
+
+    assert_eq!(Some(create), get);
+}
+
+pub async fn create_group_duplicate(repository: &impl Repository) {
+    let group: Group = Faker.fake();
+
+    let create = repository.create_group(group).await.unwrap();
+    let mut duplicate: Group = Faker.fake();
+    duplicate.id = create.id.clone();
+
+    assert!(matches!(
+        repository.create_group(duplicate).await,
+        Err(RepositoryError::Conflict(_))
+    ));
+    assert_eq!(
+        repository.get_group(&create.id).await.unwrap(),
+        Some(create)
+    );
+}
+
+pub async fn get_group_not_found(repository: &impl Repository) {
+    let id: GroupID = Faker.fake();
+
+    assert_eq!(repository.get_group(&id).await.unwrap(), None);
+}
+
+pub async fn delete_group(repository: &impl Repository) {
+    let group: Group = Faker.fake();
+
+    let create = repository.create_group(group).await.unwrap();
+    repository.delete_group(&create.id).await.unwrap();
+    let delete = repository.get_group(&create.id).await.unwrap();
+
+    assert_eq!(delete, None);
+}
+
+pub async fn delete_group_not_found(repository: &impl Repository) {
+    let id: GroupID = Faker.fake();
+
+    assert!(matches!(
+        repository.delete_group(&id).await,
+        Err(RepositoryError::NotFound)
+    ));
+}
+
+pub async fn update_group(repository: &impl Repository) {
+    let group: Group = Faker.fake();
+
+    let mut create = repository.create_group(group).await.unwrap();
+    create.title = Faker.fake();
+    let update = repository.update_group(create.clone()).await.unwrap();
+    let get = repository.get_group(&create.id).await.unwrap();
+
+    assert_eq!(update, create);
+    assert_eq!(get, Some(create));
+}
+
+pub async fn update_group_not_found(repository: &impl Repository) {
+    let group: Group = Faker.fake();
+
+    assert!(matches!(
+        repository.update_group(group.clone()).await,
+        Err(RepositoryError::NotFound)
+    ));
+    assert_eq!(repository.get_group(&group.id).await.unwrap(), None);
+}
+
+pub async fn add_participant(repository: &impl Repository) {
+    let group: Group = Faker.fake();
+    let user: UserID = Faker.fake();
+
+    let create = repository.create_group(group).await.unwrap();
+    repository.add_participant(&create.id, &user).await.unwrap();
+    let add = repository.add_participant(&create.id, &user).await.unwrap();
+    let get = repository.get_group(&create.id).await.unwrap().unwrap();
+
+    let mut participants = create.participants.clone();
+    participants.push(user);
+    assert_eq!(add.participants, participants);
+    assert_eq!(get, add);
+}
+
+pub async fn add_participant_not_found(repository: &impl Repository) {
+    let group: Group = Faker.fake();
+    let user: UserID = Faker.fake();
+
+    assert!(matches!(
+        repository.add_participant(&group.id, &user).await,
+        Err(RepositoryError::NotFound)
+    ));
+}
+
+pub async fn get_groups_by_user(repository: &impl Repository) {
+    let user: UserID = Faker.fake();
+
+    let mut group1: Group = Faker.fake();
+    let mut group2: Group = Faker.fake();
+    let group3: Group = Faker.fake();
+
+    group1.participants.push(user.clone());
+    group2.participants.push(user.clone());
+
+    let _ = repository.create_group(group1.clone()).await.unwrap();
+    let _ = repository.create_group(group2.clone()).await.unwrap();
+    let _ = repository.create_group(group3).await.unwrap();
+
+    let groups = repository.get_groups_by_user(&user).await.unwrap();
+
+    assert_eq!(groups, vec![group1, group2]);
+}
+
+pub async fn create_invite(repository: &impl Repository) {
+    let invite: Invite = Faker.fake();
+
+    let create = repository.create_invite(invite).await.unwrap();
+    let get = repository.get_invite(&create.token).await.unwrap();
+
+    assert_eq!(Some(create), get);
+}
+
+pub async fn create_invite_duplicate(repository: &impl Repository) {
+    let invite: Invite = Faker.fake();
+
+    let create = repository.create_invite(invite).await.unwrap();
+    let mut duplicate: Invite = Faker.fake();
+    duplicate.token = create.token.clone();
+
+    assert!(matches!(
+        repository.create_invite(duplicate).await,
+        Err(RepositoryError::Conflict(_))
+    ));
+}
+
+pub async fn delete_invite(repository: &impl Repository) {
+    let invite: Invite = Faker.fake();
+
+    let create = repository.create_invite(invite).await.unwrap();
+    repository.delete_invite(&create.token).await.unwrap();
+    let delete = repository.get_invite(&create.token).await.unwrap();
+
+    assert_eq!(delete, None);
+    assert!(matches!(
+        repository.delete_invite(&create.token).await,
+        Err(RepositoryError::NotFound)
+    ));
+}
+
+pub async fn get_invites_by_group(repository: &impl Repository) {
+    let mut invite1: Invite = Faker.fake();
+    let mut invite2: Invite = Faker.fake();
+    let invite3: Invite = Faker.fake();
+
+    let group: GroupID = Faker.fake();
+    invite1.group = group.clone();
+    invite2.group = group.clone();
+
+    repository.create_invite(invite1.clone()).await.unwrap();
+    repository.create_invite(invite2.clone()).await.unwrap();
+    repository.create_invite(invite3).await.unwrap();
+
+    let get = repository.get_invites_by_group(&group).await.unwrap();
+
+    assert_eq!(vec![invite1, invite2], get);
+}
+
+pub async fn create_payment(repository: &impl Repository) {
+    let payment: Payment = Faker.fake();
+
+    let create = repository.create_payment(payment).await.unwrap();
+    let get = repository.get_payment(&create.id).await.unwrap();
+
+    assert_eq!(Some(create), get);
+}
+
+pub async fn create_payment_duplicate(repository: &impl Repository) {
+    let payment: Payment = Faker.fake();
+
+    let create = repository.create_payment(payment).await.unwrap();
+    let mut duplicate: Payment = Faker.fake();
+    duplicate.id = create.id.clone();
+
+    assert!(matches!(
+        repository.create_payment(duplicate).await,
+        Err(RepositoryError::Conflict(_))
+    ));
+    assert_eq!(
+        repository.get_payment(&create.id).await.unwrap(),
+        Some(create)
+    );
+}
+
+pub async fn delete_payment(repository: &impl Repository) {
+    let payment: Payment = Faker.fake();
+
+    let create = repository.create_payment(payment).await.unwrap();
+    repository.delete_payment(&create.id).await.unwrap();
+    let delete = repository.get_payment(&create.id).await.unwrap();
+
+    assert_eq!(delete, None);
+}
+
+pub async fn delete_payment_not_found(repository: &impl Repository) {
+    let payment: Payment = Faker.fake();
+
+    assert!(matches!(
+        repository.delete_payment(&payment.id).await,
+        Err(RepositoryError::NotFound)
+    ));
+}
+
+pub async fn update_payment(repository: &impl Repository) {
+    let payment: Payment = Faker.fake();
+
+    let mut create = repository.create_payment(payment).await.unwrap();
+    create.title = Faker.fake();
+    let update = repository.update_payment(create.clone()).await.unwrap();
+    let get = repository.get_payment(&create.id).await.unwrap();
+
+    assert_eq!(update, create);
+    assert_eq!(get, Some(create));
+}
+
+pub async fn update_payment_not_found(repository: &impl Repository) {
+    let payment: Payment = Faker.fake();
+
+    assert!(matches!(
+        repository.update_payment(payment.clone()).await,
+        Err(RepositoryError::NotFound)
+    ));
+    assert_eq!(repository.get_payment(&payment.id).await.unwrap(), None);
+}
+
+/// 作成した順に返す
+pub async fn get_payments_by_group(repository: &impl Repository) {
+    let mut payment1: Payment = Faker.fake();
+    let mut payment2: Payment = Faker.fake();
+    let mut payment3: Payment = Faker.fake();
+    let payment4: Payment = Faker.fake();
+
+    let group: GroupID = Faker.fake();
+    payment1.group = group.clone();
+    payment2.group = group.clone();
+    payment3.group = group.clone();
+
+    repository.create_payment(payment2.clone()).await.unwrap();
+    repository.create_payment(payment3.clone()).await.unwrap();
+    repository.create_payment(payment1.clone()).await.unwrap();
+    repository.create_payment(payment4).await.unwrap();
+
+    let get = repository.get_payments_by_group(&group).await.unwrap();
+
+    assert_eq!(vec![payment2, payment3, payment1], get);
+}
+
+pub async fn get_payments_by_filter(repository: &impl Repository) {
+    let group: GroupID = Faker.fake();
+
+    let mut payment1: Payment = Faker.fake();
+    let mut payment2: Payment = Faker.fake();
+    let mut payment3: Payment = Faker.fake();
+    let mut payment4: Payment = Faker.fake();
+    payment1.group = group.clone();
+    payment1.category = Category::Food;
+    payment1.tags = vec!["dinner".to_string()];
+    payment2.group = group.clone();
+    payment2.category = Category::Food;
+    payment2.tags = vec![];
+    payment3.group = group.clone();
+    payment3.category = Category::Transport;
+    payment3.tags = normalize_tags(vec!["Dinner".to_string(), " taxi".to_string()]);
+    payment4.category = Category::Food;
+
+    repository.create_payment(payment1.clone()).await.unwrap();
+    repository.create_payment(payment2.clone()).await.unwrap();
+    repository.create_payment(payment3.clone()).await.unwrap();
+    repository.create_payment(payment4).await.unwrap();
+
+    let food = PaymentFilter {
+        category: Some(Category::Food),
+        tag: None,
+    };
+    let dinner = PaymentFilter {
+        category: None,
+        tag: Some("dinner".to_string()),
+    };
+    let food_dinner = PaymentFilter {
+        category: Some(Category::Food),
+        tag: Some("dinner".to_string()),
+    };
+    // 利用者が入力したままの条件も、保存したタグと同じ形にそろえてから渡す
+    let taxi = PaymentFilter {
+        category: None,
+        tag: Some(" Taxi".to_string()),
+    }
+    .normalized();
+
+    let all = repository
+        .get_payments_by_filter(&group, &PaymentFilter::default())
+        .await
+        .unwrap();
+    let food = repository
+        .get_payments_by_filter(&group, &food)
+        .await
+        .unwrap();
+    let dinner = repository
+        .get_payments_by_filter(&group, &dinner)
+        .await
+        .unwrap();
+    let food_dinner = repository
+        .get_payments_by_filter(&group, &food_dinner)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        all,
+        vec![payment1.clone(), payment2.clone(), payment3.clone()]
+    );
+    assert_eq!(food, vec![payment1.clone(), payment2]);
+    assert_eq!(dinner, vec![payment1.clone(), payment3.clone()]);
+    assert_eq!(food_dinner, vec![payment1]);
+    assert_eq!(
+        repository
+            .get_payments_by_filter(&group, &taxi)
+            .await
+            .unwrap(),
+        vec![payment3]
+    );
+}
+
+pub async fn create_recurring_payment(repository: &impl Repository) {
+    let recurring: RecurringPayment = Faker.fake();
+
+    let create = repository
+        .create_recurring_payment(recurring)
+        .await
+        .unwrap();
+    let get = repository.get_recurring_payment(&create.id).await.unwrap();
+
+    assert_eq!(Some(create), get);
+}
+
+pub async fn delete_recurring_payment(repository: &impl Repository) {
+    let recurring: RecurringPayment = Faker.fake();
+
+    let create = repository
+        .create_recurring_payment(recurring)
+        .await
+        .unwrap();
+    repository
+        .delete_recurring_payment(&create.id)
+        .await
+        .unwrap();
+    let delete = repository.get_recurring_payment(&create.id).await.unwrap();
+
+    assert_eq!(delete, None);
+    assert!(matches!(
+        repository.delete_recurring_payment(&create.id).await,
+        Err(RepositoryError::NotFound)
+    ));
+}
+
+pub async fn update_recurring_payment(repository: &impl Repository) {
+    let recurring: RecurringPayment = Faker.fake();
+
+    let mut create = repository
+        .create_recurring_payment(recurring)
+        .await
+        .unwrap();
+    create.last_occurrence = Faker.fake();
+    create.stalled_reason = Faker.fake();
+    let update = repository
+        .update_recurring_payment(create.clone())
+        .await
+        .unwrap();
+    let get = repository.get_recurring_payment(&create.id).await.unwrap();
+
+    assert_eq!(update, create);
+    assert_eq!(get, Some(create));
+}
+
+pub async fn get_recurring_payments_by_group(repository: &impl Repository) {
+    let mut recurring1: RecurringPayment = Faker.fake();
+    let mut recurring2: RecurringPayment = Faker.fake();
+    let recurring3: RecurringPayment = Faker.fake();
+
+    let group: GroupID = Faker.fake();
+    recurring1.group = group.clone();
+    recurring2.group = group.clone();
+
+    repository
+        .create_recurring_payment(recurring1.clone())
+        .await
+        .unwrap();
+    repository
+        .create_recurring_payment(recurring2.clone())
+        .await
+        .unwrap();
+    repository
+        .create_recurring_payment(recurring3)
+        .await
+        .unwrap();
+
+    let get = repository
+        .get_recurring_payments_by_group(&group)
+        .await
+        .unwrap();
+
+    assert_eq!(vec![recurring1, recurring2], get);
+}
+
+pub async fn get_recurring_payments(repository: &impl Repository) {
+    let recurring1: RecurringPayment = Faker.fake();
+    let recurring2: RecurringPayment = Faker.fake();
+
+    repository
+        .create_recurring_payment(recurring1.clone())
+        .await
+        .unwrap();
+    repository
+        .create_recurring_payment(recurring2.clone())
+        .await
+        .unwrap();
+
+    // 他のテストが作ったものも含む
+    let get = repository.get_recurring_payments().await.unwrap();
+
+    assert!(get.contains(&recurring1));
+    assert!(get.contains(&recurring2));
+}
+
+pub async fn create_settlement(repository: &impl Repository) {
+    let settlement: Settlement = Faker.fake();
+
+    let create = repository.create_settlement(settlement).await.unwrap();
+    let get = repository.get_settlement(&create.id).await.unwrap();
+
+    assert_eq!(Some(create), get);
+}
+
+pub async fn delete_settlement(repository: &impl Repository) {
+    let settlement: Settlement = Faker.fake();
+
+    let create = repository.create_settlement(settlement).await.unwrap();
+    repository.delete_settlement(&create.id).await.unwrap();
+    let delete = repository.get_settlement(&create.id).await.unwrap();
+
+    assert_eq!(delete, None);
+    assert!(matches!(
+        repository.delete_settlement(&create.id).await,
+        Err(RepositoryError::NotFound)
+    ));
+}
+
+pub async fn update_settlement(repository: &impl Repository) {
+    let settlement: Settlement = Faker.fake();
+
+    let mut create = repository.create_settlement(settlement).await.unwrap();
+    create.amount = Faker.fake();
+    let update = repository.update_settlement(create.clone()).await.unwrap();
+    let get = repository.get_settlement(&create.id).await.unwrap();
+
+    assert_eq!(update, create);
+    assert_eq!(get, Some(create));
+}
+
+pub async fn get_settlements_by_group(repository: &impl Repository) {
+    let mut settlement1: Settlement = Faker.fake();
+    let mut settlement2: Settlement = Faker.fake();
+    let settlement3: Settlement = Faker.fake();
+
+    let group: GroupID = Faker.fake();
+    settlement1.group = group.clone();
+    settlement2.group = group.clone();
+
+    repository
+        .create_settlement(settlement1.clone())
+        .await
+        .unwrap();
+    repository
+        .create_settlement(settlement2.clone())
+        .await
+        .unwrap();
+    repository.create_settlement(settlement3).await.unwrap();
+
+    let get = repository.get_settlements_by_group(&group).await.unwrap();
+
+    assert_eq!(vec![settlement1, settlement2], get);
+}
+
+pub async fn create_user(repository: &impl Repository) {
+    let user: User = Faker.fake();
+
+    let create = repository.create_user(user).await.unwrap();
+    let get = repository.get_user(&create.id).await.unwrap();
+
+    assert_eq!(Some(create), get);
+}
+
+pub async fn create_user_duplicate(repository: &impl Repository) {
+    let user: User = Faker.fake();
+
+    let create = repository.create_user(user).await.unwrap();
+    let mut duplicate: User = Faker.fake();
+    duplicate.id = create.id.clone();
+
+    assert!(matches!(
+        repository.create_user(duplicate).await,
+        Err(RepositoryError::Conflict(_))
+    ));
+    assert_eq!(repository.get_user(&create.id).await.unwrap(), Some(create));
+}
+
+pub async fn get_user_not_found(repository: &impl Repository) {
+    let id: UserID = Faker.fake();
+
+    assert_eq!(repository.get_user(&id).await.unwrap(), None);
+}
+
+pub async fn delete_user(repository: &impl Repository) {
+    let user: User = Faker.fake();
+
+    let create = repository.create_user(user).await.unwrap();
+    repository.delete_user(&create.id).await.unwrap();
+    let delete = repository.get_user(&create.id).await.unwrap();
+
+    assert_eq!(delete, None);
+}
+
+pub async fn delete_user_not_found(repository: &impl Repository) {
+    let id: UserID = Faker.fake();
+
+    assert!(matches!(
+        repository.delete_user(&id).await,
+        Err(RepositoryError::NotFound)
+    ));
+}
+
+pub async fn commit(repository: &impl Repository) {
+    let group = repository.create_group(Faker.fake()).await.unwrap();
+    let mut payment: Payment = Faker.fake();
+    payment.group = group.id.clone();
+    let payment = repository.create_payment(payment).await.unwrap();
+    let mut settlement: Settlement = Faker.fake();
+    settlement.group = group.id.clone();
+
+    let mut update = group.clone();
+    update.title = Faker.fake();
+    let work = UnitOfWork {
+        operations: vec![
+            Operation::DeletePayment(payment.id.clone()),
+            Operation::CreateSettlement(settlement.clone()),
+            Operation::UpdateGroup(update.clone()),
+        ],
+    };
+    repository.commit(work).await.unwrap();
+
+    assert_eq!(repository.get_payment(&payment.id).await.unwrap(), None);
+    assert_eq!(
+        repository.get_settlement(&settlement.id).await.unwrap(),
+        Some(settlement)
+    );
+    assert_eq!(repository.get_group(&group.id).await.unwrap(), Some(update));
+}
+
+pub async fn commit_delete_by_group(repository: &impl Repository) {
+    let group = repository.create_group(Faker.fake()).await.unwrap();
+    let mut payment: Payment = Faker.fake();
+    payment.group = group.id.clone();
+    let payment = repository.create_payment(payment).await.unwrap();
+    let mut settlement: Settlement = Faker.fake();
+    settlement.group = group.id.clone();
+    let settlement = repository.create_settlement(settlement).await.unwrap();
+    let mut invite: Invite = Faker.fake();
+    invite.group = group.id.clone();
+    let invite = repository.create_invite(invite).await.unwrap();
+    let mut recurring: RecurringPayment = Faker.fake();
+    recurring.group = group.id.clone();
+    let recurring = repository
+        .create_recurring_payment(recurring)
+        .await
+        .unwrap();
+    let other = repository.create_payment(Faker.fake()).await.unwrap();
+
+    let work = UnitOfWork {
+        operations: vec![
+            Operation::DeletePaymentsByGroup(group.id.clone()),
+            Operation::DeleteSettlementsByGroup(group.id.clone()),
+            Operation::DeleteInvitesByGroup(group.id.clone()),
+            Operation::DeleteRecurringPaymentsByGroup(group.id.clone()),
+            // 1 つもなくてもエラーにしない
+            Operation::DeletePaymentsByGroup(group.id.clone()),
+            Operation::DeleteGroup(group.id.clone()),
+        ],
+    };
+    repository.commit(work).await.unwrap();
+
+    assert_eq!(repository.get_payment(&payment.id).await.unwrap(), None);
+    assert_eq!(
+        repository.get_settlement(&settlement.id).await.unwrap(),
+        None
+    );
+    assert_eq!(repository.get_invite(&invite.token).await.unwrap(), None);
+    assert_eq!(
+        repository
+            .get_recurring_payment(&recurring.id)
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(repository.get_group(&group.id).await.unwrap(), None);
+    assert_eq!(
+        repository.get_payment(&other.id).await.unwrap(),
+        Some(other)
+    );
+}
+
+pub async fn commit_rollback(repository: &impl Repository) {
+    let group = repository.create_group(Faker.fake()).await.unwrap();
+    let mut payment: Payment = Faker.fake();
+    payment.group = group.id.clone();
+    let payment = repository.create_payment(payment).await.unwrap();
+    let user: User = Faker.fake();
+
+    let work = UnitOfWork {
+        operations: vec![
+            Operation::DeletePayment(payment.id.clone()),
+            Operation::CreateUser(user.clone()),
+            Operation::DeleteGroup(group.id.clone()),
+            Operation::DeleteGroup(group.id.clone()),
+        ],
+    };
+    assert!(matches!(
+        repository.commit(work).await,
+        Err(RepositoryError::NotFound)
+    ));
+
+    assert_eq!(
+        repository.get_payment(&payment.id).await.unwrap(),
+        Some(payment)
+    );
+    assert_eq!(repository.get_user(&user.id).await.unwrap(), None);
+    assert_eq!(repository.get_group(&group.id).await.unwrap(), Some(group));
+}
@@ -0,0 +1,23 @@
+use thiserror::Error;
+
+/// `Repository` の各メソッドが返すエラー。保存先ごとのエラーはこれに変換する
+#[derive(Debug, Error)]
+pub enum RepositoryError {
+    /// 更新・削除しようとしたものが存在しない
+    #[error("not found")]
+    NotFound,
+
+    /// 同じキーのものがすでに存在する
+    #[error("duplicate key: {0}")]
+    Conflict(String),
+
+    /// 保存先で起きたその他のエラー
+    #[error("backend error: {0}")]
+    Backend(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl RepositoryError {
+    pub fn backend(error: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self::Backend(Box::new(error))
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{
+    entities::{Group, GroupID, UserID},
+    repositories::{
+        memory::{InMemory, Keyed},
+        GroupRepository, RepositoryError,
+    },
+};
+use async_trait::async_trait;
+
+impl Keyed for Group {
+    type Key = GroupID;
+
+    fn key(&self) -> &GroupID {
+        &self.id
+    }
+}
+
+#[async_trait]
+impl GroupRepository for InMemory {
+    async fn create_group(&self, group: Group) -> Result<Group, RepositoryError> {
+        Ok(self.groups.insert(group)?)
+    }
+
+    async fn delete_group(&self, id: &GroupID) -> Result<(), RepositoryError> {
+        Ok(self.groups.remove(id)?)
+    }
+
+    async fn update_group(&self, group: Group) -> Result<Group, RepositoryError> {
+        Ok(self.groups.replace(group)?)
+    }
+
+    async fn get_group(&self, id: &GroupID) -> Result<Option<Group>, RepositoryError> {
+        Ok(self.groups.get(id))
+    }
+
+    async fn get_groups_by_user(&self, id: &UserID) -> Result<Vec<Group>, RepositoryError> {
+        Ok(self.groups.filter(|group| group.participants.contains(id)))
+    }
+
+    async fn add_participant(&self, id: &GroupID, user: &UserID) -> Result<Group, RepositoryError> {
+        let mut groups = self.groups.write();
+        let group = groups
+            .iter_mut()
+            .find(|group| &group.id == id)
+            .ok_or(RepositoryError::NotFound)?;
+        if !group.participants.contains(user) {
+            group.participants.push(user.clone());
+        }
+        Ok(group.clone())
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{
+    entities::{GroupID, Invite, InviteToken},
+    repositories::{
+        memory::{InMemory, Keyed},
+        InviteRepository, RepositoryError,
+    },
+};
+use async_trait::async_trait;
+
+impl Keyed for Invite {
+    type Key = InviteToken;
+
+    fn key(&self) -> &InviteToken {
+        &self.token
+    }
+}
+
+#[async_trait]
+impl InviteRepository for InMemory {
+    async fn create_invite(&self, invite: Invite) -> Result<Invite, RepositoryError> {
+        Ok(self.invites.insert(invite)?)
+    }
+
+    async fn delete_invite(&self, token: &InviteToken) -> Result<(), RepositoryError> {
+        Ok(self.invites.remove(token)?)
+    }
+
+    async fn get_invite(&self, token: &InviteToken) -> Result<Option<Invite>, RepositoryError> {
+        Ok(self.invites.get(token))
+    }
+
+    async fn get_invites_by_group(&self, group: &GroupID) -> Result<Vec<Invite>, RepositoryError> {
+        Ok(self.invites.filter(|invite| &invite.group == group))
+    }
+}
@@ -0,0 +1,128 @@
+mod group;
+mod invite;
+mod payment;
+mod recurring;
+mod settlement;
+mod transaction;
+mod user;
+
+use crate::{
+    entities::{Group, Invite, Payment, RecurringPayment, Settlement, User},
+    repositories::{Repository, RepositoryError},
+};
+use shaku::Component;
+use std::sync::{PoisonError, RwLock, RwLockWriteGuard};
+use thiserror::Error;
+
+/// プロセスのメモリ上に保存する `Repository`。テストや手元での開発で MongoDB の代わりに使う
+#[derive(Debug, Default, Component)]
+#[shaku(interface = Repository)]
+pub struct InMemory {
+    groups: Table<Group>,
+    invites: Table<Invite>,
+    payments: Table<Payment>,
+    recurring_payments: Table<RecurringPayment>,
+    settlements: Table<Settlement>,
+    users: Table<User>,
+}
+
+#[derive(Debug, Error)]
+pub enum InMemoryError {
+    #[error("duplicate key: {0}")]
+    DuplicateKey(String),
+
+    #[error("not found")]
+    NotFound,
+}
+
+impl From<InMemoryError> for RepositoryError {
+    fn from(error: InMemoryError) -> Self {
+        match error {
+            InMemoryError::DuplicateKey(key) => RepositoryError::Conflict(key),
+            InMemoryError::NotFound => RepositoryError::NotFound,
+        }
+    }
+}
+
+/// `Table` に保存できる、一意なキーを持つもの
+trait Keyed: Clone {
+    type Key: PartialEq + ToString;
+
+    fn key(&self) -> &Self::Key;
+}
+
+/// 挿入した順に並んだ行。`Mongo` のコレクションと同じく、キーが一意になるようにする
+#[derive(Debug)]
+struct Table<T> {
+    rows: RwLock<Vec<T>>,
+}
+
+impl<T> Default for Table<T> {
+    fn default() -> Self {
+        Self {
+            rows: RwLock::new(vec![]),
+        }
+    }
+}
+
+impl<T: Keyed> Table<T> {
+    fn write(&self) -> RwLockWriteGuard<'_, Vec<T>> {
+        self.rows.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn insert(&self, row: T) -> Result<T, InMemoryError> {
+        insert_row(&mut self.write(), row)
+    }
+
+    fn remove(&self, key: &T::Key) -> Result<(), InMemoryError> {
+        remove_row(&mut self.write(), key)
+    }
+
+    fn replace(&self, row: T) -> Result<T, InMemoryError> {
+        replace_row(&mut self.write(), row)
+    }
+
+    fn get(&self, key: &T::Key) -> Option<T> {
+        let rows = self.rows.read().unwrap_or_else(PoisonError::into_inner);
+        rows.iter().find(|r| r.key() == key).cloned()
+    }
+
+    fn filter(&self, predicate: impl Fn(&T) -> bool) -> Vec<T> {
+        let rows = self.rows.read().unwrap_or_else(PoisonError::into_inner);
+        rows.iter().filter(|r| predicate(r)).cloned().collect()
+    }
+}
+
+fn insert_row<T: Keyed>(rows: &mut Vec<T>, row: T) -> Result<T, InMemoryError> {
+    if rows.iter().any(|r| r.key() == row.key()) {
+        return Err(InMemoryError::DuplicateKey(row.key().to_string()));
+    }
+    rows.push(row.clone());
+    Ok(row)
+}
+
+fn remove_row<T: Keyed>(rows: &mut Vec<T>, key: &T::Key) -> Result<(), InMemoryError> {
+    let index = rows
+        .iter()
+        .position(|r| r.key() == key)
+        .ok_or(InMemoryError::NotFound)?;
+    rows.remove(index);
+    Ok(())
+}
+
+fn replace_row<T: Keyed>(rows: &mut [T], row: T) -> Result<T, InMemoryError> {
+    let target = rows
+        .iter_mut()
+        .find(|r| r.key() == row.key())
+        .ok_or(InMemoryError::NotFound)?;
+    *target = row.clone();
+    Ok(row)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::conformance_tests;
+
+    conformance_tests!(InMemory::default());
+}
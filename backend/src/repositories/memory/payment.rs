@@ -0,0 +1,52 @@
+use crate::{
+    entities::{GroupID, Payment, PaymentFilter, PaymentID},
+    repositories::{
+        memory::{InMemory, Keyed},
+        PaymentRepository, RepositoryError,
+    },
+};
+use async_trait::async_trait;
+
+impl Keyed for Payment {
+    type Key = PaymentID;
+
+    fn key(&self) -> &PaymentID {
+        &self.id
+    }
+}
+
+#[async_trait]
+impl PaymentRepository for InMemory {
+    async fn create_payment(&self, payment: Payment) -> Result<Payment, RepositoryError> {
+        Ok(self.payments.insert(payment)?)
+    }
+
+    async fn delete_payment(&self, id: &PaymentID) -> Result<(), RepositoryError> {
+        Ok(self.payments.remove(id)?)
+    }
+
+    async fn update_payment(&self, payment: Payment) -> Result<Payment, RepositoryError> {
+        Ok(self.payments.replace(payment)?)
+    }
+
+    async fn get_payment(&self, id: &PaymentID) -> Result<Option<Payment>, RepositoryError> {
+        Ok(self.payments.get(id))
+    }
+
+    async fn get_payments_by_group(
+        &self,
+        group: &GroupID,
+    ) -> Result<Vec<Payment>, RepositoryError> {
+        Ok(self.payments.filter(|payment| &payment.group == group))
+    }
+
+    async fn get_payments_by_filter(
+        &self,
+        group: &GroupID,
+        filter: &PaymentFilter,
+    ) -> Result<Vec<Payment>, RepositoryError> {
+        Ok(self
+            .payments
+            .filter(|payment| &payment.group == group && filter.matches(payment)))
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{
+    entities::{GroupID, RecurringPayment, RecurringPaymentID},
+    repositories::{
+        memory::{InMemory, Keyed},
+        RecurringPaymentRepository, RepositoryError,
+    },
+};
+use async_trait::async_trait;
+
+impl Keyed for RecurringPayment {
+    type Key = RecurringPaymentID;
+
+    fn key(&self) -> &RecurringPaymentID {
+        &self.id
+    }
+}
+
+#[async_trait]
+impl RecurringPaymentRepository for InMemory {
+    async fn create_recurring_payment(
+        &self,
+        recurring: RecurringPayment,
+    ) -> Result<RecurringPayment, RepositoryError> {
+        Ok(self.recurring_payments.insert(recurring)?)
+    }
+
+    async fn delete_recurring_payment(
+        &self,
+        id: &RecurringPaymentID,
+    ) -> Result<(), RepositoryError> {
+        Ok(self.recurring_payments.remove(id)?)
+    }
+
+    async fn update_recurring_payment(
+        &self,
+        recurring: RecurringPayment,
+    ) -> Result<RecurringPayment, RepositoryError> {
+        Ok(self.recurring_payments.replace(recurring)?)
+    }
+
+    async fn get_recurring_payment(
+        &self,
+        id: &RecurringPaymentID,
+    ) -> Result<Option<RecurringPayment>, RepositoryError> {
+        Ok(self.recurring_payments.get(id))
+    }
+
+    async fn get_recurring_payments_by_group(
+        &self,
+        group: &GroupID,
+    ) -> Result<Vec<RecurringPayment>, RepositoryError> {
+        Ok(self
+            .recurring_payments
+            .filter(|recurring| &recurring.group == group))
+    }
+
+    async fn get_recurring_payments(&self) -> Result<Vec<RecurringPayment>, RepositoryError> {
+        Ok(self.recurring_payments.filter(|_| true))
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{
+    entities::{GroupID, Settlement, SettlementID},
+    repositories::{
+        memory::{InMemory, Keyed},
+        RepositoryError, SettlementRepository,
+    },
+};
+use async_trait::async_trait;
+
+impl Keyed for Settlement {
+    type Key = SettlementID;
+
+    fn key(&self) -> &SettlementID {
+        &self.id
+    }
+}
+
+#[async_trait]
+impl SettlementRepository for InMemory {
+    async fn create_settlement(
+        &self,
+        settlement: Settlement,
+    ) -> Result<Settlement, RepositoryError> {
+        Ok(self.settlements.insert(settlement)?)
+    }
+
+    async fn delete_settlement(&self, id: &SettlementID) -> Result<(), RepositoryError> {
+        Ok(self.settlements.remove(id)?)
+    }
+
+    async fn update_settlement(
+        &self,
+        settlement: Settlement,
+    ) -> Result<Settlement, RepositoryError> {
+        Ok(self.settlements.replace(settlement)?)
+    }
+
+    async fn get_settlement(
+        &self,
+        id: &SettlementID,
+    ) -> Result<Option<Settlement>, RepositoryError> {
+        Ok(self.settlements.get(id))
+    }
+
+    async fn get_settlements_by_group(
+        &self,
+        group: &GroupID,
+    ) -> Result<Vec<Settlement>, RepositoryError> {
+        Ok(self
+            .settlements
+            .filter(|settlement| &settlement.group == group))
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{
+    entities::{Group, Invite, Payment, RecurringPayment, Settlement, User},
+    repositories::{
+        memory::{insert_row, remove_row, replace_row, InMemory, InMemoryError},
+        Operation, RepositoryError, TransactionRepository, UnitOfWork,
+    },
+};
+use async_trait::async_trait;
+
+/// 書き込みを適用している途中の全テーブルの複製
+struct Snapshot {
+    groups: Vec<Group>,
+    invites: Vec<Invite>,
+    payments: Vec<Payment>,
+    recurring_payments: Vec<RecurringPayment>,
+    settlements: Vec<Settlement>,
+    users: Vec<User>,
+}
+
+#[async_trait]
+impl TransactionRepository for InMemory {
+    async fn commit(&self, work: UnitOfWork) -> Result<(), RepositoryError> {
+        // 途中で失敗しても元のテーブルに影響しないよう、複製に適用してから差し替える。
+        // ロックは常に同じ順で取る
+        let mut groups = self.groups.write();
+        let mut invites = self.invites.write();
+        let mut payments = self.payments.write();
+        let mut recurring_payments = self.recurring_payments.write();
+        let mut settlements = self.settlements.write();
+        let mut users = self.users.write();
+
+        let mut snapshot = Snapshot {
+            groups: groups.clone(),
+            invites: invites.clone(),
+            payments: payments.clone(),
+            recurring_payments: recurring_payments.clone(),
+            settlements: settlements.clone(),
+            users: users.clone(),
+        };
+        for operation in work.operations {
+            snapshot.apply(operation)?;
+        }
+
+        *groups = snapshot.groups;
+        *invites = snapshot.invites;
+        *payments = snapshot.payments;
+        *recurring_payments = snapshot.recurring_payments;
+        *settlements = snapshot.settlements;
+        *users = snapshot.users;
+        Ok(())
+    }
+}
+
+impl Snapshot {
+    fn apply(&mut self, operation: Operation) -> Result<(), InMemoryError> {
+        match operation {
+            Operation::CreateGroup(group) => insert_row(&mut self.groups, group).map(drop),
+            Operation::UpdateGroup(group) => replace_row(&mut self.groups, group).map(drop),
+            Operation::DeleteGroup(id) => remove_row(&mut self.groups, &id),
+            Operation::CreateInvite(invite) => insert_row(&mut self.invites, invite).map(drop),
+            Operation::DeleteInvite(token) => remove_row(&mut self.invites, &token),
+            Operation::DeleteInvitesByGroup(group) => {
+                self.invites.retain(|invite| invite.group != group);
+                Ok(())
+            }
+            Operation::CreatePayment(payment) => insert_row(&mut self.payments, payment).map(drop),
+            Operation::UpdatePayment(payment) => replace_row(&mut self.payments, payment).map(drop),
+            Operation::DeletePayment(id) => remove_row(&mut self.payments, &id),
+            Operation::DeletePaymentsByGroup(group) => {
+                self.payments.retain(|payment| payment.group != group);
+                Ok(())
+            }
+            Operation::CreateRecurringPayment(recurring) => {
+                insert_row(&mut self.recurring_payments, recurring).map(drop)
+            }
+            Operation::UpdateRecurringPayment(recurring) => {
+                replace_row(&mut self.recurring_payments, recurring).map(drop)
+            }
+            Operation::DeleteRecurringPayment(id) => remove_row(&mut self.recurring_payments, &id),
+            Operation::DeleteRecurringPaymentsByGroup(group) => {
+                self.recurring_payments
+                    .retain(|recurring| recurring.group != group);
+                Ok(())
+            }
+            Operation::CreateSettlement(settlement) => {
+                insert_row(&mut self.settlements, settlement).map(drop)
+            }
+            Operation::UpdateSettlement(settlement) => {
+                replace_row(&mut self.settlements, settlement).map(drop)
+            }
+            Operation::DeleteSettlement(id) => remove_row(&mut self.settlements, &id),
+            Operation::DeleteSettlementsByGroup(group) => {
+                self.settlements
+                    .retain(|settlement| settlement.group != group);
+                Ok(())
+            }
+            Operation::CreateUser(user) => insert_row(&mut self.users, user).map(drop),
+            Operation::DeleteUser(id) => remove_row(&mut self.users, &id),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use crate::{
+    entities::{User, UserID},
+    repositories::{
+        memory::{InMemory, Keyed},
+        RepositoryError, UserRepository,
+    },
+};
+use async_trait::async_trait;
+
+impl Keyed for User {
+    type Key = UserID;
+
+    fn key(&self) -> &UserID {
+        &self.id
+    }
+}
+
+#[async_trait]
+impl UserRepository for InMemory {
+    async fn create_user(&self, user: User) -> Result<User, RepositoryError> {
+        Ok(self.users.insert(user)?)
+    }
+
+    async fn delete_user(&self, id: &UserID) -> Result<(), RepositoryError> {
+        Ok(self.users.remove(id)?)
+    }
+
+    async fn get_user(&self, id: &UserID) -> Result<Option<User>, RepositoryError> {
+        Ok(self.users.get(id))
+    }
+}
@@ -1,9 +1,30 @@
+mod clock;
+#[cfg(test)]
+mod conformance;
+mod error;
+mod memory;
 mod mongo;
+mod rates;
+mod sql;
+mod transaction;
 
+pub use clock::*;
+pub use error::*;
+pub use memory::*;
 pub use mongo::*;
+pub use rates::*;
+pub use sql::*;
+pub use transaction::*;
+
+#[cfg(test)]
+pub(crate) use conformance::conformance_tests;
 
-use crate::entities::{Group, GroupID, Payment, PaymentID, User, UserID};
+use crate::entities::{
+    Currency, ExchangeRate, Group, GroupID, Invite, InviteToken, Payment, PaymentFilter, PaymentID,
+    RecurringPayment, RecurringPaymentID, Settlement, SettlementID, User, UserID,
+};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use shaku::Interface;
 
 #[cfg(test)]
@@ -11,76 +32,173 @@ use mockall::predicate::*;
 #[cfg(test)]
 use mockall::*;
 
+/// データの保存先。どの実装も `conformance` のテストで同じ振る舞いを確かめる:
+/// 一覧は作成した順に返し、同じキーでの作成は `RepositoryError::Conflict`、
+/// 存在しないものの更新・削除は `RepositoryError::NotFound` になる。
+/// 複数の書き込みをまとめて適用するときは `TransactionRepository::commit` を使う
 #[async_trait]
-pub trait Repository: GroupRepository + PaymentRepository + UserRepository {}
+pub trait Repository:
+    GroupRepository
+    + InviteRepository
+    + PaymentRepository
+    + RecurringPaymentRepository
+    + SettlementRepository
+    + TransactionRepository
+    + UserRepository
+{
+}
 
-impl<T: GroupRepository + PaymentRepository + UserRepository> Repository for T {}
+impl<
+        T: GroupRepository
+            + InviteRepository
+            + PaymentRepository
+            + RecurringPaymentRepository
+            + SettlementRepository
+            + TransactionRepository
+            + UserRepository,
+    > Repository for T
+{
+}
 
 #[async_trait]
 #[cfg_attr(test, automock)]
 pub trait GroupRepository: Interface {
-    async fn create_group(
+    async fn create_group(&self, group: Group) -> Result<Group, RepositoryError>;
+
+    async fn delete_group(&self, id: &GroupID) -> Result<(), RepositoryError>;
+
+    async fn update_group(&self, group: Group) -> Result<Group, RepositoryError>;
+
+    async fn get_group(&self, id: &GroupID) -> Result<Option<Group>, RepositoryError>;
+
+    async fn get_groups_by_user(&self, id: &UserID) -> Result<Vec<Group>, RepositoryError>;
+
+    /// グループ全体を書き戻さずに参加者を 1 人追加する。参加済みなら何もしない。
+    /// 同時に参加した人を取りこぼさないよう、読み込みと追加を分けずに 1 回の操作で行う
+    async fn add_participant(&self, id: &GroupID, user: &UserID) -> Result<Group, RepositoryError>;
+}
+
+#[async_trait]
+#[cfg_attr(test, automock)]
+pub trait InviteRepository: Interface {
+    async fn create_invite(&self, invite: Invite) -> Result<Invite, RepositoryError>;
+
+    async fn delete_invite(&self, token: &InviteToken) -> Result<(), RepositoryError>;
+
+    async fn get_invite(&self, token: &InviteToken) -> Result<Option<Invite>, RepositoryError>;
+
+    async fn get_invites_by_group(&self, group: &GroupID) -> Result<Vec<Invite>, RepositoryError>;
+}
+
+#[async_trait]
+#[cfg_attr(test, automock)]
+pub trait PaymentRepository: Interface {
+    async fn create_payment(&self, payment: Payment) -> Result<Payment, RepositoryError>;
+
+    async fn delete_payment(&self, id: &PaymentID) -> Result<(), RepositoryError>;
+
+    async fn update_payment(&self, payment: Payment) -> Result<Payment, RepositoryError>;
+
+    async fn get_payment(&self, id: &PaymentID) -> Result<Option<Payment>, RepositoryError>;
+
+    async fn get_payments_by_group(&self, group: &GroupID)
+        -> Result<Vec<Payment>, RepositoryError>;
+
+    async fn get_payments_by_filter(
         &self,
-        group: Group,
-    ) -> Result<Group, Box<dyn std::error::Error + Send + Sync>>;
+        group: &GroupID,
+        filter: &PaymentFilter,
+    ) -> Result<Vec<Payment>, RepositoryError>;
+}
 
-    async fn delete_group(
+#[async_trait]
+#[cfg_attr(test, automock)]
+pub trait RecurringPaymentRepository: Interface {
+    async fn create_recurring_payment(
         &self,
-        id: &GroupID,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+        recurring: RecurringPayment,
+    ) -> Result<RecurringPayment, RepositoryError>;
 
-    async fn get_group(
+    async fn delete_recurring_payment(
         &self,
-        id: &GroupID,
-    ) -> Result<Option<Group>, Box<dyn std::error::Error + Send + Sync>>;
+        id: &RecurringPaymentID,
+    ) -> Result<(), RepositoryError>;
 
-    async fn get_groups_by_user(
+    async fn update_recurring_payment(
         &self,
-        id: &UserID,
-    ) -> Result<Vec<Group>, Box<dyn std::error::Error + Send + Sync>>;
+        recurring: RecurringPayment,
+    ) -> Result<RecurringPayment, RepositoryError>;
+
+    async fn get_recurring_payment(
+        &self,
+        id: &RecurringPaymentID,
+    ) -> Result<Option<RecurringPayment>, RepositoryError>;
+
+    async fn get_recurring_payments_by_group(
+        &self,
+        group: &GroupID,
+    ) -> Result<Vec<RecurringPayment>, RepositoryError>;
+
+    async fn get_recurring_payments(&self) -> Result<Vec<RecurringPayment>, RepositoryError>;
 }
 
 #[async_trait]
 #[cfg_attr(test, automock)]
-pub trait PaymentRepository: Interface {
-    async fn create_payment(
+pub trait SettlementRepository: Interface {
+    async fn create_settlement(
         &self,
-        payment: Payment,
-    ) -> Result<Payment, Box<dyn std::error::Error + Send + Sync>>;
+        settlement: Settlement,
+    ) -> Result<Settlement, RepositoryError>;
+
+    async fn delete_settlement(&self, id: &SettlementID) -> Result<(), RepositoryError>;
 
-    async fn delete_payment(
+    async fn update_settlement(
         &self,
-        id: &PaymentID,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+        settlement: Settlement,
+    ) -> Result<Settlement, RepositoryError>;
 
-    async fn get_payment(
+    async fn get_settlement(
         &self,
-        id: &PaymentID,
-    ) -> Result<Option<Payment>, Box<dyn std::error::Error + Send + Sync>>;
+        id: &SettlementID,
+    ) -> Result<Option<Settlement>, RepositoryError>;
 
-    async fn get_payments_by_group(
+    async fn get_settlements_by_group(
         &self,
         group: &GroupID,
-    ) -> Result<Vec<Payment>, Box<dyn std::error::Error + Send + Sync>>;
+    ) -> Result<Vec<Settlement>, RepositoryError>;
+}
+
+#[async_trait]
+#[cfg_attr(test, automock)]
+pub trait TransactionRepository: Interface {
+    async fn commit(&self, work: UnitOfWork) -> Result<(), RepositoryError>;
 }
 
 #[async_trait]
 #[cfg_attr(test, automock)]
 pub trait UserRepository: Interface {
-    async fn create_user(
-        &self,
-        user: User,
-    ) -> Result<User, Box<dyn std::error::Error + Send + Sync>>;
+    async fn create_user(&self, user: User) -> Result<User, RepositoryError>;
 
-    async fn delete_user(
-        &self,
-        id: &UserID,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+    async fn delete_user(&self, id: &UserID) -> Result<(), RepositoryError>;
+
+    async fn get_user(&self, id: &UserID) -> Result<Option<User>, RepositoryError>;
+}
 
-    async fn get_user(
+/// 通貨間の為替レートの取得元 (`from` 1 単位あたりの `to` の量)
+#[async_trait]
+#[cfg_attr(test, automock)]
+pub trait ExchangeRateProvider: Interface {
+    async fn get_rate(
         &self,
-        id: &UserID,
-    ) -> Result<Option<User>, Box<dyn std::error::Error + Send + Sync>>;
+        from: &Currency,
+        to: &Currency,
+    ) -> Result<Option<ExchangeRate>, RepositoryError>;
+}
+
+/// 現在時刻の取得元
+#[cfg_attr(test, automock)]
+pub trait Clock: Interface {
+    fn now(&self) -> DateTime<Utc>;
 }
 
 #[cfg(test)]
@@ -92,22 +210,56 @@ mock! {
         async fn create_group(
             &self,
             group: Group
-        ) -> Result<Group, Box<dyn std::error::Error + Send + Sync>>;
+        ) -> Result<Group, RepositoryError>;
 
         async fn delete_group(
             &self,
             id: &GroupID
-        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+        ) -> Result<(), RepositoryError>;
+
+        async fn update_group(
+            &self,
+            group: Group,
+        ) -> Result<Group, RepositoryError>;
 
         async fn get_group(
             &self,
             id: &GroupID,
-        ) -> Result<Option<Group>, Box<dyn std::error::Error + Send + Sync>>;
+        ) -> Result<Option<Group>, RepositoryError>;
 
         async fn get_groups_by_user(
             &self,
             id: &UserID,
-        ) -> Result<Vec<Group>, Box<dyn std::error::Error + Send + Sync>>;
+        ) -> Result<Vec<Group>, RepositoryError>;
+
+        async fn add_participant(
+            &self,
+            id: &GroupID,
+            user: &UserID,
+        ) -> Result<Group, RepositoryError>;
+    }
+
+    #[async_trait]
+    impl InviteRepository for Repository {
+        async fn create_invite(
+            &self,
+            invite: Invite,
+        ) -> Result<Invite, RepositoryError>;
+
+        async fn delete_invite(
+            &self,
+            token: &InviteToken,
+        ) -> Result<(), RepositoryError>;
+
+        async fn get_invite(
+            &self,
+            token: &InviteToken,
+        ) -> Result<Option<Invite>, RepositoryError>;
+
+        async fn get_invites_by_group(
+            &self,
+            group: &GroupID,
+        ) -> Result<Vec<Invite>, RepositoryError>;
     }
 
     #[async_trait]
@@ -115,22 +267,101 @@ mock! {
         async fn create_payment(
             &self,
             payment: Payment,
-        ) -> Result<Payment, Box<dyn std::error::Error + Send + Sync>>;
+        ) -> Result<Payment, RepositoryError>;
 
         async fn delete_payment(
             &self,
             id: &PaymentID,
-        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+        ) -> Result<(), RepositoryError>;
+
+        async fn update_payment(
+            &self,
+            payment: Payment,
+        ) -> Result<Payment, RepositoryError>;
 
         async fn get_payment(
             &self,
             id: &PaymentID,
-        ) -> Result<Option<Payment>, Box<dyn std::error::Error + Send + Sync>>;
+        ) -> Result<Option<Payment>, RepositoryError>;
 
         async fn get_payments_by_group(
             &self,
             group: &GroupID,
-        ) -> Result<Vec<Payment>, Box<dyn std::error::Error + Send + Sync>>;
+        ) -> Result<Vec<Payment>, RepositoryError>;
+
+        async fn get_payments_by_filter(
+            &self,
+            group: &GroupID,
+            filter: &PaymentFilter,
+        ) -> Result<Vec<Payment>, RepositoryError>;
+    }
+
+    #[async_trait]
+    impl RecurringPaymentRepository for Repository {
+        async fn create_recurring_payment(
+            &self,
+            recurring: RecurringPayment,
+        ) -> Result<RecurringPayment, RepositoryError>;
+
+        async fn delete_recurring_payment(
+            &self,
+            id: &RecurringPaymentID,
+        ) -> Result<(), RepositoryError>;
+
+        async fn update_recurring_payment(
+            &self,
+            recurring: RecurringPayment,
+        ) -> Result<RecurringPayment, RepositoryError>;
+
+        async fn get_recurring_payment(
+            &self,
+            id: &RecurringPaymentID,
+        ) -> Result<Option<RecurringPayment>, RepositoryError>;
+
+        async fn get_recurring_payments_by_group(
+            &self,
+            group: &GroupID,
+        ) -> Result<Vec<RecurringPayment>, RepositoryError>;
+
+        async fn get_recurring_payments(
+            &self,
+        ) -> Result<Vec<RecurringPayment>, RepositoryError>;
+    }
+
+    #[async_trait]
+    impl SettlementRepository for Repository {
+        async fn create_settlement(
+            &self,
+            settlement: Settlement,
+        ) -> Result<Settlement, RepositoryError>;
+
+        async fn delete_settlement(
+            &self,
+            id: &SettlementID,
+        ) -> Result<(), RepositoryError>;
+
+        async fn update_settlement(
+            &self,
+            settlement: Settlement,
+        ) -> Result<Settlement, RepositoryError>;
+
+        async fn get_settlement(
+            &self,
+            id: &SettlementID,
+        ) -> Result<Option<Settlement>, RepositoryError>;
+
+        async fn get_settlements_by_group(
+            &self,
+            group: &GroupID,
+        ) -> Result<Vec<Settlement>, RepositoryError>;
+    }
+
+    #[async_trait]
+    impl TransactionRepository for Repository {
+        async fn commit(
+            &self,
+            work: UnitOfWork,
+        ) -> Result<(), RepositoryError>;
     }
 
     #[async_trait]
@@ -138,16 +369,16 @@ mock! {
         async fn create_user(
             &self,
             user: User,
-        ) -> Result<User, Box<dyn std::error::Error + Send + Sync>>;
+        ) -> Result<User, RepositoryError>;
 
         async fn delete_user(
             &self,
             id: &UserID,
-        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+        ) -> Result<(), RepositoryError>;
 
         async fn get_user(
             &self,
             id: &UserID,
-        ) -> Result<Option<User>, Box<dyn std::error::Error + Send + Sync>>;
+        ) -> Result<Option<User>, RepositoryError>;
     }
 }
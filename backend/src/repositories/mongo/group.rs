@@ -1,12 +1,15 @@
 use crate::{
     entities::{Group, GroupID, UserID},
-    repositories::{GroupRepository, Mongo, MongoError, MONGO_COLLECTION_GROUPS},
+    repositories::{
+        mongo::creation_order, GroupRepository, Mongo, MongoError, RepositoryError,
+        MONGO_COLLECTION_GROUPS,
+    },
 };
 use async_trait::async_trait;
 use futures::stream::TryStreamExt;
 use mongodb::{
     bson::{doc, Bson},
-    options::IndexOptions,
+    options::{FindOneAndUpdateOptions, IndexOptions, ReturnDocument},
     Collection, IndexModel,
 };
 
@@ -36,118 +39,68 @@ impl Mongo {
 
 #[async_trait]
 impl GroupRepository for Mongo {
-    async fn create_group(
-        &self,
-        group: Group,
-    ) -> Result<Group, Box<dyn std::error::Error + Send + Sync>> {
+    async fn create_group(&self, group: Group) -> Result<Group, RepositoryError> {
         let groups: Collection<Group> = self.database.collection(MONGO_COLLECTION_GROUPS);
         let _ = groups.insert_one(&group, None).await?;
         Ok(group)
     }
 
-    async fn delete_group(
-        &self,
-        id: &GroupID,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    async fn delete_group(&self, id: &GroupID) -> Result<(), RepositoryError> {
         let groups: Collection<Group> = self.database.collection(MONGO_COLLECTION_GROUPS);
 
         let filter = doc! { "id": id };
         let result = groups.delete_one(filter, None).await?;
 
-        assert!(result.deleted_count == 1);
+        if result.deleted_count == 0 {
+            return Err(RepositoryError::NotFound);
+        }
         Ok(())
     }
 
-    async fn get_group(
-        &self,
-        id: &GroupID,
-    ) -> Result<Option<Group>, Box<dyn std::error::Error + Send + Sync>> {
+    async fn update_group(&self, group: Group) -> Result<Group, RepositoryError> {
         let groups: Collection<Group> = self.database.collection(MONGO_COLLECTION_GROUPS);
 
-        let filter = doc! { "id": id };
-        let result = groups.find_one(filter, None).await?;
+        let filter = doc! { "id": &group.id };
+        let result = groups.replace_one(filter, &group, None).await?;
 
-        Ok(result)
+        if result.matched_count == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+        Ok(group)
     }
 
-    async fn get_groups_by_user(
-        &self,
-        id: &UserID,
-    ) -> Result<Vec<Group>, Box<dyn std::error::Error + Send + Sync>> {
+    async fn get_group(&self, id: &GroupID) -> Result<Option<Group>, RepositoryError> {
         let groups: Collection<Group> = self.database.collection(MONGO_COLLECTION_GROUPS);
 
-        let filter = doc! { "participants": id };
-        let result = groups.find(filter, None).await?.try_collect().await?;
+        let filter = doc! { "id": id };
+        let result = groups.find_one(filter, None).await?;
 
         Ok(result)
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::repositories::MongoConfig;
-    use fake::{Fake, Faker};
-
-    #[tokio::test]
-    async fn create_group() {
-        let mongo = Mongo::new(MongoConfig {
-            uri: "mongodb://localhost:27017",
-            database: "warikan",
-        })
-        .await
-        .unwrap();
-
-        let group: Group = Faker.fake();
-
-        let create = mongo.create_group(group).await.unwrap();
-        let get = mongo.get_group(&create.id).await.unwrap();
-
-        assert_eq!(Some(create), get);
-    }
-
-    #[tokio::test]
-    async fn delete_group() {
-        let mongo = Mongo::new(MongoConfig {
-            uri: "mongodb://localhost:27017",
-            database: "warikan",
-        })
-        .await
-        .unwrap();
 
-        let group: Group = Faker.fake();
+    async fn get_groups_by_user(&self, id: &UserID) -> Result<Vec<Group>, RepositoryError> {
+        let groups: Collection<Group> = self.database.collection(MONGO_COLLECTION_GROUPS);
 
-        let create = mongo.create_group(group).await.unwrap();
-        mongo.delete_group(&create.id).await.unwrap();
-        let delete = mongo.get_group(&create.id).await.unwrap();
+        let filter = doc! { "participants": id };
+        let result = groups
+            .find(filter, creation_order())
+            .await?
+            .try_collect()
+            .await?;
 
-        assert_eq!(delete, None);
+        Ok(result)
     }
 
-    #[tokio::test]
-    async fn get_groups_by_user() {
-        let mongo = Mongo::new(MongoConfig {
-            uri: "mongodb://localhost:27017",
-            database: "warikan",
-        })
-        .await
-        .unwrap();
-
-        let user: UserID = Faker.fake();
-
-        let mut group1: Group = Faker.fake();
-        let mut group2: Group = Faker.fake();
-        let group3: Group = Faker.fake();
-
-        group1.participants.push(user.clone());
-        group2.participants.push(user.clone());
-
-        let _ = mongo.create_group(group1.clone()).await.unwrap();
-        let _ = mongo.create_group(group2.clone()).await.unwrap();
-        let _ = mongo.create_group(group3).await.unwrap();
+    async fn add_participant(&self, id: &GroupID, user: &UserID) -> Result<Group, RepositoryError> {
+        let groups: Collection<Group> = self.database.collection(MONGO_COLLECTION_GROUPS);
 
-        let groups = mongo.get_groups_by_user(&user).await.unwrap();
+        let filter = doc! { "id": id };
+        let update = doc! { "$addToSet": { "participants": user } };
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+        let result = groups.find_one_and_update(filter, update, options).await?;
 
-        assert_eq!(groups, vec![group1, group2]);
+        result.ok_or(RepositoryError::NotFound)
     }
 }
@@ -0,0 +1,81 @@
+use crate::{
+    entities::{GroupID, Invite, InviteToken},
+    repositories::{
+        mongo::creation_order, InviteRepository, Mongo, MongoError, RepositoryError,
+        MONGO_COLLECTION_INVITES,
+    },
+};
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use mongodb::{
+    bson::{doc, Bson},
+    options::IndexOptions,
+    Collection, IndexModel,
+};
+
+impl From<InviteToken> for Bson {
+    fn from(value: InviteToken) -> Self {
+        Bson::String(value.0.to_string())
+    }
+}
+
+impl Mongo {
+    pub async fn create_invite_index(&self) -> Result<(), MongoError> {
+        {
+            let model = IndexModel::builder()
+                .keys(doc! {"token": 1})
+                .options(IndexOptions::builder().unique(true).build())
+                .build();
+
+            self.database
+                .collection::<Invite>(MONGO_COLLECTION_INVITES)
+                .create_index(model, None)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
+
+#[async_trait]
+impl InviteRepository for Mongo {
+    async fn create_invite(&self, invite: Invite) -> Result<Invite, RepositoryError> {
+        let invites: Collection<Invite> = self.database.collection(MONGO_COLLECTION_INVITES);
+        let _ = invites.insert_one(&invite, None).await?;
+        Ok(invite)
+    }
+
+    async fn delete_invite(&self, token: &InviteToken) -> Result<(), RepositoryError> {
+        let invites: Collection<Invite> = self.database.collection(MONGO_COLLECTION_INVITES);
+
+        let filter = doc! { "token": token };
+        let result = invites.delete_one(filter, None).await?;
+
+        if result.deleted_count == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+        Ok(())
+    }
+
+    async fn get_invite(&self, token: &InviteToken) -> Result<Option<Invite>, RepositoryError> {
+        let invites: Collection<Invite> = self.database.collection(MONGO_COLLECTION_INVITES);
+
+        let filter = doc! { "token": token };
+        let result = invites.find_one(filter, None).await?;
+
+        Ok(result)
+    }
+
+    async fn get_invites_by_group(&self, group: &GroupID) -> Result<Vec<Invite>, RepositoryError> {
+        let invites: Collection<Invite> = self.database.collection(MONGO_COLLECTION_INVITES);
+
+        let filter = doc! { "group": group };
+        let result = invites
+            .find(filter, creation_order())
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(result)
+    }
+}
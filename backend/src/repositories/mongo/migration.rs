@@ -0,0 +1,315 @@
+use crate::{
+    entities::{Currency, Money, Payer, Payment, Split, UserID},
+    repositories::{Mongo, MongoError, MONGO_COLLECTION_GROUPS, MONGO_COLLECTION_PAYMENTS},
+};
+use futures::TryStreamExt;
+use mongodb::{
+    bson::{self, doc, Bson, Document},
+    options::FindOneOptions,
+    Collection,
+};
+
+impl Mongo {
+    /// 以前の形で保存されたドキュメントを現在の形に書き換える。何度実行してもよい
+    pub async fn migrate(&self) -> Result<(), MongoError> {
+        // `migrate_payment_payers` は `Payment` として読み込むので、フィールドの追加を先に行う
+        self.migrate_group_currency().await?;
+        self.migrate_payment_occurred_at().await?;
+        self.migrate_payment_payers().await?;
+
+        Ok(())
+    }
+
+    /// `currency` のないグループは、以前の残高の計算と同じく最初の支払いの通貨を基準通貨とする。
+    /// 支払いがなければ既定の通貨にする
+    async fn migrate_group_currency(&self) -> Result<(), MongoError> {
+        let groups: Collection<Document> = self.database.collection(MONGO_COLLECTION_GROUPS);
+        let payments: Collection<Document> = self.database.collection(MONGO_COLLECTION_PAYMENTS);
+
+        let filter = doc! { "currency": { "$exists": false } };
+        let mut cursor = groups.find(filter, None).await?;
+
+        while let Some(group) = cursor.try_next().await? {
+            let id = group
+                .get_str("id")
+                .map_err(|err| MongoError::Migration(err.to_string()))?;
+
+            let options = FindOneOptions::builder().sort(doc! { "_id": 1 }).build();
+            let first = payments.find_one(doc! { "group": id }, options).await?;
+            let currency = first
+                .as_ref()
+                .and_then(|payment| payment.get_document("amount").ok())
+                .and_then(|amount| amount.get_str("currency").ok())
+                .and_then(|currency| Currency::new(currency).ok())
+                .unwrap_or_default();
+
+            let update = doc! { "$set": { "currency": bson::to_bson(&currency)? } };
+            groups.update_one(doc! { "id": id }, update, None).await?;
+        }
+
+        Ok(())
+    }
+
+    /// `occurred_at` のない支払いは、記録した日時に支払ったものとする
+    async fn migrate_payment_occurred_at(&self) -> Result<(), MongoError> {
+        let payments: Collection<Document> = self.database.collection(MONGO_COLLECTION_PAYMENTS);
+
+        let filter = doc! { "occurred_at": { "$exists": false } };
+        let update = vec![doc! { "$set": { "occurred_at": "$created_at" } }];
+        payments.update_many(filter, update, None).await?;
+
+        Ok(())
+    }
+
+    /// `creditor` だけを持つ支払いを、調整後の合計を 1 人で立て替えた `payers` に書き換える。
+    /// 書き換えられないドキュメントがあれば、ほかを書き換えたうえでその ID を挙げて失敗する
+    async fn migrate_payment_payers(&self) -> Result<(), MongoError> {
+        let payments: Collection<Document> = self.database.collection(MONGO_COLLECTION_PAYMENTS);
+
+        let filter = doc! {
+            "payers": { "$exists": false },
+            "creditor": { "$exists": true },
+        };
+        let mut cursor = payments.find(filter, None).await?;
+        let mut skipped: Vec<String> = vec![];
+
+        while let Some(document) = cursor.try_next().await? {
+            let id = document.get_str("id").unwrap_or_default().to_string();
+            let payment = match upgrade_payment(document) {
+                Ok(payment) => payment,
+                Err(err) => {
+                    skipped.push(format!("{id} ({err})"));
+                    continue;
+                }
+            };
+
+            let filter = doc! { "id": &payment.id };
+            let result = payments
+                .replace_one(filter, bson::to_document(&payment)?, None)
+                .await?;
+
+            if result.matched_count == 0 {
+                return Err(MongoError::NotFound);
+            }
+        }
+
+        if !skipped.is_empty() {
+            return Err(MongoError::Migration(format!(
+                "payments could not be migrated: {}",
+                skipped.join(", ")
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// 以前の形の支払いのドキュメントを `Payment` にする。
+/// 金額や分け方を持たない最初の形のものは、0 を `debtors` で等分した支払いとみなす
+fn upgrade_payment(mut document: Document) -> Result<Payment, MongoError> {
+    let creditor = document
+        .get_str("creditor")
+        .map_err(|err| MongoError::Migration(err.to_string()))?
+        .to_string();
+    document.remove("creditor");
+    document.insert("payers", Bson::Array(vec![]));
+
+    if !document.contains_key("amount") {
+        let amount = Money::new(0, Currency::default());
+        document.insert("amount", bson::to_bson(&amount)?);
+    }
+    if !document.contains_key("split") {
+        let debtors: Vec<UserID> = match document.remove("debtors") {
+            Some(debtors) => bson::from_bson(debtors)?,
+            None => return Err(MongoError::Migration("no split or debtors".to_string())),
+        };
+        document.insert("split", bson::to_bson(&Split::equal(debtors))?);
+    }
+
+    let mut payment: Payment = bson::from_document(document)?;
+    let total = payment
+        .total()
+        .map_err(|err| MongoError::Migration(err.to_string()))?;
+    payment.payers = vec![Payer::new(UserID::new(creditor), total)];
+    Ok(payment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        entities::{Group, GroupID, PaymentID},
+        repositories::{GroupRepository, MongoConfig, PaymentRepository},
+    };
+    use fake::{Fake, Faker};
+
+    #[tokio::test]
+    async fn migrate_group_currency() {
+        let mongo = Mongo::new(MongoConfig {
+            uri: "mongodb://localhost:27017",
+            database: "warikan",
+        })
+        .await
+        .unwrap();
+
+        let group: Group = Faker.fake();
+        let mut payment: Payment = Faker.fake();
+        payment.group = group.id.clone();
+        payment.amount = Money::new(1001, Currency::new("USD").unwrap());
+
+        let mut document = bson::to_document(&group).unwrap();
+        document.remove("currency");
+        mongo
+            .database
+            .collection::<Document>(MONGO_COLLECTION_GROUPS)
+            .insert_one(document, None)
+            .await
+            .unwrap();
+        mongo.create_payment(payment).await.unwrap();
+
+        mongo.migrate().await.unwrap();
+        let get = mongo.get_group(&group.id).await.unwrap().unwrap();
+
+        assert_eq!(get.currency, Currency::new("USD").unwrap());
+    }
+
+    #[tokio::test]
+    async fn migrate_payment_payers() {
+        let mongo = Mongo::new(MongoConfig {
+            uri: "mongodb://localhost:27017",
+            database: "warikan",
+        })
+        .await
+        .unwrap();
+
+        let payment: Payment = Faker.fake();
+        let creditor: UserID = Faker.fake();
+
+        let mut document = bson::to_document(&payment).unwrap();
+        document.remove("payers");
+        document.insert("creditor", creditor.to_string());
+        mongo
+            .database
+            .collection::<Document>(MONGO_COLLECTION_PAYMENTS)
+            .insert_one(document, None)
+            .await
+            .unwrap();
+
+        mongo.migrate().await.unwrap();
+        let get = mongo.get_payment(&payment.id).await.unwrap().unwrap();
+
+        assert_eq!(
+            get.payers,
+            vec![Payer::new(creditor, payment.total().unwrap())]
+        );
+    }
+
+    /// 金額も分け方も持たない、最初の形のドキュメント
+    #[tokio::test]
+    async fn migrate_payment_payers_baseline() {
+        let mongo = Mongo::new(MongoConfig {
+            uri: "mongodb://localhost:27017",
+            database: "warikan",
+        })
+        .await
+        .unwrap();
+
+        let id: PaymentID = Faker.fake();
+        let group: GroupID = Faker.fake();
+        let creditor: UserID = Faker.fake();
+        let debtor: UserID = Faker.fake();
+
+        let document = doc! {
+            "id": id.to_string(),
+            "created_at": "2023-04-01T12:00:00Z",
+            "title": "dinner",
+            "group": group.to_string(),
+            "creditor": creditor.to_string(),
+            "debtors": [creditor.to_string(), debtor.to_string()],
+        };
+        mongo
+            .database
+            .collection::<Document>(MONGO_COLLECTION_PAYMENTS)
+            .insert_one(document, None)
+            .await
+            .unwrap();
+
+        mongo.migrate().await.unwrap();
+        let get = mongo.get_payment(&id).await.unwrap().unwrap();
+
+        let zero = Money::new(0, Currency::default());
+        assert_eq!(get.amount, zero);
+        assert_eq!(get.split, Split::equal(vec![creditor.clone(), debtor]));
+        assert_eq!(get.payers, vec![Payer::new(creditor, zero)]);
+        assert_eq!(get.occurred_at, get.created_at);
+    }
+
+    /// 書き換えられないドキュメントがあれば、ほかを書き換えたうえで失敗する。
+    /// 失敗したままになるほかのテストと共有しないよう、専用のデータベースを使って最後に消す
+    #[tokio::test]
+    async fn migrate_payment_payers_skip() {
+        let mongo = Mongo::new(MongoConfig {
+            uri: "mongodb://localhost:27017",
+            database: "warikan_migration_skip",
+        })
+        .await
+        .unwrap();
+
+        let id: PaymentID = Faker.fake();
+        let creditor: UserID = Faker.fake();
+        let payment: Payment = Faker.fake();
+
+        let broken = doc! {
+            "id": id.to_string(),
+            "created_at": "2023-04-01T12:00:00Z",
+            "title": "dinner",
+            "creditor": creditor.to_string(),
+        };
+        let mut document = bson::to_document(&payment).unwrap();
+        document.remove("payers");
+        document.insert("creditor", creditor.to_string());
+        mongo
+            .database
+            .collection::<Document>(MONGO_COLLECTION_PAYMENTS)
+            .insert_many([broken, document], None)
+            .await
+            .unwrap();
+
+        let result = mongo.migrate().await;
+        let get = mongo.get_payment(&payment.id).await.unwrap().unwrap();
+        mongo.database.drop(None).await.unwrap();
+
+        assert!(
+            matches!(result, Err(MongoError::Migration(message)) if message.contains(&id.to_string()))
+        );
+        assert_eq!(
+            get.payers,
+            vec![Payer::new(creditor, payment.total().unwrap())]
+        );
+    }
+
+    #[tokio::test]
+    async fn migrate_payment_occurred_at() {
+        let mongo = Mongo::new(MongoConfig {
+            uri: "mongodb://localhost:27017",
+            database: "warikan",
+        })
+        .await
+        .unwrap();
+
+        let payment: Payment = Faker.fake();
+
+        let mut document = bson::to_document(&payment).unwrap();
+        document.remove("occurred_at");
+        mongo
+            .database
+            .collection::<Document>(MONGO_COLLECTION_PAYMENTS)
+            .insert_one(document, None)
+            .await
+            .unwrap();
+
+        mongo.migrate().await.unwrap();
+        let get = mongo.get_payment(&payment.id).await.unwrap().unwrap();
+
+        assert_eq!(get.occurred_at, payment.created_at);
+    }
+}
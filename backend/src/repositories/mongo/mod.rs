@@ -1,14 +1,27 @@
 mod group;
+mod invite;
+mod migration;
 mod payment;
+mod recurring;
+mod settlement;
+mod transaction;
 mod user;
 
-use crate::repositories::Repository;
-use mongodb::{Client, Database};
+use crate::repositories::{Repository, RepositoryError};
+use mongodb::{
+    bson::{self, doc},
+    error::{ErrorKind, WriteError, WriteFailure},
+    options::FindOptions,
+    Client, Database,
+};
 use shaku::Component;
 use thiserror::Error;
 
 pub const MONGO_COLLECTION_GROUPS: &str = "groups";
+pub const MONGO_COLLECTION_INVITES: &str = "invites";
 pub const MONGO_COLLECTION_PAYMENTS: &str = "payments";
+pub const MONGO_COLLECTION_RECURRING: &str = "recurring_payments";
+pub const MONGO_COLLECTION_SETTLEMENTS: &str = "settlements";
 pub const MONGO_COLLECTION_USERS: &str = "users";
 
 #[derive(Debug, Component)]
@@ -21,6 +34,21 @@ pub struct Mongo {
 pub enum MongoError {
     #[error("mongodb error")]
     Mongo(#[from] mongodb::error::Error),
+
+    #[error("bson deserialization error")]
+    Deserialize(#[from] bson::de::Error),
+
+    #[error("bson serialization error")]
+    Serialize(#[from] bson::ser::Error),
+
+    #[error("migration error: {0}")]
+    Migration(String),
+
+    #[error("not found")]
+    NotFound,
+
+    #[error("transactions require a replica set or a sharded cluster")]
+    TransactionsUnsupported,
 }
 
 #[derive(Debug)]
@@ -34,15 +62,87 @@ impl Mongo {
         let client = Client::with_uri_str(config.uri).await?;
         let database = client.database(config.database);
         let mongo = Mongo { database };
+        mongo.check_transactions().await?;
         mongo.create_index().await?;
+        mongo.migrate().await?;
         Ok(mongo)
     }
 
     pub async fn create_index(&self) -> Result<(), MongoError> {
         self.create_group_index().await?;
+        self.create_invite_index().await?;
         self.create_payment_index().await?;
+        self.create_recurring_payment_index().await?;
+        self.create_settlement_index().await?;
         self.create_user_index().await?;
 
         Ok(())
     }
+
+    /// `TransactionRepository::commit` はトランザクションを使うので、単体のサーバーでは起動しない
+    async fn check_transactions(&self) -> Result<(), MongoError> {
+        let hello = self.database.run_command(doc! { "hello": 1 }, None).await?;
+        let replica_set = hello.contains_key("setName");
+        let sharded = hello.get_str("msg").is_ok_and(|msg| msg == "isdbgrid");
+        if !replica_set && !sharded {
+            return Err(MongoError::TransactionsUnsupported);
+        }
+        Ok(())
+    }
+}
+
+impl From<MongoError> for RepositoryError {
+    fn from(error: MongoError) -> Self {
+        match error {
+            MongoError::NotFound => RepositoryError::NotFound,
+            MongoError::Mongo(error) if is_duplicate_key(&error) => {
+                RepositoryError::Conflict(error.to_string())
+            }
+            error => RepositoryError::backend(error),
+        }
+    }
+}
+
+impl From<mongodb::error::Error> for RepositoryError {
+    fn from(error: mongodb::error::Error) -> Self {
+        MongoError::from(error).into()
+    }
+}
+
+impl From<bson::de::Error> for RepositoryError {
+    fn from(error: bson::de::Error) -> Self {
+        MongoError::from(error).into()
+    }
+}
+
+impl From<bson::ser::Error> for RepositoryError {
+    fn from(error: bson::ser::Error) -> Self {
+        MongoError::from(error).into()
+    }
+}
+
+/// 一意なインデックスに反する書き込み
+fn is_duplicate_key(error: &mongodb::error::Error) -> bool {
+    matches!(
+        error.kind.as_ref(),
+        ErrorKind::Write(WriteFailure::WriteError(WriteError { code: 11000, .. }))
+    )
+}
+
+/// 作成した順 (`_id` の昇順) に並べる
+fn creation_order() -> FindOptions {
+    FindOptions::builder().sort(doc! {"_id": 1}).build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::conformance_tests;
+
+    conformance_tests!(Mongo::new(MongoConfig {
+        uri: "mongodb://localhost:27017/?directConnection=true",
+        database: "warikan",
+    })
+    .await
+    .unwrap());
 }
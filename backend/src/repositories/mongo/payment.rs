@@ -1,11 +1,14 @@
 use crate::{
-    entities::{GroupID, Payment, PaymentID},
-    repositories::{Mongo, MongoError, PaymentRepository, MONGO_COLLECTION_PAYMENTS},
+    entities::{GroupID, Payment, PaymentFilter, PaymentID},
+    repositories::{
+        mongo::creation_order, Mongo, MongoError, PaymentRepository, RepositoryError,
+        MONGO_COLLECTION_PAYMENTS,
+    },
 };
 use async_trait::async_trait;
 use futures::TryStreamExt;
 use mongodb::{
-    bson::{doc, Bson},
+    bson::{self, doc, Bson},
     options::IndexOptions,
     Collection, IndexModel,
 };
@@ -28,40 +31,55 @@ impl Mongo {
                 .collection::<Payment>(MONGO_COLLECTION_PAYMENTS)
                 .create_index(model, None)
                 .await?;
+        }
+        {
+            let model = IndexModel::builder()
+                .keys(doc! {"group": 1, "category": 1})
+                .build();
 
-            Ok(())
+            self.database
+                .collection::<Payment>(MONGO_COLLECTION_PAYMENTS)
+                .create_index(model, None)
+                .await?;
         }
+
+        Ok(())
     }
 }
 
 #[async_trait]
 impl PaymentRepository for Mongo {
-    async fn create_payment(
-        &self,
-        payment: Payment,
-    ) -> Result<Payment, Box<dyn std::error::Error + Send + Sync>> {
+    async fn create_payment(&self, payment: Payment) -> Result<Payment, RepositoryError> {
         let payments: Collection<Payment> = self.database.collection(MONGO_COLLECTION_PAYMENTS);
         let _ = payments.insert_one(&payment, None).await?;
         Ok(payment)
     }
 
-    async fn delete_payment(
-        &self,
-        id: &PaymentID,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    async fn delete_payment(&self, id: &PaymentID) -> Result<(), RepositoryError> {
         let payments: Collection<Payment> = self.database.collection(MONGO_COLLECTION_PAYMENTS);
 
         let filter = doc! { "id": id };
         let result = payments.delete_one(filter, None).await?;
 
-        assert!(result.deleted_count == 1);
+        if result.deleted_count == 0 {
+            return Err(RepositoryError::NotFound);
+        }
         Ok(())
     }
 
-    async fn get_payment(
-        &self,
-        id: &PaymentID,
-    ) -> Result<Option<Payment>, Box<dyn std::error::Error + Send + Sync>> {
+    async fn update_payment(&self, payment: Payment) -> Result<Payment, RepositoryError> {
+        let payments: Collection<Payment> = self.database.collection(MONGO_COLLECTION_PAYMENTS);
+
+        let filter = doc! { "id": &payment.id };
+        let result = payments.replace_one(filter, &payment, None).await?;
+
+        if result.matched_count == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+        Ok(payment)
+    }
+
+    async fn get_payment(&self, id: &PaymentID) -> Result<Option<Payment>, RepositoryError> {
         let payments: Collection<Payment> = self.database.collection(MONGO_COLLECTION_PAYMENTS);
 
         let filter = doc! { "id": id };
@@ -73,81 +91,39 @@ impl PaymentRepository for Mongo {
     async fn get_payments_by_group(
         &self,
         group: &GroupID,
-    ) -> Result<Vec<Payment>, Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<Vec<Payment>, RepositoryError> {
         let payments: Collection<Payment> = self.database.collection(MONGO_COLLECTION_PAYMENTS);
 
         let filter = doc! { "group": group };
-        let result = payments.find(filter, None).await?.try_collect().await?;
+        let result = payments
+            .find(filter, creation_order())
+            .await?
+            .try_collect()
+            .await?;
 
         Ok(result)
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::repositories::MongoConfig;
-    use fake::{Fake, Faker};
-
-    #[tokio::test]
-    async fn create_payment() {
-        let mongo = Mongo::new(MongoConfig {
-            uri: "mongodb://localhost:27017",
-            database: "warikan",
-        })
-        .await
-        .unwrap();
 
-        let payment: Payment = Faker.fake();
-
-        let create = mongo.create_payment(payment).await.unwrap();
-        let get = mongo.get_payment(&create.id).await.unwrap();
-
-        assert_eq!(Some(create), get);
-    }
-
-    #[tokio::test]
-    async fn delete_payment() {
-        let mongo = Mongo::new(MongoConfig {
-            uri: "mongodb://localhost:27017",
-            database: "warikan",
-        })
-        .await
-        .unwrap();
-
-        let payment: Payment = Faker.fake();
-
-        let create = mongo.create_payment(payment).await.unwrap();
-        mongo.delete_payment(&create.id).await.unwrap();
-        let delete = mongo.get_payment(&create.id).await.unwrap();
-
-        assert_eq!(delete, None);
-    }
-
-    #[tokio::test]
-    async fn get_payments_by_group() {
-        let mongo = Mongo::new(MongoConfig {
-            uri: "mongodb://localhost:27017",
-            database: "warikan",
-        })
-        .await
-        .unwrap();
-
-        let mut payment1: Payment = Faker.fake();
-        let mut payment2: Payment = Faker.fake();
-        let mut payment3: Payment = Faker.fake();
-
-        let group: GroupID = Faker.fake();
-        payment1.group = group.clone();
-        payment2.group = group.clone();
-        payment3.group = group.clone();
-
-        mongo.create_payment(payment1.clone()).await.unwrap();
-        mongo.create_payment(payment2.clone()).await.unwrap();
-        mongo.create_payment(payment3.clone()).await.unwrap();
+    async fn get_payments_by_filter(
+        &self,
+        group: &GroupID,
+        filter: &PaymentFilter,
+    ) -> Result<Vec<Payment>, RepositoryError> {
+        let payments: Collection<Payment> = self.database.collection(MONGO_COLLECTION_PAYMENTS);
 
-        let get = mongo.get_payments_by_group(&group).await.unwrap();
+        let mut query = doc! { "group": group };
+        if let Some(category) = filter.category {
+            query.insert("category", bson::to_bson(&category)?);
+        }
+        if let Some(tag) = &filter.tag {
+            query.insert("tags", tag);
+        }
+        let result = payments
+            .find(query, creation_order())
+            .await?
+            .try_collect()
+            .await?;
 
-        assert_eq!(vec![payment1, payment2, payment3], get);
+        Ok(result)
     }
 }
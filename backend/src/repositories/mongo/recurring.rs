@@ -0,0 +1,134 @@
+use crate::{
+    entities::{GroupID, RecurringPayment, RecurringPaymentID},
+    repositories::{
+        mongo::creation_order, Mongo, MongoError, RecurringPaymentRepository, RepositoryError,
+        MONGO_COLLECTION_RECURRING,
+    },
+};
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use mongodb::{
+    bson::{doc, Bson},
+    options::IndexOptions,
+    Collection, IndexModel,
+};
+
+impl From<RecurringPaymentID> for Bson {
+    fn from(value: RecurringPaymentID) -> Self {
+        Bson::String(value.0.to_string())
+    }
+}
+
+impl Mongo {
+    pub async fn create_recurring_payment_index(&self) -> Result<(), MongoError> {
+        {
+            let model = IndexModel::builder()
+                .keys(doc! {"id": 1})
+                .options(IndexOptions::builder().unique(true).build())
+                .build();
+
+            self.database
+                .collection::<RecurringPayment>(MONGO_COLLECTION_RECURRING)
+                .create_index(model, None)
+                .await?;
+        }
+        {
+            let model = IndexModel::builder().keys(doc! {"group": 1}).build();
+
+            self.database
+                .collection::<RecurringPayment>(MONGO_COLLECTION_RECURRING)
+                .create_index(model, None)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RecurringPaymentRepository for Mongo {
+    async fn create_recurring_payment(
+        &self,
+        recurring: RecurringPayment,
+    ) -> Result<RecurringPayment, RepositoryError> {
+        let recurrings: Collection<RecurringPayment> =
+            self.database.collection(MONGO_COLLECTION_RECURRING);
+        let _ = recurrings.insert_one(&recurring, None).await?;
+        Ok(recurring)
+    }
+
+    async fn delete_recurring_payment(
+        &self,
+        id: &RecurringPaymentID,
+    ) -> Result<(), RepositoryError> {
+        let recurrings: Collection<RecurringPayment> =
+            self.database.collection(MONGO_COLLECTION_RECURRING);
+
+        let filter = doc! { "id": id };
+        let result = recurrings.delete_one(filter, None).await?;
+
+        if result.deleted_count == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+        Ok(())
+    }
+
+    async fn update_recurring_payment(
+        &self,
+        recurring: RecurringPayment,
+    ) -> Result<RecurringPayment, RepositoryError> {
+        let recurrings: Collection<RecurringPayment> =
+            self.database.collection(MONGO_COLLECTION_RECURRING);
+
+        let filter = doc! { "id": &recurring.id };
+        let result = recurrings.replace_one(filter, &recurring, None).await?;
+
+        if result.matched_count == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+        Ok(recurring)
+    }
+
+    async fn get_recurring_payment(
+        &self,
+        id: &RecurringPaymentID,
+    ) -> Result<Option<RecurringPayment>, RepositoryError> {
+        let recurrings: Collection<RecurringPayment> =
+            self.database.collection(MONGO_COLLECTION_RECURRING);
+
+        let filter = doc! { "id": id };
+        let result = recurrings.find_one(filter, None).await?;
+
+        Ok(result)
+    }
+
+    async fn get_recurring_payments_by_group(
+        &self,
+        group: &GroupID,
+    ) -> Result<Vec<RecurringPayment>, RepositoryError> {
+        let recurrings: Collection<RecurringPayment> =
+            self.database.collection(MONGO_COLLECTION_RECURRING);
+
+        let filter = doc! { "group": group };
+        let result = recurrings
+            .find(filter, creation_order())
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(result)
+    }
+
+    async fn get_recurring_payments(&self) -> Result<Vec<RecurringPayment>, RepositoryError> {
+        let recurrings: Collection<RecurringPayment> =
+            self.database.collection(MONGO_COLLECTION_RECURRING);
+
+        let result = recurrings
+            .find(None, creation_order())
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(result)
+    }
+}
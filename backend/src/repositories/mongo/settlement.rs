@@ -0,0 +1,110 @@
+use crate::{
+    entities::{GroupID, Settlement, SettlementID},
+    repositories::{
+        mongo::creation_order, Mongo, MongoError, RepositoryError, SettlementRepository,
+        MONGO_COLLECTION_SETTLEMENTS,
+    },
+};
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use mongodb::{
+    bson::{doc, Bson},
+    options::IndexOptions,
+    Collection, IndexModel,
+};
+
+impl From<SettlementID> for Bson {
+    fn from(value: SettlementID) -> Self {
+        Bson::String(value.0.to_string())
+    }
+}
+
+impl Mongo {
+    pub async fn create_settlement_index(&self) -> Result<(), MongoError> {
+        {
+            let model = IndexModel::builder()
+                .keys(doc! {"id": 1})
+                .options(IndexOptions::builder().unique(true).build())
+                .build();
+
+            self.database
+                .collection::<Settlement>(MONGO_COLLECTION_SETTLEMENTS)
+                .create_index(model, None)
+                .await?;
+
+            Ok(())
+        }
+    }
+}
+
+#[async_trait]
+impl SettlementRepository for Mongo {
+    async fn create_settlement(
+        &self,
+        settlement: Settlement,
+    ) -> Result<Settlement, RepositoryError> {
+        let settlements: Collection<Settlement> =
+            self.database.collection(MONGO_COLLECTION_SETTLEMENTS);
+        let _ = settlements.insert_one(&settlement, None).await?;
+        Ok(settlement)
+    }
+
+    async fn delete_settlement(&self, id: &SettlementID) -> Result<(), RepositoryError> {
+        let settlements: Collection<Settlement> =
+            self.database.collection(MONGO_COLLECTION_SETTLEMENTS);
+
+        let filter = doc! { "id": id };
+        let result = settlements.delete_one(filter, None).await?;
+
+        if result.deleted_count == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+        Ok(())
+    }
+
+    async fn update_settlement(
+        &self,
+        settlement: Settlement,
+    ) -> Result<Settlement, RepositoryError> {
+        let settlements: Collection<Settlement> =
+            self.database.collection(MONGO_COLLECTION_SETTLEMENTS);
+
+        let filter = doc! { "id": &settlement.id };
+        let result = settlements.replace_one(filter, &settlement, None).await?;
+
+        if result.matched_count == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+        Ok(settlement)
+    }
+
+    async fn get_settlement(
+        &self,
+        id: &SettlementID,
+    ) -> Result<Option<Settlement>, RepositoryError> {
+        let settlements: Collection<Settlement> =
+            self.database.collection(MONGO_COLLECTION_SETTLEMENTS);
+
+        let filter = doc! { "id": id };
+        let result = settlements.find_one(filter, None).await?;
+
+        Ok(result)
+    }
+
+    async fn get_settlements_by_group(
+        &self,
+        group: &GroupID,
+    ) -> Result<Vec<Settlement>, RepositoryError> {
+        let settlements: Collection<Settlement> =
+            self.database.collection(MONGO_COLLECTION_SETTLEMENTS);
+
+        let filter = doc! { "group": group };
+        let result = settlements
+            .find(filter, creation_order())
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,192 @@
+use crate::repositories::{
+    Mongo, MongoError, Operation, RepositoryError, TransactionRepository, UnitOfWork,
+    MONGO_COLLECTION_GROUPS, MONGO_COLLECTION_INVITES, MONGO_COLLECTION_PAYMENTS,
+    MONGO_COLLECTION_RECURRING, MONGO_COLLECTION_SETTLEMENTS, MONGO_COLLECTION_USERS,
+};
+use async_trait::async_trait;
+use mongodb::{
+    bson::{doc, Bson, Document},
+    ClientSession,
+};
+use serde::Serialize;
+
+#[async_trait]
+impl TransactionRepository for Mongo {
+    async fn commit(&self, work: UnitOfWork) -> Result<(), RepositoryError> {
+        let mut session = self.database.client().start_session(None).await?;
+        session.start_transaction(None).await?;
+
+        for operation in work.operations {
+            if let Err(err) = self.apply(operation, &mut session).await {
+                session.abort_transaction().await?;
+                return Err(err.into());
+            }
+        }
+
+        session.commit_transaction().await?;
+        Ok(())
+    }
+}
+
+impl Mongo {
+    async fn apply(
+        &self,
+        operation: Operation,
+        session: &mut ClientSession,
+    ) -> Result<(), MongoError> {
+        match operation {
+            Operation::CreateGroup(group) => {
+                self.insert(MONGO_COLLECTION_GROUPS, &group, session).await
+            }
+            Operation::UpdateGroup(group) => {
+                let id = group.id.clone().into();
+                self.replace(MONGO_COLLECTION_GROUPS, "id", id, &group, session)
+                    .await
+            }
+            Operation::DeleteGroup(id) => {
+                self.delete(MONGO_COLLECTION_GROUPS, "id", id.into(), session)
+                    .await
+            }
+            Operation::CreateInvite(invite) => {
+                self.insert(MONGO_COLLECTION_INVITES, &invite, session)
+                    .await
+            }
+            Operation::DeleteInvite(token) => {
+                self.delete(MONGO_COLLECTION_INVITES, "token", token.into(), session)
+                    .await
+            }
+            Operation::DeleteInvitesByGroup(group) => {
+                self.delete_by_group(MONGO_COLLECTION_INVITES, group.into(), session)
+                    .await
+            }
+            Operation::CreatePayment(payment) => {
+                self.insert(MONGO_COLLECTION_PAYMENTS, &payment, session)
+                    .await
+            }
+            Operation::UpdatePayment(payment) => {
+                let id = payment.id.clone().into();
+                self.replace(MONGO_COLLECTION_PAYMENTS, "id", id, &payment, session)
+                    .await
+            }
+            Operation::DeletePayment(id) => {
+                self.delete(MONGO_COLLECTION_PAYMENTS, "id", id.into(), session)
+                    .await
+            }
+            Operation::DeletePaymentsByGroup(group) => {
+                self.delete_by_group(MONGO_COLLECTION_PAYMENTS, group.into(), session)
+                    .await
+            }
+            Operation::CreateRecurringPayment(recurring) => {
+                self.insert(MONGO_COLLECTION_RECURRING, &recurring, session)
+                    .await
+            }
+            Operation::UpdateRecurringPayment(recurring) => {
+                let id = recurring.id.clone().into();
+                self.replace(MONGO_COLLECTION_RECURRING, "id", id, &recurring, session)
+                    .await
+            }
+            Operation::DeleteRecurringPayment(id) => {
+                self.delete(MONGO_COLLECTION_RECURRING, "id", id.into(), session)
+                    .await
+            }
+            Operation::DeleteRecurringPaymentsByGroup(group) => {
+                self.delete_by_group(MONGO_COLLECTION_RECURRING, group.into(), session)
+                    .await
+            }
+            Operation::CreateSettlement(settlement) => {
+                self.insert(MONGO_COLLECTION_SETTLEMENTS, &settlement, session)
+                    .await
+            }
+            Operation::UpdateSettlement(settlement) => {
+                let id = settlement.id.clone().into();
+                self.replace(MONGO_COLLECTION_SETTLEMENTS, "id", id, &settlement, session)
+                    .await
+            }
+            Operation::DeleteSettlement(id) => {
+                self.delete(MONGO_COLLECTION_SETTLEMENTS, "id", id.into(), session)
+                    .await
+            }
+            Operation::DeleteSettlementsByGroup(group) => {
+                self.delete_by_group(MONGO_COLLECTION_SETTLEMENTS, group.into(), session)
+                    .await
+            }
+            Operation::CreateUser(user) => {
+                self.insert(MONGO_COLLECTION_USERS, &user, session).await
+            }
+            Operation::DeleteUser(id) => {
+                self.delete(MONGO_COLLECTION_USERS, "id", id.into(), session)
+                    .await
+            }
+        }
+    }
+
+    async fn insert<T: Serialize + Send + Sync>(
+        &self,
+        collection: &str,
+        row: &T,
+        session: &mut ClientSession,
+    ) -> Result<(), MongoError> {
+        let _ = self
+            .database
+            .collection::<T>(collection)
+            .insert_one_with_session(row, None, session)
+            .await?;
+        Ok(())
+    }
+
+    async fn replace<T: Serialize + Send + Sync>(
+        &self,
+        collection: &str,
+        key: &str,
+        value: Bson,
+        row: &T,
+        session: &mut ClientSession,
+    ) -> Result<(), MongoError> {
+        let filter = doc! { key: value };
+        let result = self
+            .database
+            .collection::<T>(collection)
+            .replace_one_with_session(filter, row, None, session)
+            .await?;
+
+        if result.matched_count == 0 {
+            return Err(MongoError::NotFound);
+        }
+        Ok(())
+    }
+
+    async fn delete(
+        &self,
+        collection: &str,
+        key: &str,
+        value: Bson,
+        session: &mut ClientSession,
+    ) -> Result<(), MongoError> {
+        let filter = doc! { key: value };
+        let result = self
+            .database
+            .collection::<Document>(collection)
+            .delete_one_with_session(filter, None, session)
+            .await?;
+
+        if result.deleted_count == 0 {
+            return Err(MongoError::NotFound);
+        }
+        Ok(())
+    }
+
+    async fn delete_by_group(
+        &self,
+        collection: &str,
+        group: Bson,
+        session: &mut ClientSession,
+    ) -> Result<(), MongoError> {
+        let filter = doc! { "group": group };
+        let _ = self
+            .database
+            .collection::<Document>(collection)
+            .delete_many_with_session(filter, None, session)
+            .await?;
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     entities::{User, UserID},
-    repositories::{Mongo, MongoError, UserRepository, MONGO_COLLECTION_USERS},
+    repositories::{Mongo, MongoError, RepositoryError, UserRepository, MONGO_COLLECTION_USERS},
 };
 use async_trait::async_trait;
 use mongodb::{
@@ -35,32 +35,25 @@ impl Mongo {
 
 #[async_trait]
 impl UserRepository for Mongo {
-    async fn create_user(
-        &self,
-        user: User,
-    ) -> Result<User, Box<dyn std::error::Error + Send + Sync>> {
+    async fn create_user(&self, user: User) -> Result<User, RepositoryError> {
         let users: Collection<User> = self.database.collection(MONGO_COLLECTION_USERS);
         let _ = users.insert_one(&user, None).await?;
         Ok(user)
     }
 
-    async fn delete_user(
-        &self,
-        id: &UserID,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    async fn delete_user(&self, id: &UserID) -> Result<(), RepositoryError> {
         let users: Collection<User> = self.database.collection(MONGO_COLLECTION_USERS);
 
         let filter = doc! { "id": id };
         let result = users.delete_one(filter, None).await?;
 
-        assert!(result.deleted_count == 1);
+        if result.deleted_count == 0 {
+            return Err(RepositoryError::NotFound);
+        }
         Ok(())
     }
 
-    async fn get_user(
-        &self,
-        id: &UserID,
-    ) -> Result<Option<User>, Box<dyn std::error::Error + Send + Sync>> {
+    async fn get_user(&self, id: &UserID) -> Result<Option<User>, RepositoryError> {
         let users: Collection<User> = self.database.collection(MONGO_COLLECTION_USERS);
 
         let filter = doc! { "id": id };
@@ -69,45 +62,3 @@ impl UserRepository for Mongo {
         Ok(result)
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::repositories::MongoConfig;
-    use fake::{Fake, Faker};
-
-    #[tokio::test]
-    async fn create_user() {
-        let mongo = Mongo::new(MongoConfig {
-            uri: "mongodb://localhost:27017",
-            database: "warikan",
-        })
-        .await
-        .unwrap();
-
-        let user: User = Faker.fake();
-
-        let create = mongo.create_user(user).await.unwrap();
-        let get = mongo.get_user(&create.id).await.unwrap();
-
-        assert_eq!(Some(create), get);
-    }
-
-    #[tokio::test]
-    async fn delete_user() {
-        let mongo = Mongo::new(MongoConfig {
-            uri: "mongodb://localhost:27017",
-            database: "warikan",
-        })
-        .await
-        .unwrap();
-
-        let user: User = Faker.fake();
-
-        let create = mongo.create_user(user).await.unwrap();
-        mongo.delete_user(&create.id).await.unwrap();
-        let delete = mongo.get_user(&create.id).await.unwrap();
-
-        assert_eq!(delete, None);
-    }
-}
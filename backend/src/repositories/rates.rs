@@ -0,0 +1,183 @@
+use crate::{
+    entities::{Currency, ExchangeRate},
+    repositories::{ExchangeRateProvider, RepositoryError},
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use shaku::Component;
+use std::{collections::HashMap, path::Path};
+use thiserror::Error;
+
+/// 起動時にファイルから読み込んだ為替レートの表
+#[derive(Debug, Default, Component)]
+#[shaku(interface = ExchangeRateProvider)]
+pub struct StaticExchangeRates {
+    rates: HashMap<(Currency, Currency), ExchangeRate>,
+}
+
+#[derive(Debug, Error)]
+pub enum ExchangeRatesError {
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+
+    #[error("json error")]
+    Json(#[from] serde_json::Error),
+
+    #[error("invalid exchange rate: {0}")]
+    Invalid(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct Record {
+    from: String,
+    to: String,
+    rate: String,
+}
+
+impl StaticExchangeRates {
+    /// 拡張子が `.csv` なら `from,to,rate` の CSV、それ以外は
+    /// `[{"from": "USD", "to": "JPY", "rate": "150.25"}]` の JSON として読み込む
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ExchangeRatesError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        if path.extension().is_some_and(|extension| extension == "csv") {
+            Self::from_csv(&content)
+        } else {
+            Self::from_json(&content)
+        }
+    }
+
+    pub fn from_json(content: &str) -> Result<Self, ExchangeRatesError> {
+        let records: Vec<Record> = serde_json::from_str(content)?;
+        Self::from_records(records)
+    }
+
+    /// 先頭行が `from,to,rate` ならヘッダーとして読み飛ばす
+    pub fn from_csv(content: &str) -> Result<Self, ExchangeRatesError> {
+        let records = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && *line != "from,to,rate")
+            .map(
+                |line| match line.split(',').map(str::trim).collect::<Vec<_>>()[..] {
+                    [from, to, rate] => Ok(Record {
+                        from: from.to_string(),
+                        to: to.to_string(),
+                        rate: rate.to_string(),
+                    }),
+                    _ => Err(ExchangeRatesError::Invalid(line.to_string())),
+                },
+            )
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::from_records(records)
+    }
+
+    fn from_records(records: Vec<Record>) -> Result<Self, ExchangeRatesError> {
+        let mut rates = HashMap::new();
+        for record in records {
+            let invalid = || {
+                ExchangeRatesError::Invalid(format!(
+                    "{},{},{}",
+                    record.from, record.to, record.rate
+                ))
+            };
+            let from = Currency::new(&record.from).map_err(|_| invalid())?;
+            let to = Currency::new(&record.to).map_err(|_| invalid())?;
+            let rate: ExchangeRate = record.rate.parse().map_err(|_| invalid())?;
+            if !rate.is_valid() {
+                return Err(invalid());
+            }
+            rates.insert((from, to), rate);
+        }
+        Ok(Self { rates })
+    }
+}
+
+#[async_trait]
+impl ExchangeRateProvider for StaticExchangeRates {
+    async fn get_rate(
+        &self,
+        from: &Currency,
+        to: &Currency,
+    ) -> Result<Option<ExchangeRate>, RepositoryError> {
+        if from == to {
+            return Ok(Some(ExchangeRate::IDENTITY));
+        }
+        let rate = self
+            .rates
+            .get(&(from.clone(), to.clone()))
+            .copied()
+            .or_else(|| {
+                self.rates
+                    .get(&(to.clone(), from.clone()))
+                    .and_then(ExchangeRate::inverse)
+            });
+        Ok(rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn currency(code: &str) -> Currency {
+        Currency::new(code).unwrap()
+    }
+
+    #[tokio::test]
+    async fn from_json() {
+        let rates =
+            StaticExchangeRates::from_json(r#"[{"from": "USD", "to": "JPY", "rate": "150.25"}]"#)
+                .unwrap();
+
+        assert_eq!(
+            rates
+                .get_rate(&currency("USD"), &currency("JPY"))
+                .await
+                .unwrap(),
+            Some(ExchangeRate(150_250_000))
+        );
+    }
+
+    #[tokio::test]
+    async fn from_csv() {
+        let rates =
+            StaticExchangeRates::from_csv("from,to,rate\nUSD,JPY,150\nEUR,JPY,160\n").unwrap();
+
+        assert_eq!(
+            rates
+                .get_rate(&currency("EUR"), &currency("JPY"))
+                .await
+                .unwrap(),
+            Some(ExchangeRate(160_000_000))
+        );
+        assert_eq!(
+            rates
+                .get_rate(&currency("JPY"), &currency("USD"))
+                .await
+                .unwrap(),
+            Some(ExchangeRate(6_667))
+        );
+        assert_eq!(
+            rates
+                .get_rate(&currency("JPY"), &currency("JPY"))
+                .await
+                .unwrap(),
+            Some(ExchangeRate::IDENTITY)
+        );
+        assert_eq!(
+            rates
+                .get_rate(&currency("USD"), &currency("EUR"))
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(StaticExchangeRates::from_csv("USD,JPY").is_err());
+        assert!(StaticExchangeRates::from_csv("USD,JPY,-1").is_err());
+        assert!(StaticExchangeRates::from_csv("usd,JPY,1").is_err());
+    }
+}
@@ -0,0 +1,265 @@
+use crate::{
+    entities::{Currency, ExchangeRate, Group, GroupID, ParticipantRole, RateOverride, UserID},
+    repositories::{
+        sql::{from_text, to_text, Sql, SqlError},
+        GroupRepository, RepositoryError,
+    },
+};
+use async_trait::async_trait;
+use sqlx::{AnyConnection, FromRow};
+
+#[derive(FromRow)]
+struct GroupRow {
+    id: String,
+    created_at: String,
+    title: String,
+    currency: String,
+    rounding: String,
+}
+
+impl Sql {
+    async fn load_group(&self, row: GroupRow) -> Result<Group, SqlError> {
+        let participants: Vec<(String,)> = sqlx::query_as(
+            "SELECT user_id FROM group_participants WHERE group_id = $1 ORDER BY position",
+        )
+        .bind(&row.id)
+        .fetch_all(&self.pool)
+        .await?;
+        let guests: Vec<(String,)> = sqlx::query_as(
+            "SELECT user_id FROM group_guests WHERE group_id = $1 ORDER BY position",
+        )
+        .bind(&row.id)
+        .fetch_all(&self.pool)
+        .await?;
+        let rates: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT currency, rate FROM group_rates WHERE group_id = $1 ORDER BY position",
+        )
+        .bind(&row.id)
+        .fetch_all(&self.pool)
+        .await?;
+        let roles: Vec<(String, String)> = sqlx::query_as(
+            "SELECT user_id, role FROM group_roles WHERE group_id = $1 ORDER BY position",
+        )
+        .bind(&row.id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(Group {
+            id: GroupID::new(row.id),
+            created_at: from_text(row.created_at)?,
+            title: row.title,
+            participants: participants
+                .into_iter()
+                .map(|(user,)| UserID::new(user))
+                .collect(),
+            guests: guests
+                .into_iter()
+                .map(|(user,)| UserID::new(user))
+                .collect(),
+            currency: Currency(row.currency),
+            rates: rates
+                .into_iter()
+                .map(|(currency, rate)| RateOverride {
+                    currency: Currency(currency),
+                    rate: ExchangeRate(rate),
+                })
+                .collect(),
+            rounding: from_text(row.rounding)?,
+            roles: roles
+                .into_iter()
+                .map(|(user, role)| {
+                    Ok(ParticipantRole {
+                        user: UserID::new(user),
+                        role: from_text(role)?,
+                    })
+                })
+                .collect::<Result<_, SqlError>>()?,
+        })
+    }
+}
+
+async fn insert_group_members(conn: &mut AnyConnection, group: &Group) -> Result<(), SqlError> {
+    let id = group.id.to_string();
+    for (position, user) in group.participants.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO group_participants (group_id, position, user_id) VALUES ($1, $2, $3)",
+        )
+        .bind(&id)
+        .bind(position as i64)
+        .bind(user.to_string())
+        .execute(&mut *conn)
+        .await?;
+    }
+    for (position, user) in group.guests.iter().enumerate() {
+        sqlx::query("INSERT INTO group_guests (group_id, position, user_id) VALUES ($1, $2, $3)")
+            .bind(&id)
+            .bind(position as i64)
+            .bind(user.to_string())
+            .execute(&mut *conn)
+            .await?;
+    }
+    for (position, rate) in group.rates.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO group_rates (group_id, position, currency, rate) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(&id)
+        .bind(position as i64)
+        .bind(&rate.currency.0)
+        .bind(rate.rate.0)
+        .execute(&mut *conn)
+        .await?;
+    }
+    for (position, role) in group.roles.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO group_roles (group_id, position, user_id, role) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(&id)
+        .bind(position as i64)
+        .bind(role.user.to_string())
+        .bind(to_text(&role.role)?)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn delete_group_members(conn: &mut AnyConnection, id: &GroupID) -> Result<(), SqlError> {
+    for table in [
+        "group_participants",
+        "group_guests",
+        "group_rates",
+        "group_roles",
+    ] {
+        sqlx::query(&format!("DELETE FROM {table} WHERE group_id = $1"))
+            .bind(id.to_string())
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+pub(super) async fn insert_group(conn: &mut AnyConnection, group: &Group) -> Result<(), SqlError> {
+    sqlx::query(
+        "INSERT INTO groups (id, created_at, title, currency, rounding) VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(group.id.to_string())
+    .bind(to_text(&group.created_at)?)
+    .bind(&group.title)
+    .bind(&group.currency.0)
+    .bind(to_text(&group.rounding)?)
+    .execute(&mut *conn)
+    .await?;
+    insert_group_members(conn, group).await?;
+    Ok(())
+}
+
+pub(super) async fn remove_group(conn: &mut AnyConnection, id: &GroupID) -> Result<(), SqlError> {
+    let result = sqlx::query("DELETE FROM groups WHERE id = $1")
+        .bind(id.to_string())
+        .execute(&mut *conn)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(SqlError::NotFound);
+    }
+    delete_group_members(conn, id).await?;
+    Ok(())
+}
+
+pub(super) async fn replace_group(conn: &mut AnyConnection, group: &Group) -> Result<(), SqlError> {
+    let result = sqlx::query(
+        "UPDATE groups SET created_at = $2, title = $3, currency = $4, rounding = $5 WHERE id = $1",
+    )
+    .bind(group.id.to_string())
+    .bind(to_text(&group.created_at)?)
+    .bind(&group.title)
+    .bind(&group.currency.0)
+    .bind(to_text(&group.rounding)?)
+    .execute(&mut *conn)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(SqlError::NotFound);
+    }
+    delete_group_members(conn, &group.id).await?;
+    insert_group_members(conn, group).await?;
+    Ok(())
+}
+
+#[async_trait]
+impl GroupRepository for Sql {
+    async fn create_group(&self, group: Group) -> Result<Group, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        insert_group(&mut tx, &group).await?;
+        tx.commit().await?;
+        Ok(group)
+    }
+
+    async fn delete_group(&self, id: &GroupID) -> Result<(), RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        remove_group(&mut tx, id).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn update_group(&self, group: Group) -> Result<Group, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        replace_group(&mut tx, &group).await?;
+        tx.commit().await?;
+        Ok(group)
+    }
+
+    async fn get_group(&self, id: &GroupID) -> Result<Option<Group>, RepositoryError> {
+        let row: Option<GroupRow> = sqlx::query_as(
+            "SELECT id, created_at, title, currency, rounding FROM groups WHERE id = $1",
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(Some(self.load_group(row).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_groups_by_user(&self, id: &UserID) -> Result<Vec<Group>, RepositoryError> {
+        let rows: Vec<GroupRow> = sqlx::query_as(
+            "SELECT id, created_at, title, currency, rounding FROM groups \
+             WHERE id IN (SELECT group_id FROM group_participants WHERE user_id = $1) \
+             ORDER BY seq",
+        )
+        .bind(id.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut groups = vec![];
+        for row in rows {
+            groups.push(self.load_group(row).await?);
+        }
+        Ok(groups)
+    }
+
+    async fn add_participant(&self, id: &GroupID, user: &UserID) -> Result<Group, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        // 空の更新でグループの行をロックし、同時に参加した人と position がぶつからないようにする
+        let result = sqlx::query("UPDATE groups SET title = title WHERE id = $1")
+            .bind(id.to_string())
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(RepositoryError::NotFound);
+        }
+        sqlx::query(
+            "INSERT INTO group_participants (group_id, position, user_id) \
+             SELECT $1, COALESCE(MAX(position) + 1, 0), $2 FROM group_participants \
+             WHERE group_id = $1 \
+             HAVING COUNT(CASE WHEN user_id = $2 THEN 1 END) = 0",
+        )
+        .bind(id.to_string())
+        .bind(user.to_string())
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        self.get_group(id).await?.ok_or(RepositoryError::NotFound)
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{
+    entities::{GroupID, Invite, InviteToken, UserID},
+    repositories::{
+        sql::{from_text, to_text, Sql, SqlError},
+        InviteRepository, RepositoryError,
+    },
+};
+use async_trait::async_trait;
+use sqlx::{AnyConnection, FromRow};
+
+#[derive(FromRow)]
+struct InviteRow {
+    token: String,
+    created_at: String,
+    expires_at: String,
+    group_id: String,
+    created_by: String,
+}
+
+impl TryFrom<InviteRow> for Invite {
+    type Error = SqlError;
+
+    fn try_from(row: InviteRow) -> Result<Self, Self::Error> {
+        Ok(Invite {
+            token: InviteToken::new(row.token),
+            created_at: from_text(row.created_at)?,
+            expires_at: from_text(row.expires_at)?,
+            group: GroupID::new(row.group_id),
+            created_by: UserID::new(row.created_by),
+        })
+    }
+}
+
+pub(super) async fn insert_invite(
+    conn: &mut AnyConnection,
+    invite: &Invite,
+) -> Result<(), SqlError> {
+    sqlx::query(
+        "INSERT INTO invites (token, created_at, expires_at, group_id, created_by) \
+         VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(invite.token.to_string())
+    .bind(to_text(&invite.created_at)?)
+    .bind(to_text(&invite.expires_at)?)
+    .bind(invite.group.to_string())
+    .bind(invite.created_by.to_string())
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+pub(super) async fn remove_invite(
+    conn: &mut AnyConnection,
+    token: &InviteToken,
+) -> Result<(), SqlError> {
+    let result = sqlx::query("DELETE FROM invites WHERE token = $1")
+        .bind(token.to_string())
+        .execute(&mut *conn)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(SqlError::NotFound);
+    }
+    Ok(())
+}
+
+pub(super) async fn remove_invites_by_group(
+    conn: &mut AnyConnection,
+    group: &GroupID,
+) -> Result<(), SqlError> {
+    sqlx::query("DELETE FROM invites WHERE group_id = $1")
+        .bind(group.to_string())
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+#[async_trait]
+impl InviteRepository for Sql {
+    async fn create_invite(&self, invite: Invite) -> Result<Invite, RepositoryError> {
+        let mut conn = self.pool.acquire().await?;
+        insert_invite(&mut conn, &invite).await?;
+        Ok(invite)
+    }
+
+    async fn delete_invite(&self, token: &InviteToken) -> Result<(), RepositoryError> {
+        let mut conn = self.pool.acquire().await?;
+        remove_invite(&mut conn, token).await?;
+        Ok(())
+    }
+
+    async fn get_invite(&self, token: &InviteToken) -> Result<Option<Invite>, RepositoryError> {
+        let row: Option<InviteRow> = sqlx::query_as(
+            "SELECT token, created_at, expires_at, group_id, created_by FROM invites \
+             WHERE token = $1",
+        )
+        .bind(token.to_string())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(Invite::try_from).transpose()?)
+    }
+
+    async fn get_invites_by_group(&self, group: &GroupID) -> Result<Vec<Invite>, RepositoryError> {
+        let rows: Vec<InviteRow> = sqlx::query_as(
+            "SELECT token, created_at, expires_at, group_id, created_by FROM invites \
+             WHERE group_id = $1 ORDER BY seq",
+        )
+        .bind(group.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(Invite::try_from)
+            .collect::<Result<_, _>>()?)
+    }
+}
@@ -0,0 +1,143 @@
+mod group;
+mod invite;
+mod payment;
+mod recurring;
+mod settlement;
+mod transaction;
+mod user;
+
+use crate::repositories::{Repository, RepositoryError};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use shaku::Component;
+use sqlx::{any::AnyPoolOptions, AnyPool};
+use thiserror::Error;
+
+/// SQLite (手元での開発・テスト) か PostgreSQL (本番) に保存する `Repository`
+#[derive(Debug, Component)]
+#[shaku(interface = Repository)]
+pub struct Sql {
+    pub pool: AnyPool,
+}
+
+#[derive(Debug, Error)]
+pub enum SqlError {
+    #[error("sql error")]
+    Sqlx(#[from] sqlx::Error),
+
+    #[error("migration error")]
+    Migrate(#[from] sqlx::migrate::MigrateError),
+
+    #[error("json error")]
+    Json(#[from] serde_json::Error),
+
+    #[error("not a text value: {0}")]
+    NotText(String),
+
+    #[error("unsupported database url: {0}")]
+    UnsupportedUrl(String),
+
+    #[error("not found")]
+    NotFound,
+}
+
+#[derive(Debug)]
+pub struct SqlConfig<'a> {
+    /// `sqlite:...` か `postgres://...`
+    pub url: &'a str,
+}
+
+impl Sql {
+    pub async fn new(config: SqlConfig<'_>) -> Result<Self, SqlError> {
+        sqlx::any::install_default_drivers();
+
+        let mut options = AnyPoolOptions::new();
+        if config.url.contains(":memory:") {
+            // SQLite のインメモリデータベースは接続ごとに別になる
+            options = options.max_connections(1);
+        }
+        let pool = options.connect(config.url).await?;
+
+        let sql = Sql { pool };
+        sql.migrate(config.url).await?;
+        Ok(sql)
+    }
+
+    /// `migrations/` のスキーマを埋め込んでおき、起動時に未適用のものを適用する
+    async fn migrate(&self, url: &str) -> Result<(), SqlError> {
+        if url.starts_with("sqlite:") {
+            sqlx::migrate!("migrations/sqlite").run(&self.pool).await?;
+        } else if url.starts_with("postgres:") || url.starts_with("postgresql:") {
+            sqlx::migrate!("migrations/postgres")
+                .run(&self.pool)
+                .await?;
+        } else {
+            return Err(SqlError::UnsupportedUrl(url.to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+impl From<SqlError> for RepositoryError {
+    fn from(error: SqlError) -> Self {
+        match error {
+            SqlError::NotFound => RepositoryError::NotFound,
+            SqlError::Sqlx(sqlx::Error::Database(error)) if error.is_unique_violation() => {
+                RepositoryError::Conflict(error.to_string())
+            }
+            error => RepositoryError::backend(error),
+        }
+    }
+}
+
+impl From<sqlx::Error> for RepositoryError {
+    fn from(error: sqlx::Error) -> Self {
+        SqlError::from(error).into()
+    }
+}
+
+/// 文字列として直列化される値 (列挙型や日時) を列の値にする
+fn to_text<T: Serialize>(value: &T) -> Result<String, SqlError> {
+    match serde_json::to_value(value)? {
+        Value::String(text) => Ok(text),
+        value => Err(SqlError::NotText(value.to_string())),
+    }
+}
+
+fn from_text<T: DeserializeOwned>(text: String) -> Result<T, SqlError> {
+    Ok(serde_json::from_value(Value::String(text))?)
+}
+
+/// 分け方や調整など、正規化せずに 1 つの列に保存する値
+fn to_json<T: Serialize>(value: &T) -> Result<String, SqlError> {
+    Ok(serde_json::to_string(value)?)
+}
+
+fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, SqlError> {
+    Ok(serde_json::from_str(json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repositories::conformance_tests;
+
+    conformance_tests!(Sql::new(SqlConfig {
+        url: "sqlite::memory:",
+    })
+    .await
+    .unwrap());
+
+    /// `POSTGRES_TEST_URL` (`postgres://...`) を設定したときだけ PostgreSQL でも確かめる
+    mod postgres {
+        use super::*;
+
+        conformance_tests!({
+            let Ok(url) = std::env::var("POSTGRES_TEST_URL") else {
+                return;
+            };
+            Sql::new(SqlConfig { url: &url }).await.unwrap()
+        });
+    }
+}
@@ -0,0 +1,341 @@
+use crate::{
+    entities::{
+        Currency, ExchangeRate, GroupID, Money, Payer, Payment, PaymentFilter, PaymentID, UserID,
+    },
+    repositories::{
+        sql::{from_json, from_text, to_json, to_text, Sql, SqlError},
+        PaymentRepository, RepositoryError,
+    },
+};
+use async_trait::async_trait;
+use sqlx::{AnyConnection, FromRow};
+use std::collections::HashMap;
+
+/// 分け方 (`split`) と調整 (`adjustments`) は種類ごとに形の違う入れ子の値で、
+/// 列で絞り込むこともないため JSON のまま 1 つの列に保存する。
+/// 絞り込みに使う債務者は `payment_debtors` にも書き出しておく
+#[derive(FromRow)]
+struct PaymentRow {
+    id: String,
+    created_at: String,
+    occurred_at: String,
+    title: String,
+    amount: i64,
+    currency: String,
+    adjustments: String,
+    rate: i64,
+    group_id: String,
+    split: String,
+    category: String,
+}
+
+impl PaymentRow {
+    fn into_payment(self, payers: Vec<Payer>, tags: Vec<String>) -> Result<Payment, SqlError> {
+        Ok(Payment {
+            id: PaymentID::new(self.id),
+            created_at: from_text(self.created_at)?,
+            occurred_at: from_text(self.occurred_at)?,
+            title: self.title,
+            amount: Money::new(self.amount, Currency(self.currency)),
+            adjustments: from_json(&self.adjustments)?,
+            rate: ExchangeRate(self.rate),
+            group: GroupID::new(self.group_id),
+            payers,
+            split: from_json(&self.split)?,
+            category: from_text(self.category)?,
+            tags,
+        })
+    }
+}
+
+const SELECT_PAYMENTS: &str = "SELECT id, created_at, occurred_at, title, amount, currency, \
+     adjustments, rate, group_id, split, category FROM payments";
+
+fn payer_from_row((user, amount, currency): (String, i64, String)) -> Payer {
+    Payer::new(UserID::new(user), Money::new(amount, Currency(currency)))
+}
+
+impl Sql {
+    async fn load_payment(&self, row: PaymentRow) -> Result<Payment, SqlError> {
+        let payers: Vec<(String, i64, String)> = sqlx::query_as(
+            "SELECT user_id, amount, currency FROM payment_payers \
+             WHERE payment_id = $1 ORDER BY position",
+        )
+        .bind(&row.id)
+        .fetch_all(&self.pool)
+        .await?;
+        let tags: Vec<(String,)> =
+            sqlx::query_as("SELECT tag FROM payment_tags WHERE payment_id = $1 ORDER BY position")
+                .bind(&row.id)
+                .fetch_all(&self.pool)
+                .await?;
+
+        row.into_payment(
+            payers.into_iter().map(payer_from_row).collect(),
+            tags.into_iter().map(|(tag,)| tag).collect(),
+        )
+    }
+
+    /// `group` の支払いの行に、支払者とタグを支払いごとではなくグループ単位でまとめて読み込んで合わせる
+    async fn load_payments(
+        &self,
+        group: &GroupID,
+        rows: Vec<PaymentRow>,
+    ) -> Result<Vec<Payment>, SqlError> {
+        let payers: Vec<(String, String, i64, String)> = sqlx::query_as(
+            "SELECT payment_id, user_id, amount, currency FROM payment_payers \
+             WHERE payment_id IN (SELECT id FROM payments WHERE group_id = $1) \
+             ORDER BY payment_id, position",
+        )
+        .bind(group.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+        let tags: Vec<(String, String)> = sqlx::query_as(
+            "SELECT payment_id, tag FROM payment_tags \
+             WHERE payment_id IN (SELECT id FROM payments WHERE group_id = $1) \
+             ORDER BY payment_id, position",
+        )
+        .bind(group.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut payers_by_payment: HashMap<String, Vec<Payer>> = HashMap::new();
+        for (payment, user, amount, currency) in payers {
+            payers_by_payment
+                .entry(payment)
+                .or_default()
+                .push(payer_from_row((user, amount, currency)));
+        }
+        let mut tags_by_payment: HashMap<String, Vec<String>> = HashMap::new();
+        for (payment, tag) in tags {
+            tags_by_payment.entry(payment).or_default().push(tag);
+        }
+
+        rows.into_iter()
+            .map(|row| {
+                let payers = payers_by_payment.remove(&row.id).unwrap_or_default();
+                let tags = tags_by_payment.remove(&row.id).unwrap_or_default();
+                row.into_payment(payers, tags)
+            })
+            .collect()
+    }
+}
+
+async fn insert_payment_members(
+    conn: &mut AnyConnection,
+    payment: &Payment,
+) -> Result<(), SqlError> {
+    let id = payment.id.to_string();
+    for (position, payer) in payment.payers.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO payment_payers (payment_id, position, user_id, amount, currency) \
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(&id)
+        .bind(position as i64)
+        .bind(payer.user.to_string())
+        .bind(payer.amount.amount)
+        .bind(&payer.amount.currency.0)
+        .execute(&mut *conn)
+        .await?;
+    }
+    for (position, debtor) in payment.split.debtors().iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO payment_debtors (payment_id, position, user_id) VALUES ($1, $2, $3)",
+        )
+        .bind(&id)
+        .bind(position as i64)
+        .bind(debtor.to_string())
+        .execute(&mut *conn)
+        .await?;
+    }
+    for (position, tag) in payment.tags.iter().enumerate() {
+        sqlx::query("INSERT INTO payment_tags (payment_id, position, tag) VALUES ($1, $2, $3)")
+            .bind(&id)
+            .bind(position as i64)
+            .bind(tag)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+async fn delete_payment_members(conn: &mut AnyConnection, id: &PaymentID) -> Result<(), SqlError> {
+    for table in ["payment_payers", "payment_debtors", "payment_tags"] {
+        sqlx::query(&format!("DELETE FROM {table} WHERE payment_id = $1"))
+            .bind(id.to_string())
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+pub(super) async fn insert_payment(
+    conn: &mut AnyConnection,
+    payment: &Payment,
+) -> Result<(), SqlError> {
+    sqlx::query(
+        "INSERT INTO payments (id, created_at, occurred_at, title, amount, currency, \
+         adjustments, rate, group_id, split, category) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+    )
+    .bind(payment.id.to_string())
+    .bind(to_text(&payment.created_at)?)
+    .bind(to_text(&payment.occurred_at)?)
+    .bind(&payment.title)
+    .bind(payment.amount.amount)
+    .bind(&payment.amount.currency.0)
+    .bind(to_json(&payment.adjustments)?)
+    .bind(payment.rate.0)
+    .bind(payment.group.to_string())
+    .bind(to_json(&payment.split)?)
+    .bind(to_text(&payment.category)?)
+    .execute(&mut *conn)
+    .await?;
+    insert_payment_members(conn, payment).await?;
+    Ok(())
+}
+
+pub(super) async fn remove_payment(
+    conn: &mut AnyConnection,
+    id: &PaymentID,
+) -> Result<(), SqlError> {
+    let result = sqlx::query("DELETE FROM payments WHERE id = $1")
+        .bind(id.to_string())
+        .execute(&mut *conn)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(SqlError::NotFound);
+    }
+    delete_payment_members(conn, id).await?;
+    Ok(())
+}
+
+pub(super) async fn remove_payments_by_group(
+    conn: &mut AnyConnection,
+    group: &GroupID,
+) -> Result<(), SqlError> {
+    for table in ["payment_payers", "payment_debtors", "payment_tags"] {
+        sqlx::query(&format!(
+            "DELETE FROM {table} WHERE payment_id IN \
+             (SELECT id FROM payments WHERE group_id = $1)"
+        ))
+        .bind(group.to_string())
+        .execute(&mut *conn)
+        .await?;
+    }
+    sqlx::query("DELETE FROM payments WHERE group_id = $1")
+        .bind(group.to_string())
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+pub(super) async fn replace_payment(
+    conn: &mut AnyConnection,
+    payment: &Payment,
+) -> Result<(), SqlError> {
+    let result = sqlx::query(
+        "UPDATE payments SET created_at = $2, occurred_at = $3, title = $4, amount = $5, \
+         currency = $6, adjustments = $7, rate = $8, group_id = $9, split = $10, \
+         category = $11 WHERE id = $1",
+    )
+    .bind(payment.id.to_string())
+    .bind(to_text(&payment.created_at)?)
+    .bind(to_text(&payment.occurred_at)?)
+    .bind(&payment.title)
+    .bind(payment.amount.amount)
+    .bind(&payment.amount.currency.0)
+    .bind(to_json(&payment.adjustments)?)
+    .bind(payment.rate.0)
+    .bind(payment.group.to_string())
+    .bind(to_json(&payment.split)?)
+    .bind(to_text(&payment.category)?)
+    .execute(&mut *conn)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(SqlError::NotFound);
+    }
+    delete_payment_members(conn, &payment.id).await?;
+    insert_payment_members(conn, payment).await?;
+    Ok(())
+}
+
+#[async_trait]
+impl PaymentRepository for Sql {
+    async fn create_payment(&self, payment: Payment) -> Result<Payment, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        insert_payment(&mut tx, &payment).await?;
+        tx.commit().await?;
+        Ok(payment)
+    }
+
+    async fn delete_payment(&self, id: &PaymentID) -> Result<(), RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        remove_payment(&mut tx, id).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn update_payment(&self, payment: Payment) -> Result<Payment, RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        replace_payment(&mut tx, &payment).await?;
+        tx.commit().await?;
+        Ok(payment)
+    }
+
+    async fn get_payment(&self, id: &PaymentID) -> Result<Option<Payment>, RepositoryError> {
+        let row: Option<PaymentRow> = sqlx::query_as(&format!("{SELECT_PAYMENTS} WHERE id = $1"))
+            .bind(id.to_string())
+            .fetch_optional(&self.pool)
+            .await?;
+
+        match row {
+            Some(row) => Ok(Some(self.load_payment(row).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_payments_by_group(
+        &self,
+        group: &GroupID,
+    ) -> Result<Vec<Payment>, RepositoryError> {
+        let rows: Vec<PaymentRow> = sqlx::query_as(&format!(
+            "{SELECT_PAYMENTS} WHERE group_id = $1 ORDER BY seq"
+        ))
+        .bind(group.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(self.load_payments(group, rows).await?)
+    }
+
+    async fn get_payments_by_filter(
+        &self,
+        group: &GroupID,
+        filter: &PaymentFilter,
+    ) -> Result<Vec<Payment>, RepositoryError> {
+        let mut sql = format!("{SELECT_PAYMENTS} WHERE group_id = $1");
+        let mut binds = vec![group.to_string()];
+        if let Some(category) = filter.category {
+            binds.push(to_text(&category)?);
+            sql += &format!(" AND category = ${}", binds.len());
+        }
+        if let Some(tag) = &filter.tag {
+            binds.push(tag.clone());
+            sql += &format!(
+                " AND id IN (SELECT payment_id FROM payment_tags WHERE tag = ${})",
+                binds.len()
+            );
+        }
+        sql += " ORDER BY seq";
+
+        let mut query = sqlx::query_as(&sql);
+        for bind in binds {
+            query = query.bind(bind);
+        }
+        let rows: Vec<PaymentRow> = query.fetch_all(&self.pool).await?;
+
+        Ok(self.load_payments(group, rows).await?)
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{
+    entities::{GroupID, RecurringPayment, RecurringPaymentID},
+    repositories::{
+        sql::{from_json, from_text, to_json, to_text, Sql, SqlError},
+        RecurringPaymentRepository, RepositoryError,
+    },
+};
+use async_trait::async_trait;
+use sqlx::{AnyConnection, FromRow};
+
+#[derive(FromRow)]
+struct RecurringPaymentRow {
+    id: String,
+    created_at: String,
+    group_id: String,
+    schedule: String,
+    starts_at: String,
+    ends_on: Option<String>,
+    last_occurrence: Option<String>,
+    template: String,
+    stalled_reason: Option<String>,
+}
+
+impl TryFrom<RecurringPaymentRow> for RecurringPayment {
+    type Error = SqlError;
+
+    fn try_from(row: RecurringPaymentRow) -> Result<Self, Self::Error> {
+        Ok(RecurringPayment {
+            id: RecurringPaymentID::new(row.id),
+            created_at: from_text(row.created_at)?,
+            group: GroupID::new(row.group_id),
+            schedule: from_json(&row.schedule)?,
+            starts_at: from_text(row.starts_at)?,
+            ends_on: row.ends_on.map(from_text).transpose()?,
+            last_occurrence: row.last_occurrence.map(from_text).transpose()?,
+            template: from_json(&row.template)?,
+            stalled_reason: row.stalled_reason,
+        })
+    }
+}
+
+const SELECT_RECURRING_PAYMENTS: &str = "SELECT id, created_at, group_id, schedule, starts_at, \
+     ends_on, last_occurrence, template, stalled_reason FROM recurring_payments";
+
+pub(super) async fn insert_recurring_payment(
+    conn: &mut AnyConnection,
+    recurring: &RecurringPayment,
+) -> Result<(), SqlError> {
+    sqlx::query(
+        "INSERT INTO recurring_payments \
+         (id, created_at, group_id, schedule, starts_at, ends_on, last_occurrence, template, \
+         stalled_reason) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+    )
+    .bind(recurring.id.to_string())
+    .bind(to_text(&recurring.created_at)?)
+    .bind(recurring.group.to_string())
+    .bind(to_json(&recurring.schedule)?)
+    .bind(to_text(&recurring.starts_at)?)
+    .bind(recurring.ends_on.as_ref().map(to_text).transpose()?)
+    .bind(
+        recurring
+            .last_occurrence
+            .as_ref()
+            .map(to_text)
+            .transpose()?,
+    )
+    .bind(to_json(&recurring.template)?)
+    .bind(recurring.stalled_reason.clone())
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+pub(super) async fn remove_recurring_payment(
+    conn: &mut AnyConnection,
+    id: &RecurringPaymentID,
+) -> Result<(), SqlError> {
+    let result = sqlx::query("DELETE FROM recurring_payments WHERE id = $1")
+        .bind(id.to_string())
+        .execute(&mut *conn)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(SqlError::NotFound);
+    }
+    Ok(())
+}
+
+pub(super) async fn remove_recurring_payments_by_group(
+    conn: &mut AnyConnection,
+    group: &GroupID,
+) -> Result<(), SqlError> {
+    sqlx::query("DELETE FROM recurring_payments WHERE group_id = $1")
+        .bind(group.to_string())
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+pub(super) async fn replace_recurring_payment(
+    conn: &mut AnyConnection,
+    recurring: &RecurringPayment,
+) -> Result<(), SqlError> {
+    let result = sqlx::query(
+        "UPDATE recurring_payments SET created_at = $2, group_id = $3, schedule = $4, \
+         starts_at = $5, ends_on = $6, last_occurrence = $7, template = $8, \
+         stalled_reason = $9 WHERE id = $1",
+    )
+    .bind(recurring.id.to_string())
+    .bind(to_text(&recurring.created_at)?)
+    .bind(recurring.group.to_string())
+    .bind(to_json(&recurring.schedule)?)
+    .bind(to_text(&recurring.starts_at)?)
+    .bind(recurring.ends_on.as_ref().map(to_text).transpose()?)
+    .bind(
+        recurring
+            .last_occurrence
+            .as_ref()
+            .map(to_text)
+            .transpose()?,
+    )
+    .bind(to_json(&recurring.template)?)
+    .bind(recurring.stalled_reason.clone())
+    .execute(&mut *conn)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(SqlError::NotFound);
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl RecurringPaymentRepository for Sql {
+    async fn create_recurring_payment(
+        &self,
+        recurring: RecurringPayment,
+    ) -> Result<RecurringPayment, RepositoryError> {
+        let mut conn = self.pool.acquire().await?;
+        insert_recurring_payment(&mut conn, &recurring).await?;
+        Ok(recurring)
+    }
+
+    async fn delete_recurring_payment(
+        &self,
+        id: &RecurringPaymentID,
+    ) -> Result<(), RepositoryError> {
+        let mut conn = self.pool.acquire().await?;
+        remove_recurring_payment(&mut conn, id).await?;
+        Ok(())
+    }
+
+    async fn update_recurring_payment(
+        &self,
+        recurring: RecurringPayment,
+    ) -> Result<RecurringPayment, RepositoryError> {
+        let mut conn = self.pool.acquire().await?;
+        replace_recurring_payment(&mut conn, &recurring).await?;
+        Ok(recurring)
+    }
+
+    async fn get_recurring_payment(
+        &self,
+        id: &RecurringPaymentID,
+    ) -> Result<Option<RecurringPayment>, RepositoryError> {
+        let row: Option<RecurringPaymentRow> =
+            sqlx::query_as(&format!("{SELECT_RECURRING_PAYMENTS} WHERE id = $1"))
+                .bind(id.to_string())
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(row.map(RecurringPayment::try_from).transpose()?)
+    }
+
+    async fn get_recurring_payments_by_group(
+        &self,
+        group: &GroupID,
+    ) -> Result<Vec<RecurringPayment>, RepositoryError> {
+        let rows: Vec<RecurringPaymentRow> = sqlx::query_as(&format!(
+            "{SELECT_RECURRING_PAYMENTS} WHERE group_id = $1 ORDER BY seq"
+        ))
+        .bind(group.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(RecurringPayment::try_from)
+            .collect::<Result<_, _>>()?)
+    }
+
+    async fn get_recurring_payments(&self) -> Result<Vec<RecurringPayment>, RepositoryError> {
+        let rows: Vec<RecurringPaymentRow> =
+            sqlx::query_as(&format!("{SELECT_RECURRING_PAYMENTS} ORDER BY seq"))
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(RecurringPayment::try_from)
+            .collect::<Result<_, _>>()?)
+    }
+}
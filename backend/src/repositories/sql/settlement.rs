@@ -0,0 +1,163 @@
+use crate::{
+    entities::{Currency, GroupID, Money, Settlement, SettlementID, UserID},
+    repositories::{
+        sql::{from_text, to_text, Sql, SqlError},
+        RepositoryError, SettlementRepository,
+    },
+};
+use async_trait::async_trait;
+use sqlx::{AnyConnection, FromRow};
+
+#[derive(FromRow)]
+struct SettlementRow {
+    id: String,
+    created_at: String,
+    group_id: String,
+    from_user: String,
+    to_user: String,
+    amount: i64,
+    currency: String,
+}
+
+impl TryFrom<SettlementRow> for Settlement {
+    type Error = SqlError;
+
+    fn try_from(row: SettlementRow) -> Result<Self, Self::Error> {
+        Ok(Settlement {
+            id: SettlementID::new(row.id),
+            created_at: from_text(row.created_at)?,
+            group: GroupID::new(row.group_id),
+            from: UserID::new(row.from_user),
+            to: UserID::new(row.to_user),
+            amount: Money::new(row.amount, Currency(row.currency)),
+        })
+    }
+}
+
+pub(super) async fn insert_settlement(
+    conn: &mut AnyConnection,
+    settlement: &Settlement,
+) -> Result<(), SqlError> {
+    sqlx::query(
+        "INSERT INTO settlements (id, created_at, group_id, from_user, to_user, amount, currency) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7)",
+    )
+    .bind(settlement.id.to_string())
+    .bind(to_text(&settlement.created_at)?)
+    .bind(settlement.group.to_string())
+    .bind(settlement.from.to_string())
+    .bind(settlement.to.to_string())
+    .bind(settlement.amount.amount)
+    .bind(&settlement.amount.currency.0)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+pub(super) async fn remove_settlement(
+    conn: &mut AnyConnection,
+    id: &SettlementID,
+) -> Result<(), SqlError> {
+    let result = sqlx::query("DELETE FROM settlements WHERE id = $1")
+        .bind(id.to_string())
+        .execute(&mut *conn)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(SqlError::NotFound);
+    }
+    Ok(())
+}
+
+pub(super) async fn remove_settlements_by_group(
+    conn: &mut AnyConnection,
+    group: &GroupID,
+) -> Result<(), SqlError> {
+    sqlx::query("DELETE FROM settlements WHERE group_id = $1")
+        .bind(group.to_string())
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+pub(super) async fn replace_settlement(
+    conn: &mut AnyConnection,
+    settlement: &Settlement,
+) -> Result<(), SqlError> {
+    let result = sqlx::query(
+        "UPDATE settlements SET created_at = $2, group_id = $3, from_user = $4, to_user = $5, \
+         amount = $6, currency = $7 WHERE id = $1",
+    )
+    .bind(settlement.id.to_string())
+    .bind(to_text(&settlement.created_at)?)
+    .bind(settlement.group.to_string())
+    .bind(settlement.from.to_string())
+    .bind(settlement.to.to_string())
+    .bind(settlement.amount.amount)
+    .bind(&settlement.amount.currency.0)
+    .execute(&mut *conn)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(SqlError::NotFound);
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl SettlementRepository for Sql {
+    async fn create_settlement(
+        &self,
+        settlement: Settlement,
+    ) -> Result<Settlement, RepositoryError> {
+        let mut conn = self.pool.acquire().await?;
+        insert_settlement(&mut conn, &settlement).await?;
+        Ok(settlement)
+    }
+
+    async fn delete_settlement(&self, id: &SettlementID) -> Result<(), RepositoryError> {
+        let mut conn = self.pool.acquire().await?;
+        remove_settlement(&mut conn, id).await?;
+        Ok(())
+    }
+
+    async fn update_settlement(
+        &self,
+        settlement: Settlement,
+    ) -> Result<Settlement, RepositoryError> {
+        let mut conn = self.pool.acquire().await?;
+        replace_settlement(&mut conn, &settlement).await?;
+        Ok(settlement)
+    }
+
+    async fn get_settlement(
+        &self,
+        id: &SettlementID,
+    ) -> Result<Option<Settlement>, RepositoryError> {
+        let row: Option<SettlementRow> = sqlx::query_as(
+            "SELECT id, created_at, group_id, from_user, to_user, amount, currency \
+             FROM settlements WHERE id = $1",
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(Settlement::try_from).transpose()?)
+    }
+
+    async fn get_settlements_by_group(
+        &self,
+        group: &GroupID,
+    ) -> Result<Vec<Settlement>, RepositoryError> {
+        let rows: Vec<SettlementRow> = sqlx::query_as(
+            "SELECT id, created_at, group_id, from_user, to_user, amount, currency \
+             FROM settlements WHERE group_id = $1 ORDER BY seq",
+        )
+        .bind(group.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(Settlement::try_from)
+            .collect::<Result<_, _>>()?)
+    }
+}
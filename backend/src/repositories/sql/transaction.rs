@@ -0,0 +1,65 @@
+use crate::repositories::{
+    sql::{
+        group::{insert_group, remove_group, replace_group},
+        invite::{insert_invite, remove_invite, remove_invites_by_group},
+        payment::{insert_payment, remove_payment, remove_payments_by_group, replace_payment},
+        recurring::{
+            insert_recurring_payment, remove_recurring_payment, remove_recurring_payments_by_group,
+            replace_recurring_payment,
+        },
+        settlement::{
+            insert_settlement, remove_settlement, remove_settlements_by_group, replace_settlement,
+        },
+        user::{insert_user, remove_user},
+        Sql, SqlError,
+    },
+    Operation, RepositoryError, TransactionRepository, UnitOfWork,
+};
+use async_trait::async_trait;
+use sqlx::AnyConnection;
+
+#[async_trait]
+impl TransactionRepository for Sql {
+    async fn commit(&self, work: UnitOfWork) -> Result<(), RepositoryError> {
+        // エラーで `tx` がコミットされずに破棄されるとロールバックされる
+        let mut tx = self.pool.begin().await?;
+        for operation in work.operations {
+            apply(&mut tx, operation).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+async fn apply(conn: &mut AnyConnection, operation: Operation) -> Result<(), SqlError> {
+    match operation {
+        Operation::CreateGroup(group) => insert_group(conn, &group).await,
+        Operation::UpdateGroup(group) => replace_group(conn, &group).await,
+        Operation::DeleteGroup(id) => remove_group(conn, &id).await,
+        Operation::CreateInvite(invite) => insert_invite(conn, &invite).await,
+        Operation::DeleteInvite(token) => remove_invite(conn, &token).await,
+        Operation::DeleteInvitesByGroup(group) => remove_invites_by_group(conn, &group).await,
+        Operation::CreatePayment(payment) => insert_payment(conn, &payment).await,
+        Operation::UpdatePayment(payment) => replace_payment(conn, &payment).await,
+        Operation::DeletePayment(id) => remove_payment(conn, &id).await,
+        Operation::DeletePaymentsByGroup(group) => remove_payments_by_group(conn, &group).await,
+        Operation::CreateRecurringPayment(recurring) => {
+            insert_recurring_payment(conn, &recurring).await
+        }
+        Operation::UpdateRecurringPayment(recurring) => {
+            replace_recurring_payment(conn, &recurring).await
+        }
+        Operation::DeleteRecurringPayment(id) => remove_recurring_payment(conn, &id).await,
+        Operation::DeleteRecurringPaymentsByGroup(group) => {
+            remove_recurring_payments_by_group(conn, &group).await
+        }
+        Operation::CreateSettlement(settlement) => insert_settlement(conn, &settlement).await,
+        Operation::UpdateSettlement(settlement) => replace_settlement(conn, &settlement).await,
+        Operation::DeleteSettlement(id) => remove_settlement(conn, &id).await,
+        Operation::DeleteSettlementsByGroup(group) => {
+            remove_settlements_by_group(conn, &group).await
+        }
+        Operation::CreateUser(user) => insert_user(conn, &user).await,
+        Operation::DeleteUser(id) => remove_user(conn, &id).await,
+    }
+}
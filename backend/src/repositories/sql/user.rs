@@ -0,0 +1,57 @@
+use crate::{
+    entities::{User, UserID},
+    repositories::{
+        sql::{Sql, SqlError},
+        RepositoryError, UserRepository,
+    },
+};
+use async_trait::async_trait;
+use sqlx::AnyConnection;
+
+pub(super) async fn insert_user(conn: &mut AnyConnection, user: &User) -> Result<(), SqlError> {
+    sqlx::query("INSERT INTO users (id, name) VALUES ($1, $2)")
+        .bind(user.id.to_string())
+        .bind(&user.name)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+pub(super) async fn remove_user(conn: &mut AnyConnection, id: &UserID) -> Result<(), SqlError> {
+    let result = sqlx::query("DELETE FROM users WHERE id = $1")
+        .bind(id.to_string())
+        .execute(&mut *conn)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(SqlError::NotFound);
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl UserRepository for Sql {
+    async fn create_user(&self, user: User) -> Result<User, RepositoryError> {
+        let mut conn = self.pool.acquire().await?;
+        insert_user(&mut conn, &user).await?;
+        Ok(user)
+    }
+
+    async fn delete_user(&self, id: &UserID) -> Result<(), RepositoryError> {
+        let mut conn = self.pool.acquire().await?;
+        remove_user(&mut conn, id).await?;
+        Ok(())
+    }
+
+    async fn get_user(&self, id: &UserID) -> Result<Option<User>, RepositoryError> {
+        let row: Option<(String, String)> =
+            sqlx::query_as("SELECT id, name FROM users WHERE id = $1")
+                .bind(id.to_string())
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(row.map(|(id, name)| User {
+            id: UserID::new(id),
+            name,
+        }))
+    }
+}
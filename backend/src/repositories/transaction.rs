@@ -0,0 +1,49 @@
+use crate::entities::{
+    Group, GroupID, Invite, InviteToken, Payment, PaymentID, RecurringPayment, RecurringPaymentID,
+    Settlement, SettlementID, User, UserID,
+};
+
+/// まとめて適用する書き込み。`TransactionRepository::commit` ですべて適用されるか、
+/// 1 つも適用されないかのどちらかになる
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnitOfWork {
+    pub operations: Vec<Operation>,
+}
+
+/// 各 `Repository` の書き込みメソッドに対応する操作。
+/// `Delete*ByGroup` はグループのものを適用する時点ですべて削除し、1 つもなくてもエラーにしない
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    CreateGroup(Group),
+    UpdateGroup(Group),
+    DeleteGroup(GroupID),
+    CreateInvite(Invite),
+    DeleteInvite(InviteToken),
+    DeleteInvitesByGroup(GroupID),
+    CreatePayment(Payment),
+    UpdatePayment(Payment),
+    DeletePayment(PaymentID),
+    DeletePaymentsByGroup(GroupID),
+    CreateRecurringPayment(RecurringPayment),
+    UpdateRecurringPayment(RecurringPayment),
+    DeleteRecurringPayment(RecurringPaymentID),
+    DeleteRecurringPaymentsByGroup(GroupID),
+    CreateSettlement(Settlement),
+    UpdateSettlement(Settlement),
+    DeleteSettlement(SettlementID),
+    DeleteSettlementsByGroup(GroupID),
+    CreateUser(User),
+    DeleteUser(UserID),
+}
+
+impl UnitOfWork {
+    pub fn push(&mut self, operation: Operation) {
+        self.operations.push(operation);
+    }
+}
+
+impl Extend<Operation> for UnitOfWork {
+    fn extend<I: IntoIterator<Item = Operation>>(&mut self, operations: I) {
+        self.operations.extend(operations);
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{entities::Payment, repositories::Clock, usecases::UseCase};
+use std::{sync::Arc, time::Duration};
+
+/// 定期的に繰り返す支払いのうち、支払い日が来たものを `Payment` として記録するバックグラウンドタスク
+pub struct Scheduler {
+    pub usecase: UseCase,
+    pub clock: Arc<dyn Clock>,
+    pub interval: Duration,
+}
+
+impl Scheduler {
+    pub fn new(usecase: UseCase, clock: Arc<dyn Clock>, interval: Duration) -> Self {
+        Self {
+            usecase,
+            clock,
+            interval,
+        }
+    }
+
+    pub async fn tick(&self) -> Result<Vec<Payment>, Box<dyn std::error::Error + Send + Sync>> {
+        self.usecase
+            .materialize_recurring_payments(self.clock.now())
+            .await
+    }
+
+    /// `interval` ごとに `tick` する。失敗しても次の `tick` でやり直す。
+    /// 記録できない繰り返す支払いは、その理由を `RecurringPayment::stalled_reason` に残す
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval);
+        loop {
+            interval.tick().await;
+            let _ = self.tick().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        entities::{Group, MonthlySchedule, Payer, RecurringPayment, Schedule, Split},
+        repositories::{MockExchangeRateProvider, MockRepository},
+    };
+    use chrono::{DateTime, Utc};
+    use fake::{Fake, Faker};
+    use std::sync::Mutex;
+
+    /// テストから進める時計
+    struct FakeClock(Mutex<DateTime<Utc>>);
+
+    impl FakeClock {
+        fn advance(&self, now: &str) {
+            *self.0.lock().unwrap() = now.parse().unwrap();
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> DateTime<Utc> {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    #[tokio::test]
+    async fn tick() {
+        let group: Group = Faker.fake();
+        let mut recurring: RecurringPayment = Faker.fake();
+        recurring.group = group.id.clone();
+        recurring.schedule = Schedule::Monthly(MonthlySchedule { day: 25 });
+        recurring.starts_at = "2024-01-25T00:00:00Z".parse().unwrap();
+        recurring.ends_on = None;
+        recurring.last_occurrence = None;
+        recurring.template.amount = Faker.fake();
+        recurring.template.adjustments = vec![];
+        recurring.template.rate = None;
+        recurring.template.payers = vec![Payer::new(
+            group.participants[0].clone(),
+            recurring.template.amount.clone(),
+        )];
+        recurring.template.split = Split::equal(group.participants.clone());
+
+        // 記録した支払いと繰り返す支払いを保持する
+        let payments: Arc<Mutex<Vec<Payment>>> = Arc::default();
+        let recurrings = Arc::new(Mutex::new(vec![recurring]));
+
+        let mut mock = MockRepository::new();
+        {
+            let recurrings = recurrings.clone();
+            mock.expect_get_recurring_payments()
+                .returning(move || Ok(recurrings.lock().unwrap().clone()));
+        }
+        {
+            let recurrings = recurrings.clone();
+            mock.expect_update_recurring_payment()
+                .returning(move |recurring| {
+                    *recurrings.lock().unwrap() = vec![recurring.clone()];
+                    Ok(recurring)
+                });
+        }
+        {
+            let payments = payments.clone();
+            mock.expect_get_payment().returning(move |id| {
+                let payments = payments.lock().unwrap();
+                Ok(payments.iter().find(|payment| &payment.id == id).cloned())
+            });
+        }
+        {
+            let payments = payments.clone();
+            mock.expect_create_payment().returning(move |payment| {
+                payments.lock().unwrap().push(payment.clone());
+                Ok(payment)
+            });
+        }
+        mock.expect_get_group()
+            .returning(move |_| Ok(Some(group.clone())));
+
+        let clock = Arc::new(FakeClock(Mutex::new(
+            "2024-01-24T00:00:00Z".parse().unwrap(),
+        )));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let scheduler = Scheduler::new(usecase, clock.clone(), Duration::from_secs(60));
+
+        assert!(scheduler.tick().await.unwrap().is_empty());
+
+        clock.advance("2024-01-25T00:00:00Z");
+        assert_eq!(scheduler.tick().await.unwrap().len(), 1);
+        assert!(scheduler.tick().await.unwrap().is_empty());
+
+        // 止まっていた間の分もまとめて記録する
+        clock.advance("2024-04-30T00:00:00Z");
+        assert_eq!(scheduler.tick().await.unwrap().len(), 3);
+
+        assert_eq!(payments.lock().unwrap().len(), 4);
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{
+    entities::{compute_balances, settlement_plan, AuthState, Balance, GroupID, Transfer},
+    usecases::UseCase,
+};
+
+impl UseCase {
+    pub async fn get_balances(
+        &self,
+        group: &GroupID,
+        auth: &AuthState,
+    ) -> Result<Vec<Balance>, Box<dyn std::error::Error + Send + Sync>> {
+        let group = self.get_group(group, auth).await?;
+        let payments = self.repository.get_payments_by_group(&group.id).await?;
+        let settlements = self.repository.get_settlements_by_group(&group.id).await?;
+        let balances = compute_balances(&group, &payments, &settlements)?;
+        Ok(balances)
+    }
+
+    pub async fn get_settlement_plan(
+        &self,
+        group: &GroupID,
+        auth: &AuthState,
+    ) -> Result<Vec<Transfer>, Box<dyn std::error::Error + Send + Sync>> {
+        let balances = self.get_balances(group, auth).await?;
+        Ok(settlement_plan(&balances))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        entities::{Claims, Group, Payer, Payment, Split},
+        repositories::{MockExchangeRateProvider, MockRepository},
+    };
+    use fake::{Fake, Faker};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn get_balances_unauthorized_1() {
+        let group: Group = Faker.fake();
+
+        let id = group.id.clone();
+        let auth = AuthState::UnAuthorized;
+
+        let mock = MockRepository::new();
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.get_balances(&id, &auth).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn get_balances_unauthorized_2() {
+        let group: Group = Faker.fake();
+        let claims: Claims = Faker.fake();
+
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.get_balances(&id, &auth).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn get_balances_authorized() {
+        let group: Group = Faker.fake();
+        let mut payment: Payment = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        payment.group = group.id.clone();
+        payment.payers = vec![Payer::new(
+            group.participants[0].clone(),
+            payment.amount.clone(),
+        )];
+        payment.split = Split::equal(group.participants.clone());
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let participants = group.participants.len();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+        mock.expect_get_payments_by_group()
+            .return_once(move |_| Ok(vec![payment]));
+        mock.expect_get_settlements_by_group()
+            .return_once(move |_| Ok(vec![]));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let balances = usecase.get_balances(&id, &auth).await.unwrap();
+        assert_eq!(balances.len(), participants);
+    }
+
+    #[tokio::test]
+    async fn get_settlement_plan_unauthorized() {
+        let group: Group = Faker.fake();
+
+        let id = group.id.clone();
+        let auth = AuthState::UnAuthorized;
+
+        let mock = MockRepository::new();
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.get_settlement_plan(&id, &auth).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn get_settlement_plan_authorized() {
+        let group: Group = Faker.fake();
+        let mut payment: Payment = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        payment.group = group.id.clone();
+        payment.payers = vec![Payer::new(
+            group.participants[0].clone(),
+            payment.amount.clone(),
+        )];
+        payment.split = Split::equal(group.participants.clone());
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+        mock.expect_get_payments_by_group()
+            .return_once(move |_| Ok(vec![payment]));
+        mock.expect_get_settlements_by_group()
+            .return_once(move |_| Ok(vec![]));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.get_settlement_plan(&id, &auth).await.is_ok());
+    }
+}
@@ -1,7 +1,9 @@
-use futures::future::try_join_all;
-
 use crate::{
-    entities::{AuthState, Group, GroupID, UserID},
+    entities::{
+        compute_balances, AuthState, Currency, ExchangeRate, Group, GroupID, Payment, Role,
+        RoundingPolicy, UserID,
+    },
+    repositories::{Operation, UnitOfWork},
     usecases::{UseCase, UseCaseError},
 };
 
@@ -9,10 +11,15 @@ impl UseCase {
     pub async fn create_group(
         &self,
         title: String,
+        currency: Option<Currency>,
         auth: &AuthState,
     ) -> Result<Group, Box<dyn std::error::Error + Send + Sync>> {
         if let AuthState::Authorized(claims) = auth {
-            let group = Group::new(title, claims);
+            let currency = currency.unwrap_or_default();
+            if !currency.is_valid() {
+                return Err(UseCaseError::InvalidCurrency)?;
+            }
+            let group = Group::new(title, currency, claims);
             let group = self.repository.create_group(group).await?;
             Ok(group)
         } else {
@@ -25,20 +32,20 @@ impl UseCase {
         id: &GroupID,
         auth: &AuthState,
     ) -> Result<GroupID, Box<dyn std::error::Error + Send + Sync>> {
-        if self.have_authority_group(id, auth).await {
-            try_join_all(
-                self.repository
-                    .get_payments_by_group(id)
-                    .await?
-                    .iter()
-                    .map(|payment| async { self.repository.delete_payment(&payment.id).await }),
-            )
-            .await?;
-            self.repository.delete_group(id).await?;
-            Ok(id.clone())
-        } else {
-            Err(UseCaseError::UnAuthorized)?
-        }
+        let group = self.get_group(id, auth).await?;
+        require_role(&group, auth, Role::Owner)?;
+        // 関連するものをまとめて削除し、途中で失敗したときに一部だけ消えた状態を残さない。
+        // 一覧を取ってから ID で消すと、その間に作られたものが残るのでグループで消す
+        let mut work = UnitOfWork::default();
+        work.extend([
+            Operation::DeletePaymentsByGroup(id.clone()),
+            Operation::DeleteSettlementsByGroup(id.clone()),
+            Operation::DeleteInvitesByGroup(id.clone()),
+            Operation::DeleteRecurringPaymentsByGroup(id.clone()),
+        ]);
+        work.push(Operation::DeleteGroup(id.clone()));
+        self.repository.commit(work).await?;
+        Ok(id.clone())
     }
 
     pub async fn get_group_opt(
@@ -92,6 +99,194 @@ impl UseCase {
         }
     }
 
+    pub async fn set_rounding_policy(
+        &self,
+        id: &GroupID,
+        rounding: RoundingPolicy,
+        auth: &AuthState,
+    ) -> Result<Group, Box<dyn std::error::Error + Send + Sync>> {
+        let mut group = self.get_group(id, auth).await?;
+        require_role(&group, auth, Role::Admin)?;
+        group.rounding = rounding;
+        let group = self.repository.update_group(group).await?;
+        Ok(group)
+    }
+
+    /// `rate` が `None` なら手動設定を取り消す
+    pub async fn set_exchange_rate(
+        &self,
+        id: &GroupID,
+        currency: Currency,
+        rate: Option<ExchangeRate>,
+        auth: &AuthState,
+    ) -> Result<Group, Box<dyn std::error::Error + Send + Sync>> {
+        let mut group = self.get_group(id, auth).await?;
+        require_role(&group, auth, Role::Admin)?;
+        if !currency.is_valid() || currency == group.currency {
+            return Err(UseCaseError::InvalidCurrency)?;
+        }
+        if rate.is_some_and(|rate| !rate.is_valid()) {
+            return Err(UseCaseError::InvalidExchangeRate)?;
+        }
+        group.set_rate_override(currency, rate);
+        let group = self.repository.update_group(group).await?;
+        Ok(group)
+    }
+
+    pub async fn leave_group(
+        &self,
+        id: &GroupID,
+        auth: &AuthState,
+    ) -> Result<GroupID, Box<dyn std::error::Error + Send + Sync>> {
+        let group = self.get_group(id, auth).await?;
+        let user = require_role(&group, auth, Role::Member)?;
+        if group.role(&user) == Some(Role::Owner) && 1 < group.participants.len() {
+            return Err(UseCaseError::OwnerCannotLeave)?;
+        }
+        self.remove_user(group, &user, false).await?;
+        Ok(id.clone())
+    }
+
+    /// 自分より低い権限の参加者か、ゲストを外す
+    pub async fn remove_participant(
+        &self,
+        id: &GroupID,
+        user: &UserID,
+        force: bool,
+        auth: &AuthState,
+    ) -> Result<Group, Box<dyn std::error::Error + Send + Sync>> {
+        let group = self.get_group(id, auth).await?;
+        let actor = require_role(&group, auth, Role::Admin)?;
+        if let Some(role) = group.role(user) {
+            if group.role(&actor) <= Some(role) {
+                return Err(UseCaseError::CannotRemoveHigherRole(role))?;
+            }
+        }
+        let group = self.remove_user(group, user, force).await?;
+        Ok(group)
+    }
+
+    pub async fn transfer_ownership(
+        &self,
+        id: &GroupID,
+        user: &UserID,
+        auth: &AuthState,
+    ) -> Result<Group, Box<dyn std::error::Error + Send + Sync>> {
+        let mut group = self.get_group(id, auth).await?;
+        require_role(&group, auth, Role::Owner)?;
+        if !group.participants.contains(user) {
+            return Err(UseCaseError::NotParticipants(vec![user.clone()]))?;
+        }
+        group.set_role(user, Role::Owner);
+        let group = self.repository.update_group(group).await?;
+        Ok(group)
+    }
+
+    /// オーナーの変更は `transfer_ownership` で行う
+    pub async fn set_participant_role(
+        &self,
+        id: &GroupID,
+        user: &UserID,
+        role: Role,
+        auth: &AuthState,
+    ) -> Result<Group, Box<dyn std::error::Error + Send + Sync>> {
+        let mut group = self.get_group(id, auth).await?;
+        require_role(&group, auth, Role::Owner)?;
+        match group.role(user) {
+            None => Err(UseCaseError::NotParticipants(vec![user.clone()]))?,
+            Some(Role::Owner) => Err(UseCaseError::InvalidRole)?,
+            Some(_) if role == Role::Owner => Err(UseCaseError::InvalidRole)?,
+            Some(_) => {}
+        }
+        group.set_role(user, role);
+        let group = self.repository.update_group(group).await?;
+        Ok(group)
+    }
+
+    /// 清算が済んでいないうちは、残高が残っているか未清算の支払いに関わっている参加者 (ゲストを含む) を外せない。
+    /// `force` の場合は債務者としての負担分を残りの債務者に振り分けてから外す (`Split::without`)。
+    /// 付け替えると返済の記録と差し引きが合わなくなるため、返済に関わっている参加者は外せない
+    async fn remove_user(
+        &self,
+        mut group: Group,
+        user: &UserID,
+        force: bool,
+    ) -> Result<Group, Box<dyn std::error::Error + Send + Sync>> {
+        let guest = group.guests.contains(user);
+        if !group.participants.contains(user) && !guest {
+            return Err(UseCaseError::NotParticipants(vec![user.clone()]))?;
+        }
+        if !guest && group.participants.len() == 1 {
+            return Err(UseCaseError::LastParticipant)?;
+        }
+
+        let payments = self.repository.get_payments_by_group(&group.id).await?;
+        let settlements = self.repository.get_settlements_by_group(&group.id).await?;
+        let balances = compute_balances(&group, &payments, &settlements)?;
+
+        let mut work = UnitOfWork::default();
+        if balances.iter().any(|balance| !balance.net.is_zero()) {
+            let referenced: Vec<&Payment> = payments
+                .iter()
+                .filter(|payment| payment.is_payer(user) || payment.split.debtors().contains(user))
+                .collect();
+
+            if !force {
+                if balances
+                    .iter()
+                    .any(|balance| &balance.user == user && !balance.net.is_zero())
+                {
+                    return Err(UseCaseError::OutstandingBalance(user.clone()))?;
+                }
+                if !referenced.is_empty() {
+                    return Err(UseCaseError::ReferencedByPayments(
+                        referenced
+                            .iter()
+                            .map(|payment| payment.id.clone())
+                            .collect(),
+                    ))?;
+                }
+            } else {
+                let paid: Vec<_> = referenced
+                    .iter()
+                    .filter(|payment| payment.is_payer(user))
+                    .map(|payment| payment.id.clone())
+                    .collect();
+                if !paid.is_empty() {
+                    return Err(UseCaseError::ReferencedByPayments(paid))?;
+                }
+                let settled: Vec<_> = settlements
+                    .iter()
+                    .filter(|settlement| &settlement.from == user || &settlement.to == user)
+                    .map(|settlement| settlement.id.clone())
+                    .collect();
+                if !settled.is_empty() {
+                    return Err(UseCaseError::ReferencedBySettlements(settled))?;
+                }
+
+                for payment in referenced {
+                    let Some(creditor) = payment.creditor() else {
+                        return Err(UseCaseError::InvalidPayers)?;
+                    };
+                    let mut payment = payment.clone();
+                    payment.split = payment.split.without(user, creditor);
+                    work.push(Operation::UpdatePayment(payment));
+                }
+            }
+        }
+
+        group.participants.retain(|participant| participant != user);
+        group.guests.retain(|g| g != user);
+        group.roles.retain(|role| &role.user != user);
+        work.push(Operation::UpdateGroup(group.clone()));
+        if guest {
+            // ゲストはほかのグループに属さないので、アカウントごと削除する
+            work.push(Operation::DeleteUser(user.clone()));
+        }
+        self.repository.commit(work).await?;
+        Ok(group)
+    }
+
     // TODO(2shiori17): `get_group_opt`を使ったロジックに変更する
     pub async fn have_authority_group(&self, id: &GroupID, auth: &AuthState) -> bool {
         if let AuthState::Authorized(claims) = auth {
@@ -103,12 +298,28 @@ impl UseCase {
     }
 }
 
+/// 呼び出したユーザーが `role` 以上の権限を持っていれば、そのユーザーを返す
+fn require_role(group: &Group, auth: &AuthState, role: Role) -> Result<UserID, UseCaseError> {
+    let AuthState::Authorized(claims) = auth else {
+        return Err(UseCaseError::UnAuthorized);
+    };
+    let user = UserID::new(&claims.sub);
+    match group.role(&user) {
+        Some(actual) if role <= actual => Ok(user),
+        Some(_) => Err(UseCaseError::InsufficientRole(role)),
+        None => Err(UseCaseError::UnAuthorized),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        entities::{Claims, Payment},
-        repositories::MockRepository,
+        entities::{Claims, Invite, Money, Payer, RecurringPayment, Settlement, Split, User},
+        repositories::{
+            GroupRepository, InMemory, InviteRepository, MockExchangeRateProvider, MockRepository,
+            PaymentRepository, RecurringPaymentRepository, SettlementRepository, UserRepository,
+        },
     };
     use fake::{Fake, Faker};
     use std::sync::Arc;
@@ -120,8 +331,8 @@ mod tests {
 
         let mock = MockRepository::new();
 
-        let usecase = UseCase::new(Arc::new(mock));
-        assert!(usecase.create_group(title, &auth).await.is_err());
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.create_group(title, None, &auth).await.is_err());
     }
 
     #[tokio::test]
@@ -134,8 +345,8 @@ mod tests {
         mock.expect_create_group()
             .return_once(move |group| Ok(group));
 
-        let usecase = UseCase::new(Arc::new(mock));
-        assert!(usecase.create_group(title, &auth).await.is_ok());
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.create_group(title, None, &auth).await.is_ok());
     }
 
     #[tokio::test]
@@ -147,7 +358,7 @@ mod tests {
 
         let mock = MockRepository::new();
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.delete_group(&id, &auth).await.is_err());
     }
 
@@ -163,31 +374,69 @@ mod tests {
         mock.expect_get_group()
             .return_once(move |_| Ok(Some(group)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.delete_group(&id, &auth).await.is_err());
     }
 
     #[tokio::test]
     async fn delete_group_authorized() {
+        let repository = Arc::new(InMemory::default());
         let group: Group = Faker.fake();
-        let mut payments: Vec<Payment> = vec![Faker.fake()];
+        let mut payment: Payment = Faker.fake();
+        let mut settlement: Settlement = Faker.fake();
+        let mut recurring: RecurringPayment = Faker.fake();
+        let other: Payment = Faker.fake();
         let mut claims: Claims = Faker.fake();
 
-        payments[0].group = group.id.clone();
+        payment.group = group.id.clone();
+        settlement.group = group.id.clone();
+        recurring.group = group.id.clone();
         claims.sub = group.participants[0].to_string();
         let id = group.id.clone();
+        let invite = Invite::new(id.clone(), group.participants[0].clone());
         let auth = AuthState::Authorized(claims);
 
-        let mut mock = MockRepository::new();
-        mock.expect_delete_payment().return_once(move |_| Ok(()));
-        mock.expect_delete_group().return_once(move |_| Ok(()));
-        mock.expect_get_group()
-            .return_once(move |_| Ok(Some(group)));
-        mock.expect_get_payments_by_group()
-            .return_once(move |_| Ok(payments));
-
-        let usecase = UseCase::new(Arc::new(mock));
+        repository.create_group(group).await.unwrap();
+        repository.create_payment(payment).await.unwrap();
+        repository.create_settlement(settlement).await.unwrap();
+        repository
+            .create_recurring_payment(recurring)
+            .await
+            .unwrap();
+        repository.create_invite(invite).await.unwrap();
+        let other = repository.create_payment(other).await.unwrap();
+
+        let usecase = UseCase::new(
+            repository.clone(),
+            Arc::new(MockExchangeRateProvider::new()),
+        );
         assert!(usecase.delete_group(&id, &auth).await.is_ok());
+
+        assert_eq!(repository.get_group(&id).await.unwrap(), None);
+        assert!(repository
+            .get_payments_by_group(&id)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(repository
+            .get_settlements_by_group(&id)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(repository
+            .get_recurring_payments_by_group(&id)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(repository
+            .get_invites_by_group(&id)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            repository.get_payment(&other.id).await.unwrap(),
+            Some(other)
+        );
     }
 
     #[tokio::test]
@@ -199,7 +448,7 @@ mod tests {
 
         let mock = MockRepository::new();
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.get_group(&id, &auth).await.is_err());
     }
 
@@ -215,7 +464,7 @@ mod tests {
         mock.expect_get_group()
             .return_once(move |_| Ok(Some(group)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.get_group(&id, &auth).await.is_err());
     }
 
@@ -232,7 +481,7 @@ mod tests {
         mock.expect_get_group()
             .return_once(move |_| Ok(Some(group)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.get_group(&id, &auth).await.is_ok());
     }
 
@@ -242,7 +491,7 @@ mod tests {
 
         let mock = MockRepository::new();
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.get_groups_by_user(&auth).await.is_err());
     }
 
@@ -258,10 +507,448 @@ mod tests {
         mock.expect_get_groups_by_user()
             .return_once(move |_| Ok(groups));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.get_groups_by_user(&auth).await.is_ok());
     }
 
+    #[tokio::test]
+    async fn set_rounding_policy_unauthorized() {
+        let group: Group = Faker.fake();
+        let claims: Claims = Faker.fake();
+        let rounding: RoundingPolicy = Faker.fake();
+
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase
+            .set_rounding_policy(&id, rounding, &auth)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn set_rounding_policy_authorized() {
+        let group: Group = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+        let rounding: RoundingPolicy = Faker.fake();
+
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+        mock.expect_update_group()
+            .return_once(move |group| Ok(group));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let group = usecase
+            .set_rounding_policy(&id, rounding, &auth)
+            .await
+            .unwrap();
+        assert_eq!(group.rounding, rounding);
+    }
+
+    #[tokio::test]
+    async fn set_exchange_rate() {
+        let group: Group = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+        let currency = Currency::new("USD").unwrap();
+        let rate = ExchangeRate(150_000_000);
+
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+        mock.expect_update_group()
+            .return_once(move |group| Ok(group));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let group = usecase
+            .set_exchange_rate(&id, currency.clone(), Some(rate), &auth)
+            .await
+            .unwrap();
+        assert_eq!(group.rate_override(&currency), Some(rate));
+    }
+
+    #[tokio::test]
+    async fn leave_group_settled() {
+        let repository = Arc::new(InMemory::default());
+        let mut group: Group = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        group.participants = vec![Faker.fake(), Faker.fake()];
+        claims.sub = group.participants[1].to_string();
+        let id = group.id.clone();
+        let owner = group.participants[0].clone();
+        let auth = AuthState::Authorized(claims);
+
+        repository.create_group(group).await.unwrap();
+
+        let usecase = UseCase::new(
+            repository.clone(),
+            Arc::new(MockExchangeRateProvider::new()),
+        );
+        assert!(usecase.leave_group(&id, &auth).await.is_ok());
+
+        let group = repository.get_group(&id).await.unwrap().unwrap();
+        assert_eq!(group.participants, vec![owner]);
+    }
+
+    #[tokio::test]
+    async fn leave_group_owner() {
+        let mut group: Group = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        group.participants = vec![Faker.fake(), Faker.fake()];
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let err = usecase.leave_group(&id, &auth).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UseCaseError>(),
+            Some(UseCaseError::OwnerCannotLeave)
+        ));
+    }
+
+    #[tokio::test]
+    async fn leave_group_last_participant() {
+        let mut group: Group = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        group.participants = vec![Faker.fake()];
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let err = usecase.leave_group(&id, &auth).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UseCaseError>(),
+            Some(UseCaseError::LastParticipant)
+        ));
+    }
+
+    #[tokio::test]
+    async fn remove_participant_outstanding_balance() {
+        let mut group: Group = Faker.fake();
+        let mut payment: Payment = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        group.participants = vec![Faker.fake(), Faker.fake()];
+        payment.group = group.id.clone();
+        payment.payers = vec![Payer::new(
+            group.participants[0].clone(),
+            payment.amount.clone(),
+        )];
+        payment.split = Split::equal(group.participants.clone());
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let user = group.participants[1].clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+        mock.expect_get_payments_by_group()
+            .return_once(move |_| Ok(vec![payment]));
+        mock.expect_get_settlements_by_group()
+            .return_once(move |_| Ok(vec![]));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let err = usecase
+            .remove_participant(&id, &user, false, &auth)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UseCaseError>(),
+            Some(UseCaseError::OutstandingBalance(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn remove_participant_force() {
+        let repository = Arc::new(InMemory::default());
+        let mut group: Group = Faker.fake();
+        let mut payment: Payment = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        group.participants = vec![Faker.fake(), Faker.fake()];
+        payment.group = group.id.clone();
+        payment.payers = vec![Payer::new(
+            group.participants[0].clone(),
+            payment.amount.clone(),
+        )];
+        payment.split = Split::equal(group.participants.clone());
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let creditor = group.participants[0].clone();
+        let user = group.participants[1].clone();
+        let auth = AuthState::Authorized(claims);
+
+        repository.create_group(group).await.unwrap();
+        let payment = repository.create_payment(payment).await.unwrap();
+
+        let usecase = UseCase::new(
+            repository.clone(),
+            Arc::new(MockExchangeRateProvider::new()),
+        );
+        let group = usecase
+            .remove_participant(&id, &user, true, &auth)
+            .await
+            .unwrap();
+        assert!(!group.participants.contains(&user));
+
+        let payment = repository.get_payment(&payment.id).await.unwrap().unwrap();
+        assert_eq!(payment.split, Split::equal(vec![creditor]));
+        assert_eq!(repository.get_group(&id).await.unwrap(), Some(group));
+    }
+
+    #[tokio::test]
+    async fn remove_participant_force_settled() {
+        let mut group: Group = Faker.fake();
+        let mut payment: Payment = Faker.fake();
+        let mut settlement: Settlement = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        group.participants = vec![Faker.fake(), Faker.fake()];
+        payment.group = group.id.clone();
+        payment.amount = Money::new(1000, group.currency.clone());
+        payment.payers = vec![Payer::new(
+            group.participants[0].clone(),
+            payment.amount.clone(),
+        )];
+        payment.split = Split::equal(group.participants.clone());
+        settlement.group = group.id.clone();
+        settlement.from = group.participants[1].clone();
+        settlement.to = group.participants[0].clone();
+        settlement.amount = Money::new(100, group.currency.clone());
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let user = group.participants[1].clone();
+        let settlement_id = settlement.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+        mock.expect_get_payments_by_group()
+            .return_once(move |_| Ok(vec![payment]));
+        mock.expect_get_settlements_by_group()
+            .return_once(move |_| Ok(vec![settlement]));
+        mock.expect_commit().never();
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let err = usecase
+            .remove_participant(&id, &user, true, &auth)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UseCaseError>(),
+            Some(UseCaseError::ReferencedBySettlements(ids)) if ids == &vec![settlement_id]
+        ));
+    }
+
+    #[tokio::test]
+    async fn remove_participant_insufficient_role() {
+        let mut group: Group = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        group.participants = vec![Faker.fake(), Faker.fake(), Faker.fake()];
+        claims.sub = group.participants[1].to_string();
+        let id = group.id.clone();
+        let user = group.participants[2].clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let err = usecase
+            .remove_participant(&id, &user, false, &auth)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UseCaseError>(),
+            Some(UseCaseError::InsufficientRole(Role::Admin))
+        ));
+    }
+
+    #[tokio::test]
+    async fn remove_participant_higher_role() {
+        let mut group: Group = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        group.participants = vec![Faker.fake(), Faker.fake(), Faker.fake()];
+        group.set_role(&group.participants[1].clone(), Role::Admin);
+        group.set_role(&group.participants[2].clone(), Role::Admin);
+        claims.sub = group.participants[1].to_string();
+        let id = group.id.clone();
+        let owner = group.participants[0].clone();
+        let admin = group.participants[2].clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .times(2)
+            .returning(move |_| Ok(Some(group.clone())));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        for (user, role) in [(owner, Role::Owner), (admin, Role::Admin)] {
+            let err = usecase
+                .remove_participant(&id, &user, false, &auth)
+                .await
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<UseCaseError>(),
+                Some(UseCaseError::CannotRemoveHigherRole(actual)) if *actual == role
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn remove_participant_guest() {
+        let repository = Arc::new(InMemory::default());
+        let mut group: Group = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        let guest = repository
+            .create_user(User::guest(Faker.fake()))
+            .await
+            .unwrap()
+            .id;
+        group.participants = vec![Faker.fake(), Faker.fake()];
+        group.guests = vec![guest.clone()];
+        group.set_role(&group.participants[1].clone(), Role::Admin);
+        claims.sub = group.participants[1].to_string();
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        repository.create_group(group).await.unwrap();
+
+        let usecase = UseCase::new(
+            repository.clone(),
+            Arc::new(MockExchangeRateProvider::new()),
+        );
+        let group = usecase
+            .remove_participant(&id, &guest, false, &auth)
+            .await
+            .unwrap();
+        assert!(!group.is_member(&guest));
+
+        assert_eq!(repository.get_group(&id).await.unwrap(), Some(group));
+        assert_eq!(repository.get_user(&guest).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn delete_group_member() {
+        let mut group: Group = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        group.participants = vec![Faker.fake(), Faker.fake()];
+        claims.sub = group.participants[1].to_string();
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.delete_group(&id, &auth).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn transfer_ownership() {
+        let mut group: Group = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        group.participants = vec![Faker.fake(), Faker.fake()];
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let owner = group.participants[0].clone();
+        let user = group.participants[1].clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+        mock.expect_update_group()
+            .return_once(move |group| Ok(group));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let group = usecase.transfer_ownership(&id, &user, &auth).await.unwrap();
+        assert_eq!(group.role(&user), Some(Role::Owner));
+        assert_eq!(group.role(&owner), Some(Role::Admin));
+    }
+
+    #[tokio::test]
+    async fn set_participant_role() {
+        let mut group: Group = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        group.participants = vec![Faker.fake(), Faker.fake()];
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let user = group.participants[1].clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+        mock.expect_update_group()
+            .return_once(move |group| Ok(group));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let group = usecase
+            .set_participant_role(&id, &user, Role::Admin, &auth)
+            .await
+            .unwrap();
+        assert_eq!(group.role(&user), Some(Role::Admin));
+    }
+
+    #[tokio::test]
+    async fn set_participant_role_owner() {
+        let mut group: Group = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        group.participants = vec![Faker.fake(), Faker.fake()];
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let user = group.participants[1].clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase
+            .set_participant_role(&id, &user, Role::Owner, &auth)
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn have_authority_group_unauthorized_1() {
         let group: Group = Faker.fake();
@@ -271,7 +958,7 @@ mod tests {
 
         let mock = MockRepository::new();
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(!usecase.have_authority_group(&id, &auth).await);
     }
 
@@ -287,7 +974,7 @@ mod tests {
         mock.expect_get_group()
             .return_once(move |_| Ok(Some(group)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(!usecase.have_authority_group(&id, &auth).await);
     }
 
@@ -304,7 +991,7 @@ mod tests {
         mock.expect_get_group()
             .return_once(move |_| Ok(Some(group)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.have_authority_group(&id, &auth).await);
     }
 }
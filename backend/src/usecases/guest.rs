@@ -0,0 +1,206 @@
+use crate::{
+    entities::{AuthState, Group, GroupID, User, UserID},
+    repositories::{Operation, UnitOfWork},
+    usecases::{UseCase, UseCaseError},
+};
+
+impl UseCase {
+    pub async fn add_guest(
+        &self,
+        group: &GroupID,
+        name: String,
+        auth: &AuthState,
+    ) -> Result<User, Box<dyn std::error::Error + Send + Sync>> {
+        let mut group = self.get_group(group, auth).await?;
+        let guest = self.repository.create_user(User::guest(name)).await?;
+        group.guests.push(guest.id.clone());
+        self.repository.update_group(group).await?;
+        Ok(guest)
+    }
+
+    /// ゲストの支払い・返済の記録と繰り返す支払いのテンプレートを呼び出したユーザーに付け替え、
+    /// ゲストを削除する
+    pub async fn claim_guest(
+        &self,
+        group: &GroupID,
+        guest: &UserID,
+        auth: &AuthState,
+    ) -> Result<Group, Box<dyn std::error::Error + Send + Sync>> {
+        let mut group = self.get_group(group, auth).await?;
+        let AuthState::Authorized(claims) = auth else {
+            return Err(UseCaseError::UnAuthorized)?;
+        };
+        let user = UserID::new(&claims.sub);
+        if !group.guests.contains(guest) {
+            return Err(UseCaseError::NotFound)?;
+        }
+
+        let mut work = UnitOfWork::default();
+        for mut payment in self.repository.get_payments_by_group(&group.id).await? {
+            if payment.is_payer(guest) || payment.split.debtors().contains(guest) {
+                payment.replace_payer(guest, &user)?;
+                payment.split = payment.split.replace(guest, &user);
+                work.push(Operation::UpdatePayment(payment));
+            }
+        }
+        for mut settlement in self.repository.get_settlements_by_group(&group.id).await? {
+            if &settlement.from != guest && &settlement.to != guest {
+                continue;
+            }
+            if &settlement.from == guest {
+                settlement.from = user.clone();
+            }
+            if &settlement.to == guest {
+                settlement.to = user.clone();
+            }
+            if settlement.from == settlement.to {
+                work.push(Operation::DeleteSettlement(settlement.id));
+            } else {
+                work.push(Operation::UpdateSettlement(settlement));
+            }
+        }
+
+        for mut recurring in self
+            .repository
+            .get_recurring_payments_by_group(&group.id)
+            .await?
+        {
+            if recurring.template.involves(guest) {
+                recurring.template.replace_member(guest, &user)?;
+                work.push(Operation::UpdateRecurringPayment(recurring));
+            }
+        }
+
+        group.guests.retain(|g| g != guest);
+        work.push(Operation::UpdateGroup(group.clone()));
+        work.push(Operation::DeleteUser(guest.clone()));
+        self.repository.commit(work).await?;
+        Ok(group)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        entities::{Claims, Payer, Payment, RecurringPayment, Settlement, Split},
+        repositories::{
+            GroupRepository, InMemory, MockExchangeRateProvider, MockRepository, PaymentRepository,
+            RecurringPaymentRepository, SettlementRepository, UserRepository,
+        },
+    };
+    use fake::{Fake, Faker};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn add_guest() {
+        let group: Group = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+        let name: String = Faker.fake();
+
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+        mock.expect_create_user().return_once(move |user| Ok(user));
+        mock.expect_update_group()
+            .withf(|group| group.guests.len() == 1)
+            .return_once(move |group| Ok(group));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let guest = usecase.add_guest(&id, name.clone(), &auth).await.unwrap();
+        assert!(guest.id.is_guest());
+        assert_eq!(guest.name, name);
+    }
+
+    #[tokio::test]
+    async fn claim_guest() {
+        let repository = Arc::new(InMemory::default());
+        let mut group: Group = Faker.fake();
+        let mut payment: Payment = Faker.fake();
+        let mut settlement: Settlement = Faker.fake();
+        let mut recurring: RecurringPayment = Faker.fake();
+        let mut other: RecurringPayment = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        let guest = repository
+            .create_user(User::guest(Faker.fake()))
+            .await
+            .unwrap()
+            .id;
+        let user = group.participants[0].clone();
+        group.guests = vec![guest.clone()];
+        payment.group = group.id.clone();
+        payment.payers = vec![Payer::new(guest.clone(), payment.amount.clone())];
+        payment.split = Split::equal(vec![guest.clone(), user.clone()]);
+        settlement.group = group.id.clone();
+        settlement.from = guest.clone();
+        settlement.to = user.clone();
+        recurring.group = group.id.clone();
+        recurring.template.payers =
+            vec![Payer::new(guest.clone(), recurring.template.amount.clone())];
+        recurring.template.split = Split::equal(vec![guest.clone()]);
+        other.group = group.id.clone();
+        claims.sub = user.to_string();
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        repository.create_group(group).await.unwrap();
+        let payment = repository.create_payment(payment).await.unwrap();
+        let settlement = repository.create_settlement(settlement).await.unwrap();
+        let recurring = repository
+            .create_recurring_payment(recurring)
+            .await
+            .unwrap();
+        let other = repository.create_recurring_payment(other).await.unwrap();
+
+        let usecase = UseCase::new(
+            repository.clone(),
+            Arc::new(MockExchangeRateProvider::new()),
+        );
+        let group = usecase.claim_guest(&id, &guest, &auth).await.unwrap();
+        assert!(group.guests.is_empty());
+
+        let payment = repository.get_payment(&payment.id).await.unwrap().unwrap();
+        assert_eq!(payment.creditor(), Some(&user));
+        assert_eq!(payment.split, Split::equal(vec![user.clone()]));
+        assert_eq!(
+            repository.get_settlement(&settlement.id).await.unwrap(),
+            None
+        );
+        let recurring = repository
+            .get_recurring_payment(&recurring.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(recurring.template.payers[0].user, user);
+        assert_eq!(recurring.template.split, Split::equal(vec![user.clone()]));
+        assert_eq!(
+            repository.get_recurring_payment(&other.id).await.unwrap(),
+            Some(other)
+        );
+        assert_eq!(repository.get_group(&id).await.unwrap(), Some(group));
+        assert_eq!(repository.get_user(&guest).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn claim_guest_not_guest() {
+        let group: Group = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let guest = UserID::guest();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.claim_guest(&id, &guest, &auth).await.is_err());
+    }
+}
@@ -0,0 +1,251 @@
+use crate::{
+    entities::{AuthState, Group, GroupID, Invite, InviteToken, UserID},
+    repositories::RepositoryError,
+    usecases::{UseCase, UseCaseError},
+};
+use chrono::Utc;
+
+impl UseCase {
+    pub async fn create_invite(
+        &self,
+        group: &GroupID,
+        auth: &AuthState,
+    ) -> Result<Invite, Box<dyn std::error::Error + Send + Sync>> {
+        match auth {
+            AuthState::Authorized(claims) if self.have_authority_group(group, auth).await => {
+                let invite = Invite::new(group.clone(), UserID::new(&claims.sub));
+                let invite = self.repository.create_invite(invite).await?;
+                Ok(invite)
+            }
+            _ => Err(UseCaseError::UnAuthorized)?,
+        }
+    }
+
+    pub async fn revoke_invite(
+        &self,
+        token: &InviteToken,
+        auth: &AuthState,
+    ) -> Result<InviteToken, Box<dyn std::error::Error + Send + Sync>> {
+        if self.have_authority_invite(token, auth).await {
+            self.repository.delete_invite(token).await?;
+            Ok(token.clone())
+        } else {
+            Err(UseCaseError::UnAuthorized)?
+        }
+    }
+
+    /// 招待を受け入れて呼び出したユーザーをグループに参加させる (参加済みなら何もしない)
+    pub async fn accept_invite(
+        &self,
+        token: &InviteToken,
+        auth: &AuthState,
+    ) -> Result<Group, Box<dyn std::error::Error + Send + Sync>> {
+        let AuthState::Authorized(claims) = auth else {
+            return Err(UseCaseError::UnAuthorized)?;
+        };
+
+        let invite = self
+            .repository
+            .get_invite(token)
+            .await?
+            .filter(|invite| !invite.is_expired(Utc::now()))
+            .ok_or(UseCaseError::InvalidInvite)?;
+        let user = UserID::new(&claims.sub);
+        match self.repository.add_participant(&invite.group, &user).await {
+            Ok(group) => Ok(group),
+            Err(RepositoryError::NotFound) => Err(UseCaseError::InvalidInvite)?,
+            Err(err) => Err(err)?,
+        }
+    }
+
+    pub async fn get_invites_by_group(
+        &self,
+        group: &GroupID,
+        auth: &AuthState,
+    ) -> Result<Vec<Invite>, Box<dyn std::error::Error + Send + Sync>> {
+        if !self.have_authority_group(group, auth).await {
+            return Err(UseCaseError::UnAuthorized)?;
+        }
+        let invites = self.repository.get_invites_by_group(group).await?;
+        Ok(invites)
+    }
+
+    pub async fn have_authority_invite(&self, token: &InviteToken, auth: &AuthState) -> bool {
+        if let Ok(Some(invite)) = self.repository.get_invite(token).await {
+            self.have_authority_group(&invite.group, auth).await
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        entities::Claims,
+        repositories::{MockExchangeRateProvider, MockRepository},
+    };
+    use chrono::Duration;
+    use fake::{Fake, Faker};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn create_invite_unauthorized() {
+        let group: Group = Faker.fake();
+        let claims: Claims = Faker.fake();
+
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.create_invite(&id, &auth).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn create_invite_authorized() {
+        let group: Group = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+        mock.expect_create_invite()
+            .return_once(move |invite| Ok(invite));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let invite = usecase.create_invite(&id, &auth).await.unwrap();
+        assert_eq!(invite.group, id);
+        assert!(!invite.is_expired(Utc::now()));
+    }
+
+    #[tokio::test]
+    async fn revoke_invite_unauthorized() {
+        let group: Group = Faker.fake();
+        let mut invite: Invite = Faker.fake();
+        let claims: Claims = Faker.fake();
+
+        invite.group = group.id.clone();
+        let token = invite.token.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_invite()
+            .return_once(move |_| Ok(Some(invite)));
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.revoke_invite(&token, &auth).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn revoke_invite_authorized() {
+        let group: Group = Faker.fake();
+        let mut invite: Invite = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        invite.group = group.id.clone();
+        claims.sub = group.participants[0].to_string();
+        let token = invite.token.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_invite()
+            .return_once(move |_| Ok(Some(invite)));
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+        mock.expect_delete_invite().return_once(move |_| Ok(()));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.revoke_invite(&token, &auth).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn accept_invite() {
+        let group: Group = Faker.fake();
+        let mut invite: Invite = Faker.fake();
+        let claims: Claims = Faker.fake();
+
+        invite.group = group.id.clone();
+        invite.expires_at = Utc::now() + Duration::days(1);
+        let user = UserID::new(&claims.sub);
+        let token = invite.token.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_invite()
+            .return_once(move |_| Ok(Some(invite)));
+        mock.expect_add_participant().return_once(move |_, user| {
+            let mut group = group;
+            group.participants.push(user.clone());
+            Ok(group)
+        });
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let group = usecase.accept_invite(&token, &auth).await.unwrap();
+        assert!(group.participants.contains(&user));
+    }
+
+    #[tokio::test]
+    async fn accept_invite_already_participant() {
+        let group: Group = Faker.fake();
+        let mut invite: Invite = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        invite.group = group.id.clone();
+        invite.expires_at = Utc::now() + Duration::days(1);
+        claims.sub = group.participants[0].to_string();
+        let participants = group.participants.clone();
+        let token = invite.token.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_invite()
+            .return_once(move |_| Ok(Some(invite)));
+        mock.expect_add_participant()
+            .return_once(move |_, _| Ok(group));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let group = usecase.accept_invite(&token, &auth).await.unwrap();
+        assert_eq!(group.participants, participants);
+    }
+
+    #[tokio::test]
+    async fn accept_invite_expired() {
+        let mut invite: Invite = Faker.fake();
+        let claims: Claims = Faker.fake();
+
+        invite.expires_at = Utc::now() - Duration::seconds(1);
+        let token = invite.token.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_invite()
+            .return_once(move |_| Ok(Some(invite)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.accept_invite(&token, &auth).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn accept_invite_unauthorized() {
+        let invite: Invite = Faker.fake();
+
+        let token = invite.token.clone();
+        let auth = AuthState::UnAuthorized;
+
+        let mock = MockRepository::new();
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.accept_invite(&token, &auth).await.is_err());
+    }
+}
@@ -1,18 +1,35 @@
+mod balance;
 mod group;
+mod guest;
+mod invite;
 mod payment;
+mod recurring;
+mod settlement;
 mod user;
+mod validation;
 
-use crate::repositories::Repository;
+use crate::{
+    entities::{Currency, PaymentID, Role, SettlementID, UserID},
+    repositories::{ExchangeRateProvider, Repository, RepositoryError},
+};
 use std::sync::Arc;
 use thiserror::Error;
 
+#[derive(Clone)]
 pub struct UseCase {
     pub repository: Arc<dyn Repository>,
+    pub exchange_rates: Arc<dyn ExchangeRateProvider>,
 }
 
 impl UseCase {
-    pub fn new(repository: Arc<dyn Repository>) -> Self {
-        Self { repository }
+    pub fn new(
+        repository: Arc<dyn Repository>,
+        exchange_rates: Arc<dyn ExchangeRateProvider>,
+    ) -> Self {
+        Self {
+            repository,
+            exchange_rates,
+        }
     }
 }
 
@@ -23,4 +40,131 @@ pub enum UseCaseError {
 
     #[error("unauthorized")]
     UnAuthorized,
+
+    #[error("invalid amount")]
+    InvalidAmount,
+
+    #[error("invalid currency")]
+    InvalidCurrency,
+
+    #[error("invalid exchange rate")]
+    InvalidExchangeRate,
+
+    #[error("no exchange rate from {} to {}", .0.to_string(), .1.to_string())]
+    ExchangeRateUnavailable(Currency, Currency),
+
+    #[error("invalid split")]
+    InvalidSplit,
+
+    #[error("invalid adjustment")]
+    InvalidAdjustment,
+
+    #[error("payers are empty, duplicated or do not add up to the total")]
+    InvalidPayers,
+
+    #[error("invalid schedule")]
+    InvalidSchedule,
+
+    #[error("invalid settlement")]
+    InvalidSettlement,
+
+    #[error("invalid or expired invite")]
+    InvalidInvite,
+
+    #[error("not participants of the group: {}", join(.0))]
+    NotParticipants(Vec<UserID>),
+
+    #[error("debtors are empty")]
+    EmptyDebtors,
+
+    #[error("debtors are duplicated: {}", join(.0))]
+    DuplicateDebtors(Vec<UserID>),
+
+    #[error("user has an outstanding balance: {}", .0.to_string())]
+    OutstandingBalance(UserID),
+
+    #[error("user is referenced by unsettled payments: {}", join(.0))]
+    ReferencedByPayments(Vec<PaymentID>),
+
+    #[error("user is referenced by settlements: {}", join(.0))]
+    ReferencedBySettlements(Vec<SettlementID>),
+
+    #[error("the last participant cannot leave the group")]
+    LastParticipant,
+
+    #[error("requires the {0:?} role or higher")]
+    InsufficientRole(Role),
+
+    #[error("cannot remove a participant with the {0:?} role")]
+    CannotRemoveHigherRole(Role),
+
+    #[error("the owner must transfer ownership before leaving")]
+    OwnerCannotLeave,
+
+    #[error("invalid role")]
+    InvalidRole,
+
+    #[error("already exists")]
+    Conflict,
+
+    #[error("internal error")]
+    Internal,
+}
+
+/// 保存先のエラーを、クライアントに返すエラーとして読み替える (保存先の詳細は含めない)
+impl From<&RepositoryError> for UseCaseError {
+    fn from(error: &RepositoryError) -> Self {
+        match error {
+            RepositoryError::NotFound => UseCaseError::NotFound,
+            RepositoryError::Conflict(_) => UseCaseError::Conflict,
+            RepositoryError::Backend(_) => UseCaseError::Internal,
+        }
+    }
+}
+
+impl UseCaseError {
+    /// GraphQL のエラーの `extensions.code` に入れる値
+    pub fn code(&self) -> &'static str {
+        match self {
+            UseCaseError::NotFound => "NOT_FOUND",
+            UseCaseError::UnAuthorized => "UNAUTHORIZED",
+            UseCaseError::InsufficientRole(_) | UseCaseError::CannotRemoveHigherRole(_) => {
+                "FORBIDDEN"
+            }
+            UseCaseError::Conflict => "CONFLICT",
+            UseCaseError::OutstandingBalance(_)
+            | UseCaseError::ReferencedByPayments(_)
+            | UseCaseError::ReferencedBySettlements(_)
+            | UseCaseError::LastParticipant
+            | UseCaseError::OwnerCannotLeave
+            | UseCaseError::ExchangeRateUnavailable(_, _) => "FAILED_PRECONDITION",
+            UseCaseError::Internal => "INTERNAL_SERVER_ERROR",
+            _ => "BAD_USER_INPUT",
+        }
+    }
+}
+
+fn join<T: ToString>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repository_error() {
+        let not_found = UseCaseError::from(&RepositoryError::NotFound);
+        let conflict = UseCaseError::from(&RepositoryError::Conflict("id".to_string()));
+        let backend = UseCaseError::from(&RepositoryError::Backend("connection reset".into()));
+
+        assert_eq!(not_found.code(), "NOT_FOUND");
+        assert_eq!(conflict.code(), "CONFLICT");
+        assert_eq!(backend.code(), "INTERNAL_SERVER_ERROR");
+        assert_eq!(backend.to_string(), "internal error");
+    }
 }
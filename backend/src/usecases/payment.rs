@@ -1,24 +1,28 @@
 use crate::{
-    entities::{AuthState, GroupID, Payment, PaymentID, UserID},
-    usecases::{UseCase, UseCaseError},
+    entities::{
+        compute_category_totals, sort_payments, AuthState, CategoryTotal, Currency, ExchangeRate,
+        Group, GroupID, ItemizedSplit, NewPayment, Payment, PaymentFilter, PaymentID, PaymentItem,
+        PaymentItemID, PaymentItemUpdate, PaymentUpdate, Share, Split,
+    },
+    usecases::{validation::validate_payment, UseCase, UseCaseError},
 };
 
 impl UseCase {
     pub async fn create_payment(
         &self,
-        title: String,
         group: GroupID,
-        creditor: UserID,
-        debtors: Vec<UserID>,
+        payment: NewPayment,
         auth: &AuthState,
     ) -> Result<Payment, Box<dyn std::error::Error + Send + Sync>> {
-        if self.have_authority_group(&group, auth).await {
-            let payment = Payment::new(title, group, creditor, debtors);
-            let payment = self.repository.create_payment(payment).await?;
-            Ok(payment)
-        } else {
-            Err(UseCaseError::UnAuthorized)?
-        }
+        let group = self.get_group(&group, auth).await?;
+        let rate = match payment.rate {
+            Some(rate) => rate,
+            None => self.resolve_rate(&group, &payment.amount.currency).await?,
+        };
+        let payment = Payment::new(payment, rate, group.id.clone());
+        validate_payment(&payment, &group)?;
+        let payment = self.repository.create_payment(payment).await?;
+        Ok(payment)
     }
 
     pub async fn delete_payment(
@@ -34,6 +38,45 @@ impl UseCase {
         }
     }
 
+    pub async fn update_payment(
+        &self,
+        id: &PaymentID,
+        update: PaymentUpdate,
+        auth: &AuthState,
+    ) -> Result<Payment, Box<dyn std::error::Error + Send + Sync>> {
+        if !self.have_authority_payment(id, auth).await {
+            return Err(UseCaseError::UnAuthorized)?;
+        }
+        let mut payment = self
+            .repository
+            .get_payment(id)
+            .await?
+            .ok_or(UseCaseError::NotFound)?;
+        let group = self
+            .repository
+            .get_group(&payment.group)
+            .await?
+            .ok_or(UseCaseError::NotFound)?;
+
+        let currency_changed = update
+            .amount
+            .as_ref()
+            .is_some_and(|amount| amount.currency != payment.amount.currency);
+        let resolve = currency_changed && update.rate.is_none();
+        let sync = update.payers.is_none();
+        payment.apply(update);
+        if resolve {
+            payment.rate = self.resolve_rate(&group, &payment.amount.currency).await?;
+        }
+        if sync {
+            payment.sync_sole_payer()?;
+        }
+        validate_payment(&payment, &group)?;
+
+        let payment = self.repository.update_payment(payment).await?;
+        Ok(payment)
+    }
+
     pub async fn get_payment_opt(
         &self,
         id: &PaymentID,
@@ -68,15 +111,143 @@ impl UseCase {
     pub async fn get_payments_by_group(
         &self,
         group: &GroupID,
+        filter: &PaymentFilter,
         auth: &AuthState,
     ) -> Result<Vec<Payment>, Box<dyn std::error::Error + Send + Sync>> {
         if !self.have_authority_group(group, auth).await {
             return Err(UseCaseError::UnAuthorized)?;
         }
-        let payments = self.repository.get_payments_by_group(group).await?;
+        let filter = filter.clone().normalized();
+        let mut payments = self
+            .repository
+            .get_payments_by_filter(group, &filter)
+            .await?;
+        sort_payments(&mut payments);
         Ok(payments)
     }
 
+    pub async fn get_category_totals(
+        &self,
+        group: &GroupID,
+        auth: &AuthState,
+    ) -> Result<Vec<CategoryTotal>, Box<dyn std::error::Error + Send + Sync>> {
+        let group = self.get_group(group, auth).await?;
+        let payments = self.repository.get_payments_by_group(&group.id).await?;
+        Ok(compute_category_totals(&group, &payments)?)
+    }
+
+    pub async fn get_shares(
+        &self,
+        payment: &Payment,
+        auth: &AuthState,
+    ) -> Result<Vec<Share>, Box<dyn std::error::Error + Send + Sync>> {
+        let group = self.get_group(&payment.group, auth).await?;
+        Ok(payment.shares(group.rounding)?)
+    }
+
+    pub async fn add_payment_item(
+        &self,
+        id: &PaymentID,
+        item: PaymentItem,
+        auth: &AuthState,
+    ) -> Result<Payment, Box<dyn std::error::Error + Send + Sync>> {
+        self.modify_items(id, auth, |split| {
+            split.items.push(item);
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn update_payment_item(
+        &self,
+        id: &PaymentID,
+        item: &PaymentItemID,
+        update: PaymentItemUpdate,
+        auth: &AuthState,
+    ) -> Result<Payment, Box<dyn std::error::Error + Send + Sync>> {
+        self.modify_items(id, auth, |split| {
+            let item = split
+                .items
+                .iter_mut()
+                .find(|i| &i.id == item)
+                .ok_or(UseCaseError::NotFound)?;
+            item.apply(update);
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn remove_payment_item(
+        &self,
+        id: &PaymentID,
+        item: &PaymentItemID,
+        auth: &AuthState,
+    ) -> Result<Payment, Box<dyn std::error::Error + Send + Sync>> {
+        self.modify_items(id, auth, |split| {
+            let len = split.items.len();
+            split.items.retain(|i| &i.id != item);
+            if split.items.len() == len {
+                return Err(UseCaseError::NotFound);
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    /// 明細ごとの分け方の支払いの明細を編集する。
+    /// 支払いの金額は変えず、明細・税・チップの合計が金額と一致しなければエラーにする
+    async fn modify_items(
+        &self,
+        id: &PaymentID,
+        auth: &AuthState,
+        modify: impl FnOnce(&mut ItemizedSplit) -> Result<(), UseCaseError> + Send,
+    ) -> Result<Payment, Box<dyn std::error::Error + Send + Sync>> {
+        if !self.have_authority_payment(id, auth).await {
+            return Err(UseCaseError::UnAuthorized)?;
+        }
+        let mut payment = self
+            .repository
+            .get_payment(id)
+            .await?
+            .ok_or(UseCaseError::NotFound)?;
+        let group = self
+            .repository
+            .get_group(&payment.group)
+            .await?
+            .ok_or(UseCaseError::NotFound)?;
+
+        let Split::Itemized(split) = &mut payment.split else {
+            return Err(UseCaseError::InvalidSplit)?;
+        };
+        modify(split)?;
+        validate_payment(&payment, &group)?;
+
+        let payment = self.repository.update_payment(payment).await?;
+        Ok(payment)
+    }
+
+    /// グループで手動設定したレートを優先し、なければ `ExchangeRateProvider` から取得する
+    pub(super) async fn resolve_rate(
+        &self,
+        group: &Group,
+        currency: &Currency,
+    ) -> Result<ExchangeRate, Box<dyn std::error::Error + Send + Sync>> {
+        if currency == &group.currency {
+            return Ok(ExchangeRate::IDENTITY);
+        }
+        if let Some(rate) = group.rate_override(currency) {
+            return Ok(rate);
+        }
+        let rate = self
+            .exchange_rates
+            .get_rate(currency, &group.currency)
+            .await?
+            .ok_or_else(|| {
+                UseCaseError::ExchangeRateUnavailable(currency.clone(), group.currency.clone())
+            })?;
+        Ok(rate)
+    }
+
     // TODO(2shiori17): `get_payment_opt`を使ったロジックに変更する
     pub async fn have_authority_payment(&self, id: &PaymentID, auth: &AuthState) -> bool {
         if let Ok(Some(payment)) = self.repository.get_payment(id).await {
@@ -91,27 +262,38 @@ impl UseCase {
 mod tests {
     use super::*;
     use crate::{
-        entities::{Claims, Group},
-        repositories::MockRepository,
+        entities::{Category, Claims, Money, Payer, PercentageDebtor, PercentagesSplit},
+        repositories::{MockExchangeRateProvider, MockRepository},
     };
     use fake::{Fake, Faker};
     use std::sync::Arc;
 
+    fn new_payment(amount: Money, payers: Vec<Payer>, split: Split) -> NewPayment {
+        NewPayment {
+            amount,
+            adjustments: vec![],
+            rate: None,
+            payers,
+            split,
+            ..Faker.fake()
+        }
+    }
+
     #[tokio::test]
     async fn create_payment_unauthorized_1() {
-        let title: String = Faker.fake();
+        let amount: Money = Faker.fake();
         let group: Group = Faker.fake();
-        let creditor: UserID = Faker.fake();
-        let debtors: Vec<UserID> = Faker.fake();
+        let payers = vec![Payer::new(Faker.fake(), amount.clone())];
+        let split: Split = Faker.fake();
 
         let id = group.id.clone();
         let auth = AuthState::UnAuthorized;
 
         let mock = MockRepository::new();
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase
-            .create_payment(title, id, creditor, debtors, &auth)
+            .create_payment(id, new_payment(amount, payers, split), &auth)
             .await
             .is_err());
     }
@@ -119,10 +301,10 @@ mod tests {
     #[tokio::test]
     async fn create_payment_unauthorized_2() {
         let claims: Claims = Faker.fake();
-        let title: String = Faker.fake();
+        let amount: Money = Faker.fake();
         let group: Group = Faker.fake();
-        let creditor: UserID = Faker.fake();
-        let debtors: Vec<UserID> = Faker.fake();
+        let payers = vec![Payer::new(Faker.fake(), amount.clone())];
+        let split: Split = Faker.fake();
 
         let id = group.id.clone();
         let auth = AuthState::Authorized(claims);
@@ -131,9 +313,9 @@ mod tests {
         mock.expect_get_group()
             .return_once(move |_| Ok(Some(group)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase
-            .create_payment(title, id, creditor, debtors, &auth)
+            .create_payment(id, new_payment(amount, payers, split), &auth)
             .await
             .is_err());
     }
@@ -141,10 +323,10 @@ mod tests {
     #[tokio::test]
     async fn create_payment_authorized() {
         let mut claims: Claims = Faker.fake();
-        let title: String = Faker.fake();
+        let amount: Money = Faker.fake();
         let group: Group = Faker.fake();
-        let creditor: UserID = Faker.fake();
-        let debtors: Vec<UserID> = Faker.fake();
+        let payers = vec![Payer::new(group.participants[0].clone(), amount.clone())];
+        let split = Split::equal(group.participants.clone());
 
         claims.sub = group.participants[0].to_string();
         let id = group.id.clone();
@@ -156,13 +338,171 @@ mod tests {
         mock.expect_get_group()
             .return_once(move |_| Ok(Some(group)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase
-            .create_payment(title, id, creditor, debtors, &auth)
+            .create_payment(id, new_payment(amount, payers, split), &auth)
             .await
             .is_ok());
     }
 
+    #[tokio::test]
+    async fn create_payment_not_participant() {
+        let mut claims: Claims = Faker.fake();
+        let amount: Money = Faker.fake();
+        let group: Group = Faker.fake();
+        let payers = vec![Payer::new(group.participants[0].clone(), amount.clone())];
+        let split = Split::equal(vec![group.participants[0].clone(), Faker.fake()]);
+
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase
+            .create_payment(id, new_payment(amount, payers, split), &auth)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn create_payment_exchange_rate() {
+        let mut claims: Claims = Faker.fake();
+        let amount = Money::new(1000, Currency::new("USD").unwrap());
+        let group: Group = Faker.fake();
+        let payers = vec![Payer::new(group.participants[0].clone(), amount.clone())];
+        let split = Split::equal(group.participants.clone());
+
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+        mock.expect_create_payment()
+            .return_once(move |payment| Ok(payment));
+        let mut rates = MockExchangeRateProvider::new();
+        rates
+            .expect_get_rate()
+            .return_once(move |_, _| Ok(Some(ExchangeRate(150_000_000))));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(rates));
+        let payment = usecase
+            .create_payment(id, new_payment(amount, payers, split), &auth)
+            .await
+            .unwrap();
+        assert_eq!(payment.rate, ExchangeRate(150_000_000));
+    }
+
+    #[tokio::test]
+    async fn create_payment_exchange_rate_override() {
+        let mut claims: Claims = Faker.fake();
+        let amount = Money::new(1000, Currency::new("USD").unwrap());
+        let mut group: Group = Faker.fake();
+        let payers = vec![Payer::new(group.participants[0].clone(), amount.clone())];
+        let split = Split::equal(group.participants.clone());
+
+        group.set_rate_override(amount.currency.clone(), Some(ExchangeRate(140_000_000)));
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+        mock.expect_create_payment()
+            .return_once(move |payment| Ok(payment));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let payment = usecase
+            .create_payment(id, new_payment(amount, payers, split), &auth)
+            .await
+            .unwrap();
+        assert_eq!(payment.rate, ExchangeRate(140_000_000));
+    }
+
+    #[tokio::test]
+    async fn create_payment_exchange_rate_unavailable() {
+        let mut claims: Claims = Faker.fake();
+        let amount = Money::new(1000, Currency::new("USD").unwrap());
+        let group: Group = Faker.fake();
+        let payers = vec![Payer::new(group.participants[0].clone(), amount.clone())];
+        let split = Split::equal(group.participants.clone());
+
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+        let mut rates = MockExchangeRateProvider::new();
+        rates.expect_get_rate().return_once(move |_, _| Ok(None));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(rates));
+        assert!(usecase
+            .create_payment(id, new_payment(amount, payers, split), &auth)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn create_payment_invalid_amount() {
+        for amount in [0, -1, Money::MAX_AMOUNT + 1] {
+            let mut claims: Claims = Faker.fake();
+            let amount = Money::new(amount, Faker.fake());
+            let group: Group = Faker.fake();
+            let payers = vec![Payer::new(Faker.fake(), amount.clone())];
+            let split: Split = Faker.fake();
+
+            claims.sub = group.participants[0].to_string();
+            let id = group.id.clone();
+            let auth = AuthState::Authorized(claims);
+
+            let mut mock = MockRepository::new();
+            mock.expect_get_group()
+                .return_once(move |_| Ok(Some(group)));
+
+            let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+            assert!(usecase
+                .create_payment(id, new_payment(amount, payers, split), &auth)
+                .await
+                .is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn create_payment_invalid_split() {
+        let mut claims: Claims = Faker.fake();
+        let amount: Money = Faker.fake();
+        let group: Group = Faker.fake();
+        let payers = vec![Payer::new(Faker.fake(), amount.clone())];
+        let split = Split::Percentages(PercentagesSplit {
+            debtors: vec![PercentageDebtor {
+                user: group.participants[0].clone(),
+                percent: 99,
+            }],
+        });
+
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase
+            .create_payment(id, new_payment(amount, payers, split), &auth)
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn delete_payment_unauthorized_1() {
         let payment: Payment = Faker.fake();
@@ -174,7 +514,7 @@ mod tests {
         mock.expect_get_payment()
             .return_once(move |_| Ok(Some(payment)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.delete_payment(&id, &auth).await.is_err());
     }
 
@@ -193,7 +533,7 @@ mod tests {
         mock.expect_get_group()
             .return_once(move |_| Ok(Some(group)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.delete_payment(&id, &auth).await.is_err());
     }
 
@@ -214,10 +554,267 @@ mod tests {
         mock.expect_get_group()
             .return_once(move |_| Ok(Some(group)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.delete_payment(&id, &auth).await.is_ok());
     }
 
+    #[tokio::test]
+    async fn update_payment_unauthorized() {
+        let group: Group = Faker.fake();
+        let payment: Payment = Faker.fake();
+        let claims: Claims = Faker.fake();
+        let update: PaymentUpdate = Faker.fake();
+
+        let id = payment.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_payment()
+            .return_once(move |_| Ok(Some(payment)));
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.update_payment(&id, update, &auth).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn update_payment_authorized() {
+        let group: Group = Faker.fake();
+        let mut payment: Payment = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+        let title: String = Faker.fake();
+
+        payment.group = group.id.clone();
+        payment.payers = vec![Payer::new(
+            group.participants[0].clone(),
+            payment.amount.clone(),
+        )];
+        payment.split = Split::equal(group.participants.clone());
+        claims.sub = group.participants[0].to_string();
+        let id = payment.id.clone();
+        let created_at = payment.created_at;
+        let auth = AuthState::Authorized(claims);
+        let update = PaymentUpdate {
+            title: Some(title.clone()),
+            ..Default::default()
+        };
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_payment()
+            .times(2)
+            .returning(move |_| Ok(Some(payment.clone())));
+        mock.expect_get_group()
+            .times(2)
+            .returning(move |_| Ok(Some(group.clone())));
+        mock.expect_update_payment()
+            .return_once(move |payment| Ok(payment));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let payment = usecase.update_payment(&id, update, &auth).await.unwrap();
+        assert_eq!(payment.id, id);
+        assert_eq!(payment.title, title);
+        assert_eq!(payment.created_at, created_at);
+    }
+
+    #[tokio::test]
+    async fn update_payment_sole_payer() {
+        let group: Group = Faker.fake();
+        let mut payment: Payment = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        payment.group = group.id.clone();
+        payment.payers = vec![Payer::new(
+            group.participants[0].clone(),
+            payment.amount.clone(),
+        )];
+        payment.split = Split::equal(group.participants.clone());
+        claims.sub = group.participants[0].to_string();
+        let id = payment.id.clone();
+        let amount = Money::new(payment.amount.amount + 1, payment.amount.currency.clone());
+        let auth = AuthState::Authorized(claims);
+        let update = PaymentUpdate {
+            amount: Some(amount.clone()),
+            ..Default::default()
+        };
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_payment()
+            .times(2)
+            .returning(move |_| Ok(Some(payment.clone())));
+        mock.expect_get_group()
+            .times(2)
+            .returning(move |_| Ok(Some(group.clone())));
+        mock.expect_update_payment()
+            .return_once(move |payment| Ok(payment));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let payment = usecase.update_payment(&id, update, &auth).await.unwrap();
+        assert_eq!(payment.payers[0].amount, amount);
+    }
+
+    #[tokio::test]
+    async fn update_payment_not_participant() {
+        let group: Group = Faker.fake();
+        let mut payment: Payment = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        payment.group = group.id.clone();
+        payment.payers = vec![Payer::new(
+            group.participants[0].clone(),
+            payment.amount.clone(),
+        )];
+        payment.split = Split::equal(group.participants.clone());
+        claims.sub = group.participants[0].to_string();
+        let id = payment.id.clone();
+        let auth = AuthState::Authorized(claims);
+        let update = PaymentUpdate {
+            payers: Some(vec![Faker.fake()]),
+            ..Default::default()
+        };
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_payment()
+            .times(2)
+            .returning(move |_| Ok(Some(payment.clone())));
+        mock.expect_get_group()
+            .times(2)
+            .returning(move |_| Ok(Some(group.clone())));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.update_payment(&id, update, &auth).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn add_payment_item() {
+        let group: Group = Faker.fake();
+        let mut payment: Payment = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        let currency = payment.amount.currency.clone();
+        payment.group = group.id.clone();
+        payment.amount = Money::new(2000, currency.clone());
+        payment.split = Split::Itemized(ItemizedSplit {
+            items: vec![PaymentItem::new(
+                Faker.fake(),
+                Money::new(500, currency.clone()),
+                1,
+                group.participants.clone(),
+            )],
+            tax: Money::zero(currency.clone()),
+            tip: Money::zero(currency.clone()),
+        });
+        payment.payers = vec![Payer::new(
+            group.participants[0].clone(),
+            payment.amount.clone(),
+        )];
+        claims.sub = group.participants[0].to_string();
+        let id = payment.id.clone();
+        let item = PaymentItem::new(
+            Faker.fake(),
+            Money::new(500, currency),
+            3,
+            group.participants.clone(),
+        );
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_payment()
+            .times(2)
+            .returning(move |_| Ok(Some(payment.clone())));
+        mock.expect_get_group()
+            .times(2)
+            .returning(move |_| Ok(Some(group.clone())));
+        mock.expect_update_payment()
+            .return_once(move |payment| Ok(payment));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let payment = usecase.add_payment_item(&id, item, &auth).await.unwrap();
+        assert_eq!(payment.amount.amount, 2000);
+        assert!(matches!(payment.split, Split::Itemized(split) if split.items.len() == 2));
+    }
+
+    #[tokio::test]
+    async fn add_payment_item_not_itemized() {
+        let group: Group = Faker.fake();
+        let mut payment: Payment = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        payment.group = group.id.clone();
+        payment.split = Split::equal(group.participants.clone());
+        payment.payers = vec![Payer::new(
+            group.participants[0].clone(),
+            payment.amount.clone(),
+        )];
+        claims.sub = group.participants[0].to_string();
+        let id = payment.id.clone();
+        let item = PaymentItem::new(
+            Faker.fake(),
+            payment.amount.clone(),
+            1,
+            group.participants.clone(),
+        );
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_payment()
+            .times(2)
+            .returning(move |_| Ok(Some(payment.clone())));
+        mock.expect_get_group()
+            .times(2)
+            .returning(move |_| Ok(Some(group.clone())));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let err = usecase
+            .add_payment_item(&id, item, &auth)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UseCaseError>(),
+            Some(UseCaseError::InvalidSplit)
+        ));
+    }
+
+    #[tokio::test]
+    async fn remove_payment_item_not_found() {
+        let group: Group = Faker.fake();
+        let mut payment: Payment = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        payment.group = group.id.clone();
+        payment.split = Split::Itemized(ItemizedSplit {
+            items: vec![],
+            tax: Money::zero(payment.amount.currency.clone()),
+            tip: Money::zero(payment.amount.currency.clone()),
+        });
+        payment.payers = vec![Payer::new(
+            group.participants[0].clone(),
+            payment.amount.clone(),
+        )];
+        claims.sub = group.participants[0].to_string();
+        let id = payment.id.clone();
+        let item: PaymentItemID = Faker.fake();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_payment()
+            .times(2)
+            .returning(move |_| Ok(Some(payment.clone())));
+        mock.expect_get_group()
+            .times(2)
+            .returning(move |_| Ok(Some(group.clone())));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let err = usecase
+            .remove_payment_item(&id, &item, &auth)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UseCaseError>(),
+            Some(UseCaseError::NotFound)
+        ));
+    }
+
     #[tokio::test]
     async fn get_payment_unauthorized() {
         let payment: Payment = Faker.fake();
@@ -233,7 +830,7 @@ mod tests {
         mock.expect_get_group()
             .return_once(move |_| Ok(Some(group)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.get_payment(&id, &auth).await.is_err());
     }
 
@@ -253,7 +850,7 @@ mod tests {
         mock.expect_get_group()
             .return_once(move |_| Ok(Some(group)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.get_payment(&id, &auth).await.is_ok());
     }
 
@@ -269,8 +866,11 @@ mod tests {
         mock.expect_get_group()
             .return_once(move |_| Ok(Some(group)));
 
-        let usecase = UseCase::new(Arc::new(mock));
-        assert!(usecase.get_payments_by_group(&id, &auth).await.is_err());
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase
+            .get_payments_by_group(&id, &PaymentFilter::default(), &auth)
+            .await
+            .is_err());
     }
 
     #[tokio::test]
@@ -284,13 +884,85 @@ mod tests {
         let auth = AuthState::Authorized(claims);
 
         let mut mock = MockRepository::new();
+        mock.expect_get_payments_by_filter()
+            .return_once(move |_, _| Ok(payments));
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let payments = usecase
+            .get_payments_by_group(&id, &PaymentFilter::default(), &auth)
+            .await
+            .unwrap();
+        assert!(payments
+            .windows(2)
+            .all(|pair| pair[0].occurred_at <= pair[1].occurred_at));
+    }
+
+    #[tokio::test]
+    async fn get_category_totals() {
+        let group: Group = Faker.fake();
+        let mut payment: Payment = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        payment.group = group.id.clone();
+        payment.category = Category::Food;
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let amount = payment.amount.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
         mock.expect_get_payments_by_group()
-            .return_once(move |_| Ok(payments));
+            .return_once(move |_| Ok(vec![payment]));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let totals = usecase.get_category_totals(&id, &auth).await.unwrap();
+        assert_eq!(
+            totals,
+            vec![CategoryTotal {
+                category: Category::Food,
+                total: amount,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn get_shares_unauthorized() {
+        let payment: Payment = Faker.fake();
+        let group: Group = Faker.fake();
+        let claims: Claims = Faker.fake();
+
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.get_shares(&payment, &auth).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn get_shares_authorized() {
+        let mut payment: Payment = Faker.fake();
+        let group: Group = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        payment.group = group.id.clone();
+        claims.sub = group.participants[0].to_string();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
         mock.expect_get_group()
             .return_once(move |_| Ok(Some(group)));
 
-        let usecase = UseCase::new(Arc::new(mock));
-        assert!(usecase.get_payments_by_group(&id, &auth).await.is_ok());
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let shares = usecase.get_shares(&payment, &auth).await.unwrap();
+        let sum: i64 = shares.iter().map(|share| share.amount.amount).sum();
+        assert_eq!(sum, payment.amount.amount);
     }
 
     #[tokio::test]
@@ -304,7 +976,7 @@ mod tests {
         mock.expect_get_payment()
             .return_once(move |_| Ok(Some(payment)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(!usecase.have_authority_payment(&id, &auth).await);
     }
 
@@ -323,7 +995,7 @@ mod tests {
         mock.expect_get_payment()
             .return_once(move |_| Ok(Some(payment)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(!usecase.have_authority_payment(&id, &auth).await);
     }
 
@@ -343,7 +1015,7 @@ mod tests {
         mock.expect_get_payment()
             .return_once(move |_| Ok(Some(payment)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.have_authority_payment(&id, &auth).await);
     }
 }
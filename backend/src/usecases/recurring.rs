@@ -0,0 +1,642 @@
+use crate::{
+    entities::{
+        AuthState, Group, GroupID, NewPayment, Payment, RecurringPayment, RecurringPaymentID,
+        RecurringPaymentUpdate, Schedule,
+    },
+    usecases::{validation::validate_payment, UseCase, UseCaseError},
+};
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+
+impl UseCase {
+    pub async fn create_recurring_payment(
+        &self,
+        group: GroupID,
+        schedule: Schedule,
+        starts_at: DateTime<FixedOffset>,
+        ends_on: Option<NaiveDate>,
+        template: NewPayment,
+        auth: &AuthState,
+    ) -> Result<RecurringPayment, Box<dyn std::error::Error + Send + Sync>> {
+        let group = self.get_group(&group, auth).await?;
+        let recurring =
+            RecurringPayment::new(group.id.clone(), schedule, starts_at, ends_on, template);
+        if !recurring.is_valid() || recurring.starts_too_early(Utc::now()) {
+            return Err(UseCaseError::InvalidSchedule)?;
+        }
+        // 支払い日を待たずに、作った時点でテンプレートが正しいか確かめる
+        self.recurring_payment(&recurring, &group, starts_at)
+            .await?;
+        let recurring = self.repository.create_recurring_payment(recurring).await?;
+        Ok(recurring)
+    }
+
+    pub async fn delete_recurring_payment(
+        &self,
+        id: &RecurringPaymentID,
+        auth: &AuthState,
+    ) -> Result<RecurringPaymentID, Box<dyn std::error::Error + Send + Sync>> {
+        if self.have_authority_recurring_payment(id, auth).await {
+            self.repository.delete_recurring_payment(id).await?;
+            Ok(id.clone())
+        } else {
+            Err(UseCaseError::UnAuthorized)?
+        }
+    }
+
+    /// 作成時と同じく、規則とテンプレートが正しいことを確かめてから保存する。
+    /// 記録が止まっていた場合は、テンプレートが直ったものとして次の支払い日から記録を再開する
+    pub async fn update_recurring_payment(
+        &self,
+        id: &RecurringPaymentID,
+        update: RecurringPaymentUpdate,
+        auth: &AuthState,
+    ) -> Result<RecurringPayment, Box<dyn std::error::Error + Send + Sync>> {
+        if !self.have_authority_recurring_payment(id, auth).await {
+            return Err(UseCaseError::UnAuthorized)?;
+        }
+        let mut recurring = self
+            .repository
+            .get_recurring_payment(id)
+            .await?
+            .ok_or(UseCaseError::NotFound)?;
+        let group = self
+            .repository
+            .get_group(&recurring.group)
+            .await?
+            .ok_or(UseCaseError::NotFound)?;
+
+        recurring.apply(update);
+        if !recurring.is_valid() {
+            return Err(UseCaseError::InvalidSchedule)?;
+        }
+        self.recurring_payment(&recurring, &group, recurring.starts_at)
+            .await?;
+        recurring.stalled_reason = None;
+
+        let recurring = self.repository.update_recurring_payment(recurring).await?;
+        Ok(recurring)
+    }
+
+    pub async fn get_recurring_payments_by_group(
+        &self,
+        group: &GroupID,
+        auth: &AuthState,
+    ) -> Result<Vec<RecurringPayment>, Box<dyn std::error::Error + Send + Sync>> {
+        if self.have_authority_group(group, auth).await {
+            let recurrings = self
+                .repository
+                .get_recurring_payments_by_group(group)
+                .await?;
+            Ok(recurrings)
+        } else {
+            Err(UseCaseError::UnAuthorized)?
+        }
+    }
+
+    /// `now` までに来た支払い日の `Payment` を記録する。支払い日ごとに決まった ID で記録するので、
+    /// 途中で失敗したり複数回実行したりしても同じ支払い日を 2 度記録することはない。
+    /// 1 つの繰り返す支払いで失敗しても、ほかの繰り返す支払いは記録する
+    pub async fn materialize_recurring_payments(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Payment>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut created = vec![];
+
+        for recurring in self.repository.get_recurring_payments().await? {
+            // 保存し直せなかったものは、次の実行で記録済みの支払い日を読み飛ばしてやり直す
+            if let Ok(payments) = self.materialize_recurring_payment(recurring, now).await {
+                created.extend(payments);
+            }
+        }
+
+        Ok(created)
+    }
+
+    /// 1 つの繰り返す支払いについて、`now` までに来た支払い日の `Payment` を記録する。
+    /// 記録できなかったらその理由を `stalled_reason` に残し、そこまでの進み具合と一緒に保存する
+    async fn materialize_recurring_payment(
+        &self,
+        mut recurring: RecurringPayment,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Payment>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut created = vec![];
+
+        let due = recurring.due(now);
+        if due.is_empty() {
+            return Ok(created);
+        }
+
+        recurring.stalled_reason = None;
+        match self.repository.get_group(&recurring.group).await {
+            Ok(Some(group)) => {
+                for date in due {
+                    // メンバーが抜けるなどしてテンプレートが正しくなくなったら、直されるまで記録しない
+                    match self.materialize_occurrence(&recurring, &group, date).await {
+                        Ok(payment) => created.extend(payment),
+                        Err(err) => {
+                            recurring.stalled_reason = Some(err.to_string());
+                            break;
+                        }
+                    }
+                    recurring.last_occurrence = Some(date);
+                }
+            }
+            Ok(None) => recurring.stalled_reason = Some(UseCaseError::NotFound.to_string()),
+            Err(err) => recurring.stalled_reason = Some(UseCaseError::from(&err).to_string()),
+        }
+
+        self.repository.update_recurring_payment(recurring).await?;
+        Ok(created)
+    }
+
+    /// 支払い日 `date` の `Payment` を記録する。記録済みなら何もしない
+    async fn materialize_occurrence(
+        &self,
+        recurring: &RecurringPayment,
+        group: &Group,
+        date: NaiveDate,
+    ) -> Result<Option<Payment>, Box<dyn std::error::Error + Send + Sync>> {
+        let id = recurring.payment_id(date);
+        if self.repository.get_payment(&id).await?.is_some() {
+            return Ok(None);
+        }
+        let occurred_at = recurring
+            .occurred_at(date)
+            .ok_or(UseCaseError::InvalidSchedule)?;
+        let mut payment = self
+            .recurring_payment(recurring, group, occurred_at)
+            .await?;
+        payment.id = id;
+        let payment = self.repository.create_payment(payment).await?;
+        Ok(Some(payment))
+    }
+
+    /// `recurring` のテンプレートから `occurred_at` に支払った `Payment` を作り、検証する
+    async fn recurring_payment(
+        &self,
+        recurring: &RecurringPayment,
+        group: &Group,
+        occurred_at: DateTime<FixedOffset>,
+    ) -> Result<Payment, Box<dyn std::error::Error + Send + Sync>> {
+        let template = NewPayment {
+            occurred_at: Some(occurred_at),
+            ..recurring.template.clone()
+        };
+        let rate = match template.rate {
+            Some(rate) => rate,
+            None => self.resolve_rate(group, &template.amount.currency).await?,
+        };
+        let payment = Payment::new(template, rate, group.id.clone());
+        validate_payment(&payment, group)?;
+        Ok(payment)
+    }
+
+    /// 存在しない繰り返す支払いは、ID があるかどうかを知られないよう権限がないものとして扱う
+    pub async fn have_authority_recurring_payment(
+        &self,
+        id: &RecurringPaymentID,
+        auth: &AuthState,
+    ) -> bool {
+        if let Ok(Some(recurring)) = self.repository.get_recurring_payment(id).await {
+            self.have_authority_group(&recurring.group, auth).await
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        entities::{Claims, Money, MonthlySchedule, Payer, Split},
+        repositories::{
+            GroupRepository, InMemory, MockExchangeRateProvider, MockRepository, PaymentRepository,
+            RecurringPaymentRepository,
+        },
+    };
+    use fake::{Fake, Faker};
+    use std::sync::Arc;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn template(group: &Group) -> NewPayment {
+        let amount: Money = Faker.fake();
+        NewPayment {
+            payers: vec![Payer::new(group.participants[0].clone(), amount.clone())],
+            amount,
+            adjustments: vec![],
+            rate: None,
+            split: Split::equal(group.participants.clone()),
+            ..Faker.fake()
+        }
+    }
+
+    #[tokio::test]
+    async fn create_recurring_payment_invalid_schedule() {
+        let mut claims: Claims = Faker.fake();
+        let group: Group = Faker.fake();
+        let template = template(&group);
+
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase
+            .create_recurring_payment(
+                id,
+                Schedule::Monthly(MonthlySchedule { day: 32 }),
+                Utc::now().fixed_offset(),
+                None,
+                template,
+                &auth,
+            )
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn create_recurring_payment_starts_too_early() {
+        let mut claims: Claims = Faker.fake();
+        let group: Group = Faker.fake();
+        let template = template(&group);
+
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let starts_at =
+            Utc::now() - chrono::Duration::days(RecurringPayment::MAX_BACKFILL_DAYS + 1);
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+        mock.expect_create_recurring_payment().never();
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let err = usecase
+            .create_recurring_payment(
+                id,
+                Schedule::Monthly(MonthlySchedule { day: 1 }),
+                starts_at.fixed_offset(),
+                None,
+                template,
+                &auth,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UseCaseError>(),
+            Some(UseCaseError::InvalidSchedule)
+        ));
+    }
+
+    #[tokio::test]
+    async fn create_recurring_payment_authorized() {
+        let mut claims: Claims = Faker.fake();
+        let group: Group = Faker.fake();
+        let template = template(&group);
+
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+        mock.expect_create_recurring_payment().return_once(Ok);
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase
+            .create_recurring_payment(
+                id,
+                Schedule::Monthly(MonthlySchedule { day: 1 }),
+                Utc::now().fixed_offset(),
+                None,
+                template,
+                &auth,
+            )
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn delete_recurring_payment_not_found() {
+        let claims: Claims = Faker.fake();
+        let id: RecurringPaymentID = Faker.fake();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_recurring_payment()
+            .return_once(|_| Ok(None));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let err = usecase
+            .delete_recurring_payment(&id, &auth)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UseCaseError>(),
+            Some(UseCaseError::UnAuthorized)
+        ));
+    }
+
+    #[tokio::test]
+    async fn delete_recurring_payment_authorized() {
+        let group: Group = Faker.fake();
+        let mut recurring: RecurringPayment = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        recurring.group = group.id.clone();
+        claims.sub = group.participants[0].to_string();
+        let id = recurring.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_recurring_payment()
+            .return_once(move |_| Ok(Some(recurring)));
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+        mock.expect_delete_recurring_payment()
+            .return_once(|_| Ok(()));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.delete_recurring_payment(&id, &auth).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn update_recurring_payment() {
+        let repository = Arc::new(InMemory::default());
+        let group: Group = Faker.fake();
+        let mut recurring: RecurringPayment = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        recurring.group = group.id.clone();
+        recurring.schedule = Schedule::Monthly(MonthlySchedule { day: 1 });
+        recurring.ends_on = None;
+        recurring.last_occurrence = Some(date(2024, 3, 1));
+        recurring.stalled_reason = Some("not participants".to_string());
+        claims.sub = group.participants[0].to_string();
+        let id = recurring.id.clone();
+        let template = template(&group);
+        let update = RecurringPaymentUpdate {
+            template: Some(template.clone()),
+            ..Default::default()
+        };
+        let auth = AuthState::Authorized(claims);
+
+        repository.create_group(group).await.unwrap();
+        repository
+            .create_recurring_payment(recurring)
+            .await
+            .unwrap();
+
+        let usecase = UseCase::new(
+            repository.clone(),
+            Arc::new(MockExchangeRateProvider::new()),
+        );
+        let recurring = usecase
+            .update_recurring_payment(&id, update, &auth)
+            .await
+            .unwrap();
+        assert_eq!(recurring.last_occurrence, Some(date(2024, 3, 1)));
+        assert_eq!(recurring.stalled_reason, None);
+        assert_eq!(recurring.template, template);
+        assert_eq!(
+            repository.get_recurring_payment(&id).await.unwrap(),
+            Some(recurring)
+        );
+    }
+
+    #[tokio::test]
+    async fn update_recurring_payment_invalid_template() {
+        let repository = Arc::new(InMemory::default());
+        let group: Group = Faker.fake();
+        let mut recurring: RecurringPayment = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        recurring.group = group.id.clone();
+        recurring.schedule = Schedule::Monthly(MonthlySchedule { day: 1 });
+        recurring.ends_on = None;
+        claims.sub = group.participants[0].to_string();
+        let id = recurring.id.clone();
+        let mut template = template(&group);
+        template.split = Split::equal(vec![Faker.fake()]);
+        let update = RecurringPaymentUpdate {
+            template: Some(template),
+            ..Default::default()
+        };
+        let auth = AuthState::Authorized(claims);
+
+        repository.create_group(group).await.unwrap();
+        let recurring = repository
+            .create_recurring_payment(recurring)
+            .await
+            .unwrap();
+
+        let usecase = UseCase::new(
+            repository.clone(),
+            Arc::new(MockExchangeRateProvider::new()),
+        );
+        assert!(usecase
+            .update_recurring_payment(&id, update, &auth)
+            .await
+            .is_err());
+        assert_eq!(
+            repository.get_recurring_payment(&id).await.unwrap(),
+            Some(recurring)
+        );
+    }
+
+    #[tokio::test]
+    async fn materialize_recurring_payments() {
+        let repository = Arc::new(InMemory::default());
+        let group: Group = Faker.fake();
+        let mut recurring: RecurringPayment = Faker.fake();
+        recurring.group = group.id.clone();
+        recurring.schedule = Schedule::Monthly(MonthlySchedule { day: 1 });
+        recurring.starts_at = "2024-01-01T09:00:00+09:00".parse().unwrap();
+        recurring.ends_on = None;
+        recurring.last_occurrence = None;
+        recurring.template = template(&group);
+
+        // 2 月 1 日の分は前回の途中で記録済み
+        let mut existing: Payment = Faker.fake();
+        existing.id = recurring.payment_id(date(2024, 2, 1));
+        existing.group = group.id.clone();
+
+        repository.create_group(group.clone()).await.unwrap();
+        let recurring = repository
+            .create_recurring_payment(recurring)
+            .await
+            .unwrap();
+        repository.create_payment(existing).await.unwrap();
+
+        let usecase = UseCase::new(
+            repository.clone(),
+            Arc::new(MockExchangeRateProvider::new()),
+        );
+        let created = usecase
+            .materialize_recurring_payments("2024-03-15T00:00:00Z".parse().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            created
+                .iter()
+                .map(|payment| &payment.id)
+                .collect::<Vec<_>>(),
+            vec![
+                &recurring.payment_id(date(2024, 1, 1)),
+                &recurring.payment_id(date(2024, 3, 1)),
+            ]
+        );
+        assert_eq!(
+            created[0].occurred_at,
+            "2024-01-01T09:00:00+09:00"
+                .parse::<DateTime<FixedOffset>>()
+                .unwrap()
+        );
+        assert_eq!(
+            repository
+                .get_payments_by_group(&group.id)
+                .await
+                .unwrap()
+                .len(),
+            3
+        );
+        let recurring = repository
+            .get_recurring_payment(&recurring.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(recurring.last_occurrence, Some(date(2024, 3, 1)));
+    }
+
+    #[tokio::test]
+    async fn materialize_recurring_payments_stalled() {
+        let repository = Arc::new(InMemory::default());
+        let group: Group = Faker.fake();
+        let mut recurring: RecurringPayment = Faker.fake();
+        recurring.group = group.id.clone();
+        recurring.schedule = Schedule::Monthly(MonthlySchedule { day: 1 });
+        recurring.starts_at = "2024-01-01T09:00:00+09:00".parse().unwrap();
+        recurring.ends_on = None;
+        recurring.last_occurrence = None;
+        recurring.template = template(&group);
+        // 債務者がグループから抜けた
+        recurring.template.split = Split::equal(vec![Faker.fake()]);
+
+        repository.create_group(group.clone()).await.unwrap();
+        let recurring = repository
+            .create_recurring_payment(recurring)
+            .await
+            .unwrap();
+
+        let usecase = UseCase::new(
+            repository.clone(),
+            Arc::new(MockExchangeRateProvider::new()),
+        );
+        let created = usecase
+            .materialize_recurring_payments("2024-03-15T00:00:00Z".parse().unwrap())
+            .await
+            .unwrap();
+
+        assert!(created.is_empty());
+        assert!(repository
+            .get_payments_by_group(&group.id)
+            .await
+            .unwrap()
+            .is_empty());
+        let recurring = repository
+            .get_recurring_payment(&recurring.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(recurring.last_occurrence, None);
+        assert!(recurring.stalled_reason.is_some());
+    }
+
+    #[tokio::test]
+    async fn materialize_recurring_payments_isolated() {
+        let repository = Arc::new(InMemory::default());
+        let group: Group = Faker.fake();
+        let recurrings: Vec<RecurringPayment> = (0..3)
+            .map(|i| {
+                let mut recurring: RecurringPayment = Faker.fake();
+                recurring.group = group.id.clone();
+                recurring.schedule = Schedule::Monthly(MonthlySchedule { day: 1 });
+                recurring.starts_at = "2024-03-01T09:00:00+09:00".parse().unwrap();
+                recurring.ends_on = None;
+                recurring.last_occurrence = None;
+                recurring.template = template(&group);
+                if i == 1 {
+                    recurring.template.split = Split::equal(vec![Faker.fake()]);
+                }
+                recurring
+            })
+            .collect();
+        let recorded = recurrings[2].payment_id(date(2024, 3, 1));
+
+        repository.create_group(group).await.unwrap();
+        for recurring in &recurrings {
+            repository
+                .create_recurring_payment(recurring.clone())
+                .await
+                .unwrap();
+        }
+
+        let usecase = UseCase::new(
+            repository.clone(),
+            Arc::new(MockExchangeRateProvider::new()),
+        );
+        let created = usecase
+            .materialize_recurring_payments("2024-03-15T00:00:00Z".parse().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(created.len(), 2);
+        assert_eq!(created[1].id, recorded);
+        for (i, recurring) in recurrings.iter().enumerate() {
+            let recurring = repository
+                .get_recurring_payment(&recurring.id)
+                .await
+                .unwrap()
+                .unwrap();
+            if i == 1 {
+                assert_eq!(recurring.last_occurrence, None);
+                assert!(recurring.stalled_reason.is_some());
+            } else {
+                assert_eq!(recurring.last_occurrence, Some(date(2024, 3, 1)));
+                assert_eq!(recurring.stalled_reason, None);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn materialize_recurring_payments_not_due() {
+        let mut recurring: RecurringPayment = Faker.fake();
+        recurring.schedule = Schedule::Monthly(MonthlySchedule { day: 1 });
+        recurring.starts_at = "2024-01-01T09:00:00+09:00".parse().unwrap();
+        recurring.ends_on = None;
+        recurring.last_occurrence = Some(date(2024, 3, 1));
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_recurring_payments()
+            .return_once(move || Ok(vec![recurring]));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        let created = usecase
+            .materialize_recurring_payments("2024-03-15T00:00:00Z".parse().unwrap())
+            .await
+            .unwrap();
+
+        assert!(created.is_empty());
+    }
+}
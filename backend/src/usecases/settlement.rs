@@ -0,0 +1,219 @@
+use crate::{
+    entities::{AuthState, GroupID, Money, Settlement, SettlementID, UserID},
+    usecases::{validation::validate_amount, UseCase, UseCaseError},
+};
+
+impl UseCase {
+    pub async fn record_settlement(
+        &self,
+        group: GroupID,
+        from: UserID,
+        to: UserID,
+        amount: Money,
+        auth: &AuthState,
+    ) -> Result<Settlement, Box<dyn std::error::Error + Send + Sync>> {
+        let group = self.get_group(&group, auth).await?;
+        validate_amount(&amount)?;
+        if from == to
+            || !group.is_member(&from)
+            || !group.is_member(&to)
+            || amount.currency != group.currency
+        {
+            return Err(UseCaseError::InvalidSettlement)?;
+        }
+        let settlement = Settlement::new(group.id, from, to, amount);
+        let settlement = self.repository.create_settlement(settlement).await?;
+        Ok(settlement)
+    }
+
+    pub async fn delete_settlement(
+        &self,
+        id: &SettlementID,
+        auth: &AuthState,
+    ) -> Result<SettlementID, Box<dyn std::error::Error + Send + Sync>> {
+        if self.have_authority_settlement(id, auth).await {
+            self.repository.delete_settlement(id).await?;
+            Ok(id.clone())
+        } else {
+            Err(UseCaseError::UnAuthorized)?
+        }
+    }
+
+    pub async fn get_settlements_by_group(
+        &self,
+        group: &GroupID,
+        auth: &AuthState,
+    ) -> Result<Vec<Settlement>, Box<dyn std::error::Error + Send + Sync>> {
+        if !self.have_authority_group(group, auth).await {
+            return Err(UseCaseError::UnAuthorized)?;
+        }
+        let settlements = self.repository.get_settlements_by_group(group).await?;
+        Ok(settlements)
+    }
+
+    pub async fn have_authority_settlement(&self, id: &SettlementID, auth: &AuthState) -> bool {
+        if let Ok(Some(settlement)) = self.repository.get_settlement(id).await {
+            self.have_authority_group(&settlement.group, auth).await
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        entities::{Claims, Group},
+        repositories::{MockExchangeRateProvider, MockRepository},
+    };
+    use fake::{Fake, Faker};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn record_settlement_unauthorized() {
+        let group: Group = Faker.fake();
+        let claims: Claims = Faker.fake();
+        let amount: Money = Faker.fake();
+
+        let id = group.id.clone();
+        let from = group.participants[0].clone();
+        let to: UserID = Faker.fake();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase
+            .record_settlement(id, from, to, amount, &auth)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn record_settlement_not_participant() {
+        let group: Group = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+        let amount: Money = Faker.fake();
+
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let from = group.participants[0].clone();
+        let to: UserID = Faker.fake();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase
+            .record_settlement(id, from, to, amount, &auth)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn record_settlement_authorized() {
+        let mut group: Group = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+        let amount: Money = Faker.fake();
+
+        let from: UserID = Faker.fake();
+        let to: UserID = Faker.fake();
+        group.participants = vec![from.clone(), to.clone()];
+        claims.sub = from.to_string();
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+        mock.expect_create_settlement()
+            .return_once(move |settlement| Ok(settlement));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase
+            .record_settlement(id, from, to, amount, &auth)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn delete_settlement_unauthorized() {
+        let group: Group = Faker.fake();
+        let settlement: Settlement = Faker.fake();
+        let claims: Claims = Faker.fake();
+
+        let id = settlement.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_settlement()
+            .return_once(move |_| Ok(Some(settlement)));
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.delete_settlement(&id, &auth).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn delete_settlement_authorized() {
+        let group: Group = Faker.fake();
+        let settlement: Settlement = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        claims.sub = group.participants[0].to_string();
+        let id = settlement.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_delete_settlement().return_once(move |_| Ok(()));
+        mock.expect_get_settlement()
+            .return_once(move |_| Ok(Some(settlement)));
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.delete_settlement(&id, &auth).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn get_settlements_by_group_unauthorized() {
+        let group: Group = Faker.fake();
+        let claims: Claims = Faker.fake();
+
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.get_settlements_by_group(&id, &auth).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn get_settlements_by_group_authorized() {
+        let settlements: Vec<Settlement> = Faker.fake();
+        let group: Group = Faker.fake();
+        let mut claims: Claims = Faker.fake();
+
+        claims.sub = group.participants[0].to_string();
+        let id = group.id.clone();
+        let auth = AuthState::Authorized(claims);
+
+        let mut mock = MockRepository::new();
+        mock.expect_get_settlements_by_group()
+            .return_once(move |_| Ok(settlements));
+        mock.expect_get_group()
+            .return_once(move |_| Ok(Some(group)));
+
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
+        assert!(usecase.get_settlements_by_group(&id, &auth).await.is_ok());
+    }
+}
@@ -85,7 +85,10 @@ impl UseCase {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{entities::Claims, repositories::MockRepository};
+    use crate::{
+        entities::Claims,
+        repositories::{MockExchangeRateProvider, MockRepository},
+    };
     use fake::{Fake, Faker};
     use std::sync::Arc;
 
@@ -96,7 +99,7 @@ mod tests {
 
         let mock = MockRepository::new();
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.create_user(name, &auth).await.is_err());
     }
 
@@ -109,7 +112,7 @@ mod tests {
         let mut mock = MockRepository::new();
         mock.expect_create_user().return_once(move |user| Ok(user));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.create_user(name, &auth).await.is_ok());
     }
 
@@ -122,7 +125,7 @@ mod tests {
 
         let mock = MockRepository::new();
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.delete_user(&id, &auth).await.is_err());
     }
 
@@ -137,7 +140,7 @@ mod tests {
         let mut mock = MockRepository::new();
         mock.expect_get_user().return_once(move |_| Ok(Some(user)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.delete_user(&id, &auth).await.is_err());
     }
 
@@ -154,7 +157,7 @@ mod tests {
         mock.expect_delete_user().return_once(move |_| Ok(()));
         mock.expect_get_user().return_once(move |_| Ok(Some(user)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.delete_user(&id, &auth).await.is_ok());
     }
 
@@ -167,7 +170,7 @@ mod tests {
 
         let mock = MockRepository::new();
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.get_user(&id, &auth).await.is_err());
     }
 
@@ -182,7 +185,7 @@ mod tests {
         let mut mock = MockRepository::new();
         mock.expect_get_user().return_once(move |_| Ok(Some(user)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.get_user(&id, &auth).await.is_ok());
     }
 
@@ -198,7 +201,7 @@ mod tests {
         let mut mock = MockRepository::new();
         mock.expect_get_user().return_once(move |_| Ok(Some(user)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.get_user(&id, &auth).await.is_ok());
     }
 
@@ -211,7 +214,7 @@ mod tests {
 
         let mock = MockRepository::new();
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.get_users(&[id], &auth).await.is_err());
     }
 
@@ -226,7 +229,7 @@ mod tests {
         let mut mock = MockRepository::new();
         mock.expect_get_user().return_once(move |_| Ok(Some(user)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.get_users(&[id], &auth).await.is_ok());
     }
 
@@ -242,7 +245,7 @@ mod tests {
         let mut mock = MockRepository::new();
         mock.expect_get_user().return_once(move |_| Ok(Some(user)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.get_users(&[id], &auth).await.is_ok());
     }
 
@@ -255,7 +258,7 @@ mod tests {
 
         let mock = MockRepository::new();
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(!usecase.have_authority_user(&id, &auth).await);
     }
 
@@ -270,7 +273,7 @@ mod tests {
         let mut mock = MockRepository::new();
         mock.expect_get_user().return_once(move |_| Ok(Some(user)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(!usecase.have_authority_user(&id, &auth).await);
     }
 
@@ -286,7 +289,7 @@ mod tests {
         let mut mock = MockRepository::new();
         mock.expect_get_user().return_once(move |_| Ok(Some(user)));
 
-        let usecase = UseCase::new(Arc::new(mock));
+        let usecase = UseCase::new(Arc::new(mock), Arc::new(MockExchangeRateProvider::new()));
         assert!(usecase.have_authority_user(&id, &auth).await);
     }
 }
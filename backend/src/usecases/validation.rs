@@ -0,0 +1,310 @@
+use crate::{
+    entities::{ExchangeRate, Group, Money, Payment, Split, UserID},
+    usecases::UseCaseError,
+};
+
+pub fn validate_amount(amount: &Money) -> Result<(), UseCaseError> {
+    if amount.currency.is_valid() && 0 < amount.amount && amount.amount <= Money::MAX_AMOUNT {
+        Ok(())
+    } else {
+        Err(UseCaseError::InvalidAmount)
+    }
+}
+
+/// 支払者・債務者がグループのメンバー (参加者かゲスト) であること、債務者が空でも重複してもいないこと、
+/// 金額・レート・分け方・調整が正しく、支払者の額の合計が調整後の合計と一致することを検証する。
+/// 明細ごとの分け方は税とチップを明細と一緒に持つので、二重に掛からないよう調整は付けられない
+pub fn validate_payment(payment: &Payment, group: &Group) -> Result<(), UseCaseError> {
+    validate_amount(&payment.amount)?;
+
+    let rate_is_valid = if payment.amount.currency == group.currency {
+        payment.rate == ExchangeRate::IDENTITY
+    } else {
+        payment.rate.is_valid()
+    };
+    if !rate_is_valid {
+        return Err(UseCaseError::InvalidExchangeRate);
+    }
+
+    if payment.payers.is_empty() {
+        return Err(UseCaseError::InvalidPayers);
+    }
+
+    let debtors = payment.split.debtors();
+    if debtors.is_empty() {
+        return Err(UseCaseError::EmptyDebtors);
+    }
+
+    let mut duplicates: Vec<UserID> = vec![];
+    for (i, debtor) in debtors.iter().enumerate() {
+        if debtors[..i].contains(debtor) && !duplicates.contains(debtor) {
+            duplicates.push(debtor.clone());
+        }
+    }
+    if !duplicates.is_empty() {
+        return Err(UseCaseError::DuplicateDebtors(duplicates));
+    }
+
+    let mut outsiders: Vec<UserID> = vec![];
+    let payers = payment.payers.iter().map(|payer| &payer.user);
+    for user in payers.chain(debtors.iter()) {
+        if !group.is_member(user) && !outsiders.contains(user) {
+            outsiders.push(user.clone());
+        }
+    }
+    if !outsiders.is_empty() {
+        return Err(UseCaseError::NotParticipants(outsiders));
+    }
+
+    if !payment.split.is_valid(&payment.amount) {
+        return Err(UseCaseError::InvalidSplit);
+    }
+
+    if matches!(payment.split, Split::Itemized(_)) && !payment.adjustments.is_empty() {
+        return Err(UseCaseError::InvalidAdjustment);
+    }
+    if !payment
+        .adjustments
+        .iter()
+        .all(|adjustment| adjustment.is_valid(&payment.amount))
+    {
+        return Err(UseCaseError::InvalidAdjustment);
+    }
+    let total = payment
+        .total()
+        .map_err(|_| UseCaseError::InvalidAdjustment)?;
+    validate_amount(&total).map_err(|_| UseCaseError::InvalidAdjustment)?;
+
+    validate_payers(payment, &total)?;
+
+    Ok(())
+}
+
+fn validate_payers(payment: &Payment, total: &Money) -> Result<(), UseCaseError> {
+    let mut sum = Money::zero(total.currency.clone());
+    for (i, payer) in payment.payers.iter().enumerate() {
+        let duplicated = payment.payers[..i]
+            .iter()
+            .any(|other| other.user == payer.user);
+        if duplicated || payer.amount.amount <= 0 {
+            return Err(UseCaseError::InvalidPayers);
+        }
+        sum = sum
+            .checked_add(&payer.amount)
+            .map_err(|_| UseCaseError::InvalidPayers)?;
+    }
+
+    if &sum == total {
+        Ok(())
+    } else {
+        Err(UseCaseError::InvalidPayers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{
+        Adjustment, AdjustmentKind, Currency, ItemizedSplit, Payer, PaymentItem,
+    };
+    use fake::{Fake, Faker};
+
+    fn valid() -> (Payment, Group) {
+        let group: Group = Faker.fake();
+        let mut payment: Payment = Faker.fake();
+
+        payment.group = group.id.clone();
+        payment.payers = vec![Payer::new(
+            group.participants[0].clone(),
+            payment.amount.clone(),
+        )];
+        payment.split = Split::equal(group.participants.clone());
+
+        (payment, group)
+    }
+
+    #[test]
+    fn validate_payment_ok() {
+        let (payment, group) = valid();
+
+        assert!(validate_payment(&payment, &group).is_ok());
+    }
+
+    #[test]
+    fn validate_payment_payer_not_participant() {
+        let (mut payment, group) = valid();
+        let outsider: UserID = Faker.fake();
+        payment.payers[0].user = outsider.clone();
+
+        assert!(matches!(
+            validate_payment(&payment, &group),
+            Err(UseCaseError::NotParticipants(users)) if users == vec![outsider]
+        ));
+    }
+
+    #[test]
+    fn validate_payment_debtors_not_participant() {
+        let (mut payment, group) = valid();
+        let outsider1: UserID = Faker.fake();
+        let outsider2: UserID = Faker.fake();
+        payment.split = Split::equal(vec![
+            group.participants[0].clone(),
+            outsider1.clone(),
+            outsider2.clone(),
+        ]);
+
+        assert!(matches!(
+            validate_payment(&payment, &group),
+            Err(UseCaseError::NotParticipants(users)) if users == vec![outsider1, outsider2]
+        ));
+    }
+
+    #[test]
+    fn validate_payment_guest() {
+        let (mut payment, mut group) = valid();
+        let guest = UserID::guest();
+        group.guests.push(guest.clone());
+        payment.payers[0].user = guest.clone();
+        payment.split = Split::equal(vec![group.participants[0].clone(), guest]);
+
+        assert!(validate_payment(&payment, &group).is_ok());
+    }
+
+    #[test]
+    fn validate_payment_empty_debtors() {
+        let (mut payment, group) = valid();
+        payment.split = Split::equal(vec![]);
+
+        assert!(matches!(
+            validate_payment(&payment, &group),
+            Err(UseCaseError::EmptyDebtors)
+        ));
+    }
+
+    #[test]
+    fn validate_payment_duplicate_debtors() {
+        let (mut payment, group) = valid();
+        let debtor = group.participants[0].clone();
+        payment.split = Split::equal(vec![debtor.clone(), debtor.clone(), debtor.clone()]);
+
+        assert!(matches!(
+            validate_payment(&payment, &group),
+            Err(UseCaseError::DuplicateDebtors(users)) if users == vec![debtor]
+        ));
+    }
+
+    #[test]
+    fn validate_payment_exchange_rate() {
+        let (mut payment, group) = valid();
+        payment.rate = "150".parse().unwrap();
+
+        assert!(matches!(
+            validate_payment(&payment, &group),
+            Err(UseCaseError::InvalidExchangeRate)
+        ));
+
+        payment.amount.currency = Currency::new("USD").unwrap();
+        payment.payers[0].amount.currency = Currency::new("USD").unwrap();
+        assert!(validate_payment(&payment, &group).is_ok());
+
+        payment.rate = ExchangeRate(0);
+        assert!(matches!(
+            validate_payment(&payment, &group),
+            Err(UseCaseError::InvalidExchangeRate)
+        ));
+    }
+
+    #[test]
+    fn validate_payment_invalid_amount() {
+        let (mut payment, group) = valid();
+        payment.amount.amount = 0;
+
+        assert!(matches!(
+            validate_payment(&payment, &group),
+            Err(UseCaseError::InvalidAmount)
+        ));
+    }
+
+    #[test]
+    fn validate_payment_adjustments() {
+        let (mut payment, group) = valid();
+        payment.adjustments = vec![Adjustment::percentage(AdjustmentKind::Tax, 1000)];
+        payment.sync_sole_payer().unwrap();
+
+        assert!(validate_payment(&payment, &group).is_ok());
+
+        payment.adjustments = vec![Adjustment::percentage(AdjustmentKind::Discount, 10_000)];
+        assert!(matches!(
+            validate_payment(&payment, &group),
+            Err(UseCaseError::InvalidAdjustment)
+        ));
+
+        let mut amount = payment.amount.clone();
+        amount.currency = Currency::new("USD").unwrap();
+        payment.adjustments = vec![Adjustment::fixed(AdjustmentKind::Tip, amount)];
+        assert!(matches!(
+            validate_payment(&payment, &group),
+            Err(UseCaseError::InvalidAdjustment)
+        ));
+    }
+
+    #[test]
+    fn validate_payment_itemized_adjustments() {
+        let (mut payment, group) = valid();
+        let currency = payment.amount.currency.clone();
+        payment.split = Split::Itemized(ItemizedSplit {
+            items: vec![PaymentItem::new(
+                Faker.fake(),
+                payment.amount.clone(),
+                1,
+                group.participants.clone(),
+            )],
+            tax: Money::zero(currency.clone()),
+            tip: Money::zero(currency),
+        });
+
+        assert!(validate_payment(&payment, &group).is_ok());
+
+        payment.adjustments = vec![Adjustment::percentage(AdjustmentKind::Tax, 1000)];
+        payment.sync_sole_payer().unwrap();
+        assert!(matches!(
+            validate_payment(&payment, &group),
+            Err(UseCaseError::InvalidAdjustment)
+        ));
+    }
+
+    #[test]
+    fn validate_payment_payers() {
+        let (mut payment, group) = valid();
+        let amount = payment.amount.amount;
+        let currency = payment.amount.currency.clone();
+        payment.amount.amount = amount * 2;
+        payment.payers = vec![
+            Payer::new(
+                group.participants[0].clone(),
+                Money::new(amount, currency.clone()),
+            ),
+            Payer::new(
+                group.participants[0].clone(),
+                Money::new(amount, currency.clone()),
+            ),
+        ];
+
+        assert!(matches!(
+            validate_payment(&payment, &group),
+            Err(UseCaseError::InvalidPayers)
+        ));
+
+        payment.payers.truncate(1);
+        assert!(matches!(
+            validate_payment(&payment, &group),
+            Err(UseCaseError::InvalidPayers)
+        ));
+
+        payment.payers.clear();
+        assert!(matches!(
+            validate_payment(&payment, &group),
+            Err(UseCaseError::InvalidPayers)
+        ));
+    }
+}